@@ -2,3 +2,55 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+/// Lower bound on the decimals used to derive the deposit precision, so pools of
+/// 0-decimal mints still get a usable LP ratio.
+pub const MIN_PRECISION_DECIMALS: u8 = 6;
+/// Upper bound on the decimals used to derive the deposit precision (10^9 fits in a u32).
+pub const MAX_PRECISION_DECIMALS: u8 = 9;
+
+/// Bounds on the StableSwap amplification coefficient.
+pub const MIN_AMP: u64 = 1;
+pub const MAX_AMP: u64 = 1_000_000;
+/// Largest factor the amplification may move by in a single ramp.
+pub const MAX_AMP_CHANGE: u64 = 10;
+/// Shortest allowed amplification ramp, in seconds.
+pub const MIN_RAMP_DURATION: i64 = 86_400;
+
+/// Weighted-curve weights are expressed in basis points of this total.
+pub const TOTAL_WEIGHT_BPS: u16 = 10_000;
+/// Smallest weight a token may carry in a weighted pool (1%).
+pub const MIN_WEIGHT_BPS: u16 = 100;
+
+/// Number of ticks stored in each concentrated-liquidity tick array account.
+pub const TICK_ARRAY_SIZE: usize = 64;
+/// Widest tick spacing a concentrated-liquidity pool may use.
+pub const MAX_TICK_SPACING: u16 = 16_384;
+
+/// Bounds on the number of tokens in a multi-asset pool.
+pub const MIN_MULTI_POOL_TOKENS: usize = 3;
+pub const MAX_MULTI_POOL_TOKENS: usize = 8;
+
+/// Scale of `variable_fee_control`: the variable fee in basis points is
+/// `variable_fee_control · volatility² / VARIABLE_FEE_PRECISION`.
+pub const VARIABLE_FEE_PRECISION: u128 = 100_000_000;
+
+/// Most fee tiers the global `AmmConfig` can hold.
+pub const MAX_FEE_TIERS: usize = 16;
+
+/// Longest delay the timelock on parameter changes may be set to, in seconds.
+pub const MAX_TIMELOCK_SECONDS: i64 = 30 * 86_400;
+
+/// Longest batch-auction window, in slots (about a minute).
+pub const MAX_BATCH_WINDOW_SLOTS: u64 = 150;
+
+/// Most intents a batch takes, all of which must fit in the one `settle_batch` transaction.
+pub const MAX_BATCH_INTENTS: u32 = 20;
+
+/// Slots after a batch closes in which it may be settled; once they pass, its intents are
+/// refunded instead.
+pub const BATCH_SETTLEMENT_SLOTS: u64 = 150;
+
+/// Current `Config` account layout. Version 0 is the original layout without the field;
+/// version 1 still held the swap state that now lives in `PoolState`.
+pub const CONFIG_VERSION: u8 = 2;
@@ -0,0 +1,138 @@
+pub mod stable_swap;
+pub use stable_swap::*;
+
+pub mod weighted;
+pub use weighted::*;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use constant_product_curve::ConstantProduct;
+
+use crate::{
+    constants::{MAX_PRECISION_DECIMALS, MIN_PRECISION_DECIMALS},
+    error::AmmError,
+    states::{Config, CurveType},
+};
+
+/// Precision handed to the LP share math, derived from the most precise of the pool mints.
+pub fn lp_precision(mint_x: &Mint, mint_y: &Mint, mint_lp: &Mint) -> u32 {
+    let decimals = mint_x
+        .decimals
+        .max(mint_y.decimals)
+        .max(mint_lp.decimals)
+        .clamp(MIN_PRECISION_DECIMALS, MAX_PRECISION_DECIMALS);
+    (10u32).pow(decimals as u32)
+}
+
+/// Pool balances as seen by the curve.
+#[derive(Clone, Copy, Debug)]
+pub struct Reserves {
+    pub x: u64,
+    pub y: u64,
+    pub decimals_x: u8,
+    pub decimals_y: u8,
+}
+
+/// Tokens paid out for `amount_in` (net of fees) on the pool curve.
+pub fn swap_amount_out(
+    config: &Config,
+    reserves: &Reserves,
+    x_to_y: bool,
+    amount_in: u64,
+    now: i64,
+) -> Result<u64> {
+    let (reserve_in, reserve_out, decimals_in, decimals_out, weight_in, weight_out) = if x_to_y {
+        (reserves.x, reserves.y, reserves.decimals_x, reserves.decimals_y, config.weight_x, config.weight_y)
+    } else {
+        (reserves.y, reserves.x, reserves.decimals_y, reserves.decimals_x, config.weight_y, config.weight_x)
+    };
+
+    match config.curve_type {
+        CurveType::ConstantProduct => {
+            ConstantProduct::delta_x_from_y_swap_amount(reserve_in, reserve_out, amount_in)
+                .map_err(|e| AmmError::from(e).into())
+        }
+        CurveType::StableSwap => {
+            // Balances are scaled to the decimals of the more precise mint
+            let decimals = decimals_in.max(decimals_out);
+            let rate_in = decimal_rate(decimals - decimals_in)?;
+            let rate_out = decimal_rate(decimals - decimals_out)?;
+            let balances = [scale(reserve_in, rate_in)?, scale(reserve_out, rate_out)?];
+            let amount_out = StableSwap::new(config.current_amp(now)).swap_amount_out(
+                &balances,
+                0,
+                1,
+                scale(amount_in, rate_in)?,
+            )?;
+            u64::try_from(amount_out / rate_out).map_err(|_| AmmError::Overflow.into())
+        }
+        CurveType::Weighted => {
+            WeightedProduct::new(weight_in, weight_out).swap_amount_out(reserve_in, reserve_out, amount_in)
+        }
+        // Concentrated pools trade through `swap_concentrated`
+        CurveType::Concentrated => err!(AmmError::UnsupportedCurve),
+    }
+}
+
+/// Marginal price of X in Y as an `(x, y)` amount pair, quoted by selling a probe of 0.01%
+/// of the X reserve so it holds for every curve.
+pub fn spot_price(config: &Config, reserves: &Reserves, now: i64) -> Result<(u64, u64)> {
+    let probe = (reserves.x / 10_000).max(1);
+    let amount_out = swap_amount_out(config, reserves, true, probe, now)?;
+    Ok((probe, amount_out))
+}
+
+/// Pro-rata `(x, y)` to deposit for `amount` LP tokens.
+pub fn deposit_amounts(
+    config: &Config,
+    reserve_x: u64,
+    reserve_y: u64,
+    supply: u64,
+    amount: u64,
+    precision: u32,
+) -> Result<(u64, u64)> {
+    match config.curve_type {
+        // Liquidity is added pro-rata on every curve; only pricing differs.
+        CurveType::ConstantProduct | CurveType::StableSwap | CurveType::Weighted => {
+            let amounts = ConstantProduct::xy_deposit_amounts_from_l(
+                reserve_x, reserve_y, supply, amount, precision,
+            )
+            .map_err(AmmError::from)?;
+            Ok((amounts.x, amounts.y))
+        }
+        CurveType::Concentrated => err!(AmmError::UnsupportedCurve),
+    }
+}
+
+/// Pro-rata `(x, y)` released by burning `amount` LP tokens.
+pub fn withdraw_amounts(
+    config: &Config,
+    reserve_x: u64,
+    reserve_y: u64,
+    supply: u64,
+    amount: u64,
+    precision: u32,
+) -> Result<(u64, u64)> {
+    match config.curve_type {
+        CurveType::ConstantProduct | CurveType::StableSwap | CurveType::Weighted => {
+            let amounts = ConstantProduct::xy_withdraw_amounts_from_l(
+                reserve_x, reserve_y, supply, amount, precision,
+            )
+            .map_err(AmmError::from)?;
+            Ok((amounts.x, amounts.y))
+        }
+        CurveType::Concentrated => err!(AmmError::UnsupportedCurve),
+    }
+}
+
+fn scale(amount: u64, rate: u128) -> Result<u128> {
+    (amount as u128)
+        .checked_mul(rate)
+        .ok_or(AmmError::Overflow.into())
+}
+
+fn decimal_rate(exponent: u8) -> Result<u128> {
+    10u128
+        .checked_pow(exponent as u32)
+        .ok_or(AmmError::InvalidPrecision.into())
+}
@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, math::U256};
+
+/// Upper bound on Newton iterations for the `D` and `y` solvers.
+const MAX_ITERATIONS: u16 = 255;
+
+/// Curve-style StableSwap invariant over `n` rate-normalised balances:
+///
+/// `A·nⁿ·Σxᵢ + D = A·D·nⁿ + Dⁿ⁺¹ / (nⁿ·Πxᵢ)`
+pub struct StableSwap {
+    pub amp: u64,
+}
+
+impl StableSwap {
+    pub fn new(amp: u64) -> Self {
+        Self { amp }
+    }
+
+    /// Invariant `D` for `balances`, solved with Newton's method.
+    pub fn compute_d(&self, balances: &[u128]) -> Result<u128> {
+        let n = U256::from(balances.len());
+        let sum = balances
+            .iter()
+            .try_fold(U256::zero(), |acc, b| acc.checked_add(U256::from(*b)))
+            .ok_or(AmmError::Overflow)?;
+        if sum.is_zero() {
+            return Ok(0);
+        }
+        require!(balances.iter().all(|b| *b > 0), AmmError::ZeroBalance);
+
+        let ann = self.ann(balances.len())?;
+        let mut d = sum;
+        for _ in 0..MAX_ITERATIONS {
+            // D_P = Dⁿ⁺¹ / (nⁿ·Πxᵢ)
+            let mut d_p = d;
+            for b in balances {
+                d_p = mul_div(d_p, d, U256::from(*b) * n)?;
+            }
+            let d_prev = d;
+            let numerator = ann
+                .checked_mul(sum)
+                .and_then(|v| v.checked_add(d_p.checked_mul(n)?))
+                .and_then(|v| v.checked_mul(d))
+                .ok_or(AmmError::Overflow)?;
+            let denominator = (ann - U256::one())
+                .checked_mul(d)
+                .and_then(|v| v.checked_add(d_p.checked_mul(n + U256::one())?))
+                .ok_or(AmmError::Overflow)?;
+            d = numerator / denominator;
+            if abs_diff(d, d_prev) <= U256::one() {
+                return to_u128(d);
+            }
+        }
+        err!(AmmError::CurveError)
+    }
+
+    /// Balance of coin `j` that keeps the invariant at `d` given every other balance.
+    /// `balances[j]` itself is ignored.
+    pub fn compute_y(&self, balances: &[u128], j: usize, d: u128) -> Result<u128> {
+        require!(j < balances.len(), AmmError::InvalidToken);
+        let n = U256::from(balances.len());
+        let ann = self.ann(balances.len())?;
+        let d = U256::from(d);
+
+        // c = Dⁿ⁺¹ / (nⁿ·Πx'·A·nⁿ), b = Σx' + D / (A·nⁿ), over every coin but `j`
+        let mut c = d;
+        let mut sum = U256::zero();
+        for (k, balance) in balances.iter().enumerate() {
+            if k == j {
+                continue;
+            }
+            let balance = U256::from(*balance);
+            require!(!balance.is_zero(), AmmError::ZeroBalance);
+            sum = sum.checked_add(balance).ok_or(AmmError::Overflow)?;
+            c = mul_div(c, d, balance * n)?;
+        }
+        c = mul_div(c, d, ann.checked_mul(n).ok_or(AmmError::Overflow)?)?;
+        let b = sum + d / ann;
+
+        // y² + (b - D)·y = c
+        let mut y = d;
+        for _ in 0..MAX_ITERATIONS {
+            let y_prev = y;
+            let numerator = y
+                .checked_mul(y)
+                .and_then(|v| v.checked_add(c))
+                .ok_or(AmmError::Overflow)?;
+            let denominator = (y * U256::from(2) + b)
+                .checked_sub(d)
+                .ok_or(AmmError::Underflow)?;
+            y = numerator / denominator;
+            if abs_diff(y, y_prev) <= U256::one() {
+                return to_u128(y);
+            }
+        }
+        err!(AmmError::CurveError)
+    }
+
+    /// Amount of coin `j` paid for `amount_in` of coin `i`.
+    pub fn swap_amount_out(
+        &self,
+        balances: &[u128],
+        i: usize,
+        j: usize,
+        amount_in: u128,
+    ) -> Result<u128> {
+        require!(i != j && i < balances.len(), AmmError::InvalidToken);
+        let d = self.compute_d(balances)?;
+
+        let mut new_balances = balances.to_vec();
+        new_balances[i] = balances[i]
+            .checked_add(amount_in)
+            .ok_or(AmmError::Overflow)?;
+        let y = self.compute_y(&new_balances, j, d)?;
+
+        // One unit is kept in the pool so rounding never favours the trader.
+        Ok(balances[j]
+            .checked_sub(y)
+            .ok_or(AmmError::Underflow)?
+            .saturating_sub(1))
+    }
+
+    /// LP tokens minted for adding `amounts` to `balances` when `supply` LP tokens exist.
+    /// The part of the deposit that unbalances the pool pays the swap fee, which stays in
+    /// the pool.
+    pub fn deposit_lp_amount(
+        &self,
+        balances: &[u128],
+        amounts: &[u128],
+        supply: u128,
+        fee_bps: u16,
+    ) -> Result<u128> {
+        require!(balances.len() == amounts.len(), AmmError::InvalidToken);
+        let new_balances = balances
+            .iter()
+            .zip(amounts)
+            .map(|(b, a)| b.checked_add(*a).ok_or(AmmError::Overflow.into()))
+            .collect::<Result<Vec<u128>>>()?;
+        let d0 = self.compute_d(balances)?;
+        let d1 = self.compute_d(&new_balances)?;
+        require!(d1 > d0, AmmError::InvalidAmount);
+        if supply == 0 {
+            return Ok(d1);
+        }
+
+        let fee_bps = imbalance_fee_bps(balances.len(), fee_bps);
+        let mut charged = new_balances.clone();
+        for (k, balance) in charged.iter_mut().enumerate() {
+            let ideal = mul_div(U256::from(d1), U256::from(balances[k]), U256::from(d0))?;
+            let diff = abs_diff(ideal, U256::from(*balance));
+            let fee = to_u128(diff * U256::from(fee_bps) / U256::from(10_000))?;
+            *balance = balance.checked_sub(fee).ok_or(AmmError::Underflow)?;
+        }
+        let d2 = self.compute_d(&charged)?;
+
+        to_u128(mul_div(
+            U256::from(supply),
+            U256::from(d2.checked_sub(d0).ok_or(AmmError::Underflow)?),
+            U256::from(d0),
+        )?)
+    }
+
+    /// Amount of coin `i` released by burning `lp_amount` of `supply` LP tokens into that
+    /// coin alone, charging the swap fee on the imbalance it creates.
+    pub fn withdraw_one_amount(
+        &self,
+        balances: &[u128],
+        lp_amount: u128,
+        supply: u128,
+        i: usize,
+        fee_bps: u16,
+    ) -> Result<u128> {
+        require!(i < balances.len(), AmmError::InvalidToken);
+        require!(lp_amount < supply, AmmError::InvalidAmount);
+        let d0 = self.compute_d(balances)?;
+        let d1 = d0 - to_u128(mul_div(U256::from(lp_amount), U256::from(d0), U256::from(supply))?)?;
+        let new_y = self.compute_y(balances, i, d1)?;
+
+        let fee_bps = imbalance_fee_bps(balances.len(), fee_bps);
+        let mut reduced = balances.to_vec();
+        for (k, balance) in reduced.iter_mut().enumerate() {
+            let scaled = to_u128(mul_div(U256::from(*balance), U256::from(d1), U256::from(d0))?)?;
+            let expected = if k == i {
+                scaled.checked_sub(new_y).ok_or(AmmError::Underflow)?
+            } else {
+                *balance - scaled
+            };
+            let fee = to_u128(U256::from(expected) * U256::from(fee_bps) / U256::from(10_000))?;
+            *balance = balance.checked_sub(fee).ok_or(AmmError::Underflow)?;
+        }
+        let y = self.compute_y(&reduced, i, d1)?;
+
+        Ok(reduced[i]
+            .checked_sub(y)
+            .ok_or(AmmError::Underflow)?
+            .saturating_sub(1))
+    }
+
+    /// `A·nⁿ`
+    fn ann(&self, n: usize) -> Result<U256> {
+        (0..n)
+            .try_fold(U256::from(self.amp), |acc, _| acc.checked_mul(U256::from(n)))
+            .ok_or(AmmError::Overflow.into())
+    }
+}
+
+/// Fee on the imbalanced part of a deposit or single-coin withdrawal, `fee · n / (4·(n-1))`,
+/// so that it matches a swap of the same size.
+fn imbalance_fee_bps(n: usize, fee_bps: u16) -> u128 {
+    (fee_bps as u128 * n as u128) / (4 * (n as u128 - 1))
+}
+
+fn mul_div(a: U256, b: U256, c: U256) -> Result<U256> {
+    a.checked_mul(b)
+        .and_then(|v| v.checked_div(c))
+        .ok_or(AmmError::Overflow.into())
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn to_u128(value: U256) -> Result<u128> {
+    require!(value <= U256::from(u128::MAX), AmmError::Overflow);
+    Ok(value.as_u128())
+}
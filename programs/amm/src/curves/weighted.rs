@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    math::{div_wad, mul_wad, pow_frac, WAD},
+};
+
+/// Upper bound on the relative error of `pow_frac`, in `WAD`; the power is rounded up by
+/// this much so the pool never pays out more than the invariant allows.
+const MAX_POW_RELATIVE_ERROR: u128 = 10_000;
+
+/// Balancer-style weighted product `Bxʷˣ · Byʷʸ = k`, weights in basis points.
+pub struct WeightedProduct {
+    pub weight_in: u16,
+    pub weight_out: u16,
+}
+
+impl WeightedProduct {
+    pub fn new(weight_in: u16, weight_out: u16) -> Self {
+        Self {
+            weight_in,
+            weight_out,
+        }
+    }
+
+    /// `Bo · (1 - (Bi / (Bi + Ai))^(wi / wo))`
+    pub fn swap_amount_out(&self, reserve_in: u64, reserve_out: u64, amount_in: u64) -> Result<u64> {
+        require!(reserve_in > 0 && reserve_out > 0, AmmError::ZeroBalance);
+        require!(self.weight_in > 0 && self.weight_out > 0, AmmError::InvalidWeights);
+
+        let reserve_in = reserve_in as u128;
+        let base = div_wad(
+            reserve_in,
+            reserve_in
+                .checked_add(amount_in as u128)
+                .ok_or(AmmError::Overflow)?,
+        )?;
+        let exponent = div_wad(self.weight_in as u128, self.weight_out as u128)?;
+
+        let power = pow_frac(base, exponent)?;
+        let power = power
+            .saturating_add(mul_wad(power, MAX_POW_RELATIVE_ERROR)?)
+            .saturating_add(1)
+            .min(WAD);
+
+        let amount_out = mul_wad(reserve_out as u128, WAD - power)?;
+        u64::try_from(amount_out).map_err(|_| AmmError::Overflow.into())
+    }
+}
@@ -39,6 +39,98 @@ pub enum AmmError {
     InsufficientBalance,
     #[msg("Zero balance.")]
     ZeroBalance,
+    #[msg("Operation not supported by this pool curve.")]
+    UnsupportedCurve,
+    #[msg("Invalid amplification coefficient.")]
+    InvalidAmp,
+    #[msg("Invalid amplification ramp time.")]
+    InvalidRampTime,
+    #[msg("Token weights must each be at least 1% and sum to 100%.")]
+    InvalidWeights,
+    #[msg("Invalid tick.")]
+    InvalidTick,
+    #[msg("Invalid sqrt price.")]
+    InvalidSqrtPrice,
+    #[msg("Invalid tick spacing.")]
+    InvalidTickSpacing,
+    #[msg("Tick array does not belong to this pool or does not cover the tick.")]
+    InvalidTickArray,
+    #[msg("A tick array required by the swap was not provided.")]
+    MissingTickArray,
+    #[msg("Reward period must end in the future.")]
+    InvalidRewardPeriod,
+    #[msg("Invalid dynamic fee parameters.")]
+    InvalidDynamicFee,
+    #[msg("Fee is not an enabled fee tier.")]
+    InvalidFeeTier,
+    #[msg("Fee tier limit reached.")]
+    TooManyFeeTiers,
+    #[msg("Pool still has outstanding LP tokens.")]
+    PoolNotEmpty,
+    #[msg("Protocol fees must be collected first.")]
+    ProtocolFeesUncollected,
+    #[msg("Price impact exceeds the pool limit.")]
+    PriceImpactExceeded,
+    #[msg("Pool oracle account was not provided.")]
+    MissingOracle,
+    #[msg("Invalid oracle account.")]
+    InvalidOracle,
+    #[msg("Oracle price is stale.")]
+    StaleOracle,
+    #[msg("Oracle confidence interval is too wide.")]
+    OracleConfidenceTooWide,
+    #[msg("Pool price diverges from the oracle price.")]
+    OracleDeviationExceeded,
+    #[msg("Swap exceeds the per-swap volume limit.")]
+    SwapVolumeLimitExceeded,
+    #[msg("Pool volume limit for this window exceeded.")]
+    PoolVolumeLimitExceeded,
+    #[msg("Wallet volume limit for this window exceeded.")]
+    WalletVolumeLimitExceeded,
+    #[msg("Wallet volume account was not provided.")]
+    MissingWalletVolume,
+    #[msg("Wallet is not on the pool allowlist.")]
+    NotAllowlisted,
+    #[msg("Referral fee exceeds the pool limit.")]
+    InvalidReferralFee,
+    #[msg("Referrer account does not hold the input mint.")]
+    InvalidReferrer,
+    #[msg("Parameter changes must be queued while a timelock is set.")]
+    TimelockActive,
+    #[msg("Queued change is not executable yet.")]
+    TimelockNotElapsed,
+    #[msg("Invalid timelock.")]
+    InvalidTimelock,
+    #[msg("Config account is not in a layout that can be migrated.")]
+    InvalidConfigLayout,
+    #[msg("Invalid order.")]
+    InvalidOrder,
+    #[msg("Invalid DCA parameters.")]
+    InvalidDca,
+    #[msg("DCA cycle is not due yet.")]
+    DcaNotDue,
+    #[msg("Pool only trades through batch auctions.")]
+    BatchAuctionOnly,
+    #[msg("Batch auctions are not enabled on this pool.")]
+    BatchModeDisabled,
+    #[msg("Invalid batch window.")]
+    InvalidBatchWindow,
+    #[msg("Batch is not accepting intents.")]
+    BatchClosed,
+    #[msg("Batch cannot be settled yet.")]
+    BatchNotReady,
+    #[msg("Invalid swap intent.")]
+    InvalidIntent,
+    #[msg("Invalid oracle parameters.")]
+    InvalidOracleParams,
+    #[msg("Batch is full.")]
+    BatchFull,
+    #[msg("Batch settlement window has passed.")]
+    BatchExpired,
+    #[msg("Intent is below the batch minimum.")]
+    IntentTooSmall,
+    #[msg("Trader cannot refer their own swap.")]
+    SelfReferral,
 }
 
 impl From<CurveError> for AmmError {
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+/// Emitted by every constant-product, StableSwap and weighted swap.
+#[event]
+pub struct SwapEvent {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub x_to_y: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Total input fee, including the referral and protocol shares.
+    pub fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+}
+
+/// Emitted when a limit order is filled; the swap itself also emits a `SwapEvent`.
+#[event]
+pub struct OrderFilledEvent {
+    pub config: Pubkey,
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub filler: Pubkey,
+    pub x_to_y: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub tip: u64,
+}
+
+/// Emitted for each executed DCA cycle; the swap itself also emits a `SwapEvent`.
+#[event]
+pub struct DcaCycleEvent {
+    pub config: Pubkey,
+    pub dca: Pubkey,
+    pub owner: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// TWAP-derived floor the cycle had to clear.
+    pub min_amount_out: u64,
+}
+
+/// Emitted when a batch clears; the pool trade for its imbalance also emits a `SwapEvent`.
+#[event]
+pub struct BatchSettledEvent {
+    pub config: Pubkey,
+    pub batch: Pubkey,
+    /// Inputs of the intents filled, and the outputs shared between them.
+    pub amount_x_in: u64,
+    pub amount_y_in: u64,
+    pub amount_x_out: u64,
+    pub amount_y_out: u64,
+    /// Intents refunded because the clearing price missed their `min_amount_out`.
+    pub refunded: u32,
+}
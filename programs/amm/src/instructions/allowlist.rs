@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{AccessMode, AllowEntry, Config, Role};
+
+#[derive(Accounts)]
+pub struct SetAccessMode<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetAccessMode<'info> {
+    pub fn set(&mut self, access_mode: AccessMode) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        self.config.access_mode = access_mode;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddAllowEntry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"allow", config.key().as_ref(), wallet.as_ref()],
+        bump,
+        space = 8 + AllowEntry::INIT_SPACE,
+    )]
+    pub allow_entry: Account<'info, AllowEntry>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddAllowEntry<'info> {
+    pub fn add(&mut self, wallet: Pubkey, bumps: AddAllowEntryBumps) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        self.allow_entry.set_inner(AllowEntry {
+            config: self.config.key(),
+            wallet,
+            bump: bumps.allow_entry,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowEntry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = authority,
+        has_one = config,
+        seeds = [b"allow", config.key().as_ref(), allow_entry.wallet.as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Account<'info, AllowEntry>,
+}
+
+impl<'info> RemoveAllowEntry<'info> {
+    pub fn remove(&mut self) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)
+    }
+}
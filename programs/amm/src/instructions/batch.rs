@@ -0,0 +1,464 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    constants::MAX_BATCH_INTENTS,
+    curves,
+    error::AmmError,
+    events::BatchSettledEvent,
+    instructions::SwapAccounts,
+    states::{reserve_price, AllowEntry, Batch, Config, PoolState, SwapIntent, WalletVolume},
+};
+
+#[derive(Accounts)]
+#[instruction(batch_id: u64)]
+pub struct SubmitIntent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint_x: Box<Account<'info, Mint>>,
+    pub mint_y: Box<Account<'info, Mint>>,
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(has_one = config)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"batch", config.key().as_ref(), batch_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Batch::INIT_SPACE,
+    )]
+    pub batch: Box<Account<'info, Batch>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint_x,
+        associated_token::authority = batch,
+    )]
+    pub escrow_x: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint_y,
+        associated_token::authority = batch,
+    )]
+    pub escrow_y: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"intent", batch.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = 8 + SwapIntent::INIT_SPACE,
+    )]
+    pub intent: Box<Account<'info, SwapIntent>>,
+    #[account(
+        mut,
+        token::authority = owner,
+    )]
+    pub owner_src: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [b"wallet_volume", config.key().as_ref(), owner.key().as_ref()],
+        bump = wallet_volume.bump,
+    )]
+    pub wallet_volume: Option<Account<'info, WalletVolume>>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), owner.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+}
+
+impl<'info> SubmitIntent<'info> {
+    /// Escrows `amount_in` into the batch open at the current slot, opening it if this is its
+    /// first intent. `batch_id` is the current slot divided by the pool's batch window. The
+    /// intent counts toward the owner's wallet volume now, valued at the pool price.
+    pub fn submit(
+        &mut self,
+        batch_id: u64,
+        x_to_y: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+        bumps: SubmitIntentBumps,
+    ) -> Result<()> {
+        let window = self.config.batch_window_slots;
+        require!(window > 0, AmmError::BatchModeDisabled);
+        self.config.check_access(self.allow_entry.as_deref())?;
+        require!(amount_in > 0, AmmError::InvalidAmount);
+
+        let slot = Clock::get()?.slot;
+        require!(batch_id == slot / window, AmmError::BatchClosed);
+        if self.batch.config == Pubkey::default() {
+            self.batch.set_inner(Batch {
+                config: self.config.key(),
+                id: batch_id,
+                end_slot: (batch_id + 1).checked_mul(window).ok_or(AmmError::Overflow)?,
+                escrow_x: self.escrow_x.key(),
+                escrow_y: self.escrow_y.key(),
+                intent_count: 0,
+                amount_x_in: 0,
+                amount_y_in: 0,
+                settled: false,
+                bump: bumps.batch,
+            });
+        }
+        // A window resized since could map this id back onto an earlier batch
+        require!(
+            !self.batch.settled && slot < self.batch.end_slot,
+            AmmError::BatchClosed
+        );
+        require!(self.batch.intent_count < MAX_BATCH_INTENTS, AmmError::BatchFull);
+
+        let volume = match x_to_y {
+            true => amount_in,
+            false => {
+                let state = self.pool_state.load()?;
+                require!(state.reserve_y > 0, AmmError::NoLiquidityInPool);
+                (amount_in as u128 * state.reserve_x as u128 / state.reserve_y as u128) as u64
+            }
+        };
+        require!(volume >= self.config.min_intent_amount, AmmError::IntentTooSmall);
+        self.config
+            .record_wallet_volume(slot, volume, self.wallet_volume.as_deref_mut())?;
+
+        let escrow = if x_to_y { &self.escrow_x } else { &self.escrow_y };
+        require_keys_eq!(self.owner_src.mint, escrow.mint, AmmError::InvalidToken);
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.owner_src.to_account_info(),
+                to: escrow.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        );
+        transfer(ctx, amount_in)?;
+
+        let batch = &mut self.batch;
+        batch.intent_count = batch.intent_count.checked_add(1).ok_or(AmmError::Overflow)?;
+        let total = if x_to_y { &mut batch.amount_x_in } else { &mut batch.amount_y_in };
+        *total = total.checked_add(amount_in).ok_or(AmmError::Overflow)?;
+
+        self.intent.set_inner(SwapIntent {
+            batch: self.batch.key(),
+            owner: self.owner.key(),
+            x_to_y,
+            amount_in,
+            min_amount_out,
+            amount_out: 0,
+            bump: bumps.intent,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SettleBatch<'info> {
+    #[account(
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(
+        mut,
+        has_one = config,
+        has_one = escrow_x,
+        has_one = escrow_y,
+    )]
+    pub batch: Box<Account<'info, Batch>>,
+    #[account(mut)]
+    pub escrow_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub escrow_y: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_y: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: must be the oracle pinned in `config`; parsed as a Pyth price account
+    pub oracle: Option<UncheckedAccount<'info>>,
+}
+
+/// How a batch clears: the inputs filled on each side, the output each side shares pro rata,
+/// and the pool trade `(x_to_y, amount_in, amount_out)` absorbing the imbalance, if any.
+struct Clearing {
+    amount_x_in: u64,
+    amount_y_in: u64,
+    amount_x_out: u64,
+    amount_y_out: u64,
+    net_trade: Option<(bool, u64, u64)>,
+}
+
+impl Clearing {
+    /// The intent's share of its side's output, everyone on a side getting the same price.
+    fn amount_out(&self, intent: &SwapIntent) -> u64 {
+        let (side_in, side_out) = match intent.x_to_y {
+            true => (self.amount_x_in, self.amount_y_out),
+            false => (self.amount_y_in, self.amount_x_out),
+        };
+        (intent.amount_in as u128 * side_out as u128 / side_in as u128) as u64
+    }
+
+    fn fills(&self, intent: &SwapIntent) -> bool {
+        let amount_out = self.amount_out(intent);
+        amount_out > 0 && amount_out >= intent.min_amount_out
+    }
+}
+
+impl<'info> SettleBatch<'info> {
+    /// Clears a closed batch at one price. Anyone may settle it before its deadline, passing
+    /// every intent of the batch, writable and sorted by address. Intents the price misses are
+    /// refunded and the price recomputed without them; the imbalance left trades with the pool.
+    pub fn settle(&mut self, intents: &'info [AccountInfo<'info>]) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(!self.batch.settled, AmmError::BatchClosed);
+        require!(clock.slot >= self.batch.end_slot, AmmError::BatchNotReady);
+        require!(clock.slot < self.batch.settle_deadline(), AmmError::BatchExpired);
+        require!(
+            intents.len() == self.batch.intent_count as usize,
+            AmmError::InvalidIntent
+        );
+
+        // Sorted, so no intent can stand in for another by being passed twice
+        let batch_key = self.batch.key();
+        let mut loaded = Vec::with_capacity(intents.len());
+        for (i, info) in intents.iter().enumerate() {
+            require!(
+                info.is_writable && (i == 0 || intents[i - 1].key < info.key),
+                AmmError::InvalidIntent
+            );
+            let intent = Account::<SwapIntent>::try_from(info)?;
+            require_keys_eq!(intent.batch, batch_key, AmmError::InvalidIntent);
+            loaded.push(intent);
+        }
+
+        // Quoted on a copy of the state, so the dynamic fee only moves with the real trade
+        let mut state = *self.pool_state.load()?;
+        // Checked here as well as by the net trade, since a batch matched within itself never
+        // reaches the pool
+        self.config.check_unlocked(&state)?;
+        let (reserve_x, reserve_y) = (state.reserve_x, state.reserve_y);
+        let fee = self
+            .config
+            .swap_fee(&mut state, clock.slot, reserve_price(reserve_x, reserve_y));
+
+        let mut filled = vec![true; loaded.len()];
+        let clearing = loop {
+            let clearing = self.clear(&loaded, &filled, reserve_x, reserve_y, fee, clock.unix_timestamp)?;
+            let mut refunded = false;
+            for (intent, filled) in loaded.iter().zip(filled.iter_mut()) {
+                if *filled && !clearing.fills(intent) {
+                    *filled = false;
+                    refunded = true;
+                }
+            }
+            if !refunded {
+                break clearing;
+            }
+        };
+
+        for (intent, filled) in loaded.iter_mut().zip(&filled) {
+            intent.amount_out = if *filled { clearing.amount_out(intent) } else { 0 };
+            intent.exit(&crate::ID)?;
+        }
+
+        match clearing.net_trade {
+            Some((x_to_y, amount_in, amount_out)) => {
+                let config = self.config.key();
+                let (id, bump) = (self.batch.id.to_le_bytes(), [self.batch.bump]);
+                let seeds: &[&[u8]] = &[b"batch", config.as_ref(), &id, &bump];
+                let batch_info = self.batch.to_account_info();
+                SwapAccounts {
+                    user: &batch_info,
+                    user_seeds: seeds,
+                    config: &self.config,
+                    pool_state: &self.pool_state,
+                    vault_x: &self.vault_x,
+                    vault_y: &self.vault_y,
+                    user_x: &self.escrow_x,
+                    user_y: &self.escrow_y,
+                    token_program: &self.token_program,
+                    oracle: self.oracle.as_deref(),
+                    wallet_volume: None,
+                    allow_entry: None,
+                    referrer: None,
+                    decimals_x: self.config.decimals_x,
+                    decimals_y: self.config.decimals_y,
+                    min_amount_out: amount_out,
+                }
+                .execute(x_to_y, amount_in, 10_000, 0)?;
+            }
+            // Matched within the batch at the pool price, which must still sit by the oracle
+            None => {
+                let reserves = curves::Reserves {
+                    x: reserve_x,
+                    y: reserve_y,
+                    decimals_x: self.config.decimals_x,
+                    decimals_y: self.config.decimals_y,
+                };
+                self.config
+                    .check_oracle_price(self.oracle.as_deref(), &reserves, clock.unix_timestamp)?;
+            }
+        }
+
+        self.batch.settled = true;
+        emit!(BatchSettledEvent {
+            config: self.config.key(),
+            batch: batch_key,
+            amount_x_in: clearing.amount_x_in,
+            amount_y_in: clearing.amount_y_in,
+            amount_x_out: clearing.amount_x_out,
+            amount_y_out: clearing.amount_y_out,
+            refunded: filled.iter().filter(|filled| !**filled).count() as u32,
+        });
+        Ok(())
+    }
+
+    /// Clears the `filled` intents against each other, quoting the imbalance against the pool
+    /// exactly as the swap will price it.
+    fn clear(
+        &self,
+        intents: &[Account<'info, SwapIntent>],
+        filled: &[bool],
+        reserve_x: u64,
+        reserve_y: u64,
+        fee: u16,
+        now: i64,
+    ) -> Result<Clearing> {
+        let (mut amount_x_in, mut amount_y_in) = (0u64, 0u64);
+        for (intent, _) in intents.iter().zip(filled).filter(|(_, filled)| **filled) {
+            // Bounded by the batch totals, which were checked as intents came in
+            match intent.x_to_y {
+                true => amount_x_in += intent.amount_in,
+                false => amount_y_in += intent.amount_in,
+            }
+        }
+
+        let mut clearing = Clearing {
+            amount_x_in,
+            amount_y_in,
+            amount_x_out: amount_x_in,
+            amount_y_out: amount_y_in,
+            net_trade: None,
+        };
+        let Some((x_to_y, amount_in)) =
+            Batch::net_trade(amount_x_in, amount_y_in, reserve_x, reserve_y, fee)?
+        else {
+            return Ok(clearing);
+        };
+
+        let amount_in_with_fee = (amount_in as u128 * (10_000 - fee as u128) / 10_000) as u64;
+        let reserves = curves::Reserves {
+            x: reserve_x,
+            y: reserve_y,
+            decimals_x: self.config.decimals_x,
+            decimals_y: self.config.decimals_y,
+        };
+        let amount_out = match amount_in_with_fee {
+            0 => 0,
+            _ => curves::swap_amount_out(&self.config, &reserves, x_to_y, amount_in_with_fee, now)?,
+        };
+        // An imbalance too small for the pool to pay anything for matches within the batch
+        if amount_out == 0 {
+            return Ok(clearing);
+        }
+
+        clearing.net_trade = Some((x_to_y, amount_in, amount_out));
+        if x_to_y {
+            clearing.amount_x_out -= amount_in;
+            clearing.amount_y_out = amount_y_in.checked_add(amount_out).ok_or(AmmError::Overflow)?;
+        } else {
+            clearing.amount_y_out -= amount_in;
+            clearing.amount_x_out = amount_x_in.checked_add(amount_out).ok_or(AmmError::Overflow)?;
+        }
+        Ok(clearing)
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimIntent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = escrow_x,
+        has_one = escrow_y,
+    )]
+    pub batch: Box<Account<'info, Batch>>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = batch,
+        has_one = owner,
+    )]
+    pub intent: Box<Account<'info, SwapIntent>>,
+    #[account(mut)]
+    pub escrow_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub escrow_y: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = escrow_x.mint,
+        token::authority = owner,
+    )]
+    pub owner_x: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = escrow_y.mint,
+        token::authority = owner,
+    )]
+    pub owner_y: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimIntent<'info> {
+    /// Pays out the intent's share of a settled batch, or refunds its input if it was left out
+    /// or the batch went unsettled past its deadline, and closes it.
+    pub fn claim(&mut self) -> Result<()> {
+        // Whatever kept the batch from settling in time, a locked pool, a tripped breaker or
+        // the oracle, its deposits come back
+        require!(
+            self.batch.settled || Clock::get()?.slot >= self.batch.settle_deadline(),
+            AmmError::BatchNotReady
+        );
+
+        let intent = &self.intent;
+        let (escrow, owner_dst, amount) = match (intent.amount_out > 0, intent.x_to_y) {
+            (true, true) => (&self.escrow_y, &self.owner_y, intent.amount_out),
+            (true, false) => (&self.escrow_x, &self.owner_x, intent.amount_out),
+            (false, true) => (&self.escrow_x, &self.owner_x, intent.amount_in),
+            (false, false) => (&self.escrow_y, &self.owner_y, intent.amount_in),
+        };
+
+        let config = self.batch.config;
+        let (id, bump) = (self.batch.id.to_le_bytes(), [self.batch.bump]);
+        let seeds: &[&[u8]] = &[b"batch", config.as_ref(), &id, &bump];
+        let signer_seeds = &[seeds];
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: escrow.to_account_info(),
+                to: owner_dst.to_account_info(),
+                authority: self.batch.to_account_info(),
+            },
+            signer_seeds,
+        );
+        transfer(ctx, amount)
+    }
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    states::{Config, CurveType, PoolIndex, PoolState, Role},
+};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Receives the rent of the closed accounts.
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    pub mint_x: Box<Account<'info, Mint>>,
+    pub mint_y: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        close = recipient,
+        has_one = mint_x,
+        has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        has_one = mint_lp,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+        constraint = config.curve_type != CurveType::Concentrated @ AmmError::UnsupportedCurve,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        close = recipient,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(constraint = mint_lp.supply == 0 @ AmmError::PoolNotEmpty)]
+    pub mint_lp: Box<Account<'info, Mint>>,
+    #[account(mut)]
+    pub vault_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_y: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint_x,
+        associated_token::authority = authority,
+    )]
+    pub authority_x: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint_y,
+        associated_token::authority = authority,
+    )]
+    pub authority_y: Box<Account<'info, TokenAccount>>,
+    /// CHECK: shrunk by `PoolIndex::unregister`
+    #[account(mut, seeds = [b"pool_index", mint_x.key().as_ref()], bump)]
+    pub pool_index_x: UncheckedAccount<'info>,
+    /// CHECK: shrunk by `PoolIndex::unregister`
+    #[account(mut, seeds = [b"pool_index", mint_y.key().as_ref()], bump)]
+    pub pool_index_y: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClosePool<'info> {
+    /// Sweeps leftover vault dust to the authority, closes both vaults, the config and its
+    /// state, and drops the pool from the mint registries. SPL Token mints cannot be closed,
+    /// so `mint_lp` stays behind with zero supply.
+    pub fn close(&mut self) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        {
+            let state = self.pool_state.load()?;
+            require!(
+                state.protocol_fees_x == 0 && state.protocol_fees_y == 0,
+                AmmError::ProtocolFeesUncollected
+            );
+        }
+
+        self.sweep_and_close(true)?;
+        self.sweep_and_close(false)?;
+
+        let recipient = self.recipient.to_account_info();
+        PoolIndex::unregister(&self.pool_index_x, self.config.key(), &recipient)?;
+        PoolIndex::unregister(&self.pool_index_y, self.config.key(), &recipient)
+    }
+
+    pub fn sweep_and_close(&self, is_x: bool) -> Result<()> {
+        let (vault, to) = match is_x {
+            true => (&self.vault_x, self.authority_x.to_account_info()),
+            false => (&self.vault_y, self.authority_y.to_account_info()),
+        };
+
+        let seeds = &[&b"config"[..], &self.config.seed.to_le_bytes(), &[self.config.config_bump]];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_program = self.token_program.to_account_info();
+
+        if vault.amount > 0 {
+            let cpi_account = Transfer {
+                from: vault.to_account_info(),
+                to,
+                authority: self.config.to_account_info(),
+            };
+            let ctx = CpiContext::new_with_signer(cpi_program.clone(), cpi_account, signer_seeds);
+            transfer(ctx, vault.amount)?;
+        }
+
+        let cpi_account = CloseAccount {
+            account: vault.to_account_info(),
+            destination: self.recipient.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(cpi_program, cpi_account, signer_seeds);
+        close_account(ctx)
+    }
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    states::{AmmConfig, Config, PoolState},
+};
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"amm_config"],
+        bump = amm_config.bump,
+        has_one = admin @ AmmError::InvalidAuthority,
+        has_one = fee_recipient,
+    )]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+    pub fee_recipient: SystemAccount<'info>,
+    pub mint_x: Box<Account<'info, Mint>>,
+    pub mint_y: Box<Account<'info, Mint>>,
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut)]
+    pub vault_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_y: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint_x,
+        associated_token::authority = fee_recipient,
+    )]
+    pub recipient_x: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint_y,
+        associated_token::authority = fee_recipient,
+    )]
+    pub recipient_y: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CollectProtocolFees<'info> {
+    /// Sends the protocol fees accrued by the pool to the fee recipient.
+    pub fn collect(&mut self) -> Result<()> {
+        let (fees_x, fees_y) = {
+            let mut state = self.pool_state.load_mut()?;
+            let fees = (state.protocol_fees_x, state.protocol_fees_y);
+            state.protocol_fees_x = 0;
+            state.protocol_fees_y = 0;
+            fees
+        };
+
+        if fees_x > 0 {
+            self.transfer_out(true, fees_x)?;
+        }
+        if fees_y > 0 {
+            self.transfer_out(false, fees_y)?;
+        }
+        Ok(())
+    }
+
+    fn transfer_out(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to) = match is_x {
+            true => (self.vault_x.to_account_info(), self.recipient_x.to_account_info()),
+            false => (self.vault_y.to_account_info(), self.recipient_y.to_account_info()),
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_account = Transfer {
+            from,
+            to,
+            authority: self.config.to_account_info(),
+        };
+        let seeds = &[&b"config"[..], &self.config.seed.to_le_bytes(), &[self.config.config_bump]];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(cpi_program, cpi_account, signer_seeds);
+        transfer(ctx, amount)
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    states::{Config, RewardPool, Role},
+};
+
+#[derive(Accounts)]
+pub struct CreateRewardPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        has_one = mint_lp,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    pub mint_lp: Box<Account<'info, Mint>>,
+    pub reward_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_pool", config.key().as_ref(), reward_mint.key().as_ref()],
+        bump,
+        space = 8 + RewardPool::INIT_SPACE,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint_lp,
+        associated_token::authority = reward_pool,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_pool,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = authority,
+    )]
+    pub authority_reward: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateRewardPool<'info> {
+    /// Opens a reward pool emitting `emission_per_second` until `end_ts`, funded up front by
+    /// the pool authority with everything it will pay out.
+    pub fn create(
+        &mut self,
+        emission_per_second: u64,
+        end_ts: i64,
+        bumps: CreateRewardPoolBumps,
+    ) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(emission_per_second > 0, AmmError::InvalidAmount);
+        require!(end_ts > now, AmmError::InvalidRewardPeriod);
+
+        let funding = emission_per_second
+            .checked_mul((end_ts - now) as u64)
+            .ok_or(AmmError::Overflow)?;
+        require!(
+            self.authority_reward.amount >= funding,
+            AmmError::InsufficientBalance
+        );
+
+        self.reward_pool.set_inner(RewardPool {
+            config: self.config.key(),
+            reward_mint: self.reward_mint.key(),
+            emission_per_second,
+            end_ts,
+            last_update_ts: now,
+            reward_per_share: 0,
+            total_staked: 0,
+            undistributed: 0,
+            bump: bumps.reward_pool,
+        });
+
+        let cpi_accounts = Transfer {
+            from: self.authority_reward.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, funding)
+    }
+}
@@ -0,0 +1,330 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    events::DcaCycleEvent,
+    instructions::{orders::close_escrow, SwapAccounts},
+    states::{reserve_price, AllowEntry, Config, CurveType, DcaVault, PoolState, WalletVolume},
+};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateDca<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint_in: Box<Account<'info, Mint>>,
+    pub mint_out: Box<Account<'info, Mint>>,
+    // The TWAP is of the reserve ratio, which is the price only on constant-product pools
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+        constraint = config.curve_type == CurveType::ConstantProduct @ AmmError::UnsupportedCurve,
+        constraint = (mint_in.key(), mint_out.key()) == (config.mint_x, config.mint_y) ||
+            (mint_in.key(), mint_out.key()) == (config.mint_y, config.mint_x)
+            @ AmmError::InvalidToken,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(has_one = config)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"dca", config.key().as_ref(), owner.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + DcaVault::INIT_SPACE,
+    )]
+    pub dca: Box<Account<'info, DcaVault>>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint_in,
+        associated_token::authority = dca,
+    )]
+    pub input_escrow: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint_out,
+        associated_token::authority = dca,
+    )]
+    pub output_escrow: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = owner,
+    )]
+    pub owner_src: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), owner.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+}
+
+impl<'info> CreateDca<'info> {
+    /// Escrows `amount` of `mint_in`, to be sold `amount_per_cycle` at a time every `interval`
+    /// seconds, the first cycle falling due one interval from now.
+    pub fn create(
+        &mut self,
+        id: u64,
+        amount: u64,
+        amount_per_cycle: u64,
+        interval: i64,
+        max_slippage_bps: u16,
+        bumps: CreateDcaBumps,
+    ) -> Result<()> {
+        self.config.check_access(self.allow_entry.as_deref())?;
+        require!(
+            amount > 0 && amount_per_cycle > 0 && interval > 0 && max_slippage_bps <= 10_000,
+            AmmError::InvalidDca
+        );
+
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.owner_src.to_account_info(),
+                to: self.input_escrow.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        );
+        transfer(ctx, amount)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.dca.set_inner(DcaVault {
+            config: self.config.key(),
+            owner: self.owner.key(),
+            input_escrow: self.input_escrow.key(),
+            output_escrow: self.output_escrow.key(),
+            id,
+            x_to_y: self.mint_in.key() == self.config.mint_x,
+            amount_per_cycle,
+            interval,
+            next_cycle_ts: now.checked_add(interval).ok_or(AmmError::Overflow)?,
+            max_slippage_bps,
+            price_cumulative: self.pool_state.load()?.price_cumulative_at(now),
+            price_cumulative_ts: now,
+            bump: bumps.dca,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDca<'info> {
+    #[account(
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(
+        mut,
+        has_one = config,
+        has_one = input_escrow,
+        has_one = output_escrow,
+    )]
+    pub dca: Box<Account<'info, DcaVault>>,
+    #[account(mut)]
+    pub input_escrow: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub output_escrow: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_y: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: must be the oracle pinned in `config`; parsed as a Pyth price account
+    pub oracle: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"wallet_volume", config.key().as_ref(), dca.owner.as_ref()],
+        bump = wallet_volume.bump,
+    )]
+    pub wallet_volume: Option<Account<'info, WalletVolume>>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), dca.owner.as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+}
+
+impl<'info> ExecuteDca<'info> {
+    /// Runs a due cycle. Anyone may crank it; the output, net of the pool fee, must be within
+    /// the vault's slippage of the pool's TWAP since the previous cycle, so a cycle cannot be
+    /// sold into a price moved just for it.
+    pub fn execute(&mut self) -> Result<()> {
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        require!(now >= self.dca.next_cycle_ts, AmmError::DcaNotDue);
+        let amount_in = self.dca.amount_per_cycle.min(self.input_escrow.amount);
+        require!(amount_in > 0, AmmError::InvalidAmount);
+
+        // The fee the swap will charge, read from a copy so the dynamic-fee state only moves
+        // with the swap itself
+        let mut state = *self.pool_state.load()?;
+        let price = reserve_price(state.reserve_x, state.reserve_y);
+        let fee = self.config.swap_fee(&mut state, clock.slot, price);
+        let price_cumulative = state.price_cumulative_at(now);
+        let min_amount_out = self.dca.min_amount_out(amount_in, fee, price_cumulative, now)?;
+        self.dca.next_cycle_ts = now.checked_add(self.dca.interval).ok_or(AmmError::Overflow)?;
+
+        let (config, owner) = (self.config.key(), self.dca.owner);
+        let (id, bump) = (self.dca.id.to_le_bytes(), [self.dca.bump]);
+        let seeds: &[&[u8]] = &[b"dca", config.as_ref(), owner.as_ref(), &id, &bump];
+        let x_to_y = self.dca.x_to_y;
+        let (user_x, user_y) = match x_to_y {
+            true => (&self.input_escrow, &self.output_escrow),
+            false => (&self.output_escrow, &self.input_escrow),
+        };
+        let dca_info = self.dca.to_account_info();
+        let amount_out = SwapAccounts {
+            user: &dca_info,
+            user_seeds: seeds,
+            config: &self.config,
+            pool_state: &self.pool_state,
+            vault_x: &self.vault_x,
+            vault_y: &self.vault_y,
+            user_x,
+            user_y,
+            token_program: &self.token_program,
+            oracle: self.oracle.as_deref(),
+            wallet_volume: self.wallet_volume.as_deref_mut(),
+            allow_entry: self.allow_entry.as_deref(),
+            referrer: None,
+            decimals_x: self.config.decimals_x,
+            decimals_y: self.config.decimals_y,
+            min_amount_out,
+        }
+        .swap(x_to_y, amount_in, 10_000, 0)?;
+
+        emit!(DcaCycleEvent {
+            config,
+            dca: self.dca.key(),
+            owner,
+            amount_in,
+            amount_out,
+            min_amount_out,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawDca<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner,
+        has_one = output_escrow,
+    )]
+    pub dca: Account<'info, DcaVault>,
+    #[account(mut)]
+    pub output_escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = output_escrow.mint,
+        token::authority = owner,
+    )]
+    pub owner_dst: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawDca<'info> {
+    /// Pays the output bought so far out to the owner; the schedule keeps running.
+    pub fn withdraw(&mut self) -> Result<()> {
+        let (config, owner) = (self.dca.config, self.owner.key());
+        let (id, bump) = (self.dca.id.to_le_bytes(), [self.dca.bump]);
+        let seeds: &[&[u8]] = &[b"dca", config.as_ref(), owner.as_ref(), &id, &bump];
+
+        pay_out(
+            &self.token_program,
+            &self.output_escrow,
+            &self.owner_dst,
+            &self.dca.to_account_info(),
+            seeds,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseDca<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = input_escrow,
+        has_one = output_escrow,
+    )]
+    pub dca: Box<Account<'info, DcaVault>>,
+    #[account(mut)]
+    pub input_escrow: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub output_escrow: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = input_escrow.mint,
+        token::authority = owner,
+    )]
+    pub owner_in: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = output_escrow.mint,
+        token::authority = owner,
+    )]
+    pub owner_out: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CloseDca<'info> {
+    /// Stops the schedule, refunding the unsold input and paying out the output bought.
+    pub fn close(&mut self) -> Result<()> {
+        let (config, owner) = (self.dca.config, self.owner.key());
+        let (id, bump) = (self.dca.id.to_le_bytes(), [self.dca.bump]);
+        let seeds: &[&[u8]] = &[b"dca", config.as_ref(), owner.as_ref(), &id, &bump];
+        let dca_info = self.dca.to_account_info();
+        let owner_info = self.owner.to_account_info();
+
+        pay_out(&self.token_program, &self.input_escrow, &self.owner_in, &dca_info, seeds)?;
+        pay_out(&self.token_program, &self.output_escrow, &self.owner_out, &dca_info, seeds)?;
+        close_escrow(&self.token_program, &self.input_escrow, &owner_info, &dca_info, seeds)?;
+        close_escrow(&self.token_program, &self.output_escrow, &owner_info, &dca_info, seeds)
+    }
+}
+
+/// Moves an escrow's whole balance to `to`, signed for by the DCA vault.
+fn pay_out<'info>(
+    token_program: &Program<'info, Token>,
+    escrow: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    dca: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    if escrow.amount == 0 {
+        return Ok(());
+    }
+    let signer_seeds = &[seeds];
+    let ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        Transfer {
+            from: escrow.to_account_info(),
+            to: to.to_account_info(),
+            authority: dca.clone(),
+        },
+        signer_seeds,
+    );
+    transfer(ctx, escrow.amount)
+}
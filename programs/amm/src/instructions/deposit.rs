@@ -3,9 +3,8 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token::{ mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer },
 };
-use constant_product_curve::ConstantProduct;
 
-use crate::{ error::AmmError, states::Config };
+use crate::{ curves, error::AmmError, states::{ AllowEntry, Config, PoolState } };
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
@@ -13,31 +12,27 @@ pub struct Deposit<'info> {
     pub user: Signer<'info>,
     pub mint_x: Account<'info, Mint>,
     pub mint_y: Account<'info, Mint>,
-    #[account(
-        mut,
-        seeds = [b"lp",config.key().as_ref()],
-        bump,
-    )]
+    #[account(mut)]
     pub mint_lp: Account<'info, Mint>,
     #[account(
         has_one = mint_x,
         has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        has_one = mint_lp,
         seeds = [b"config", config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump
     )]
     pub config: Account<'info, Config>,
     #[account(
         mut,
-        associated_token::mint = mint_x,
-        associated_token::authority = config,
+        has_one = config,
     )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut)]
     pub vault_x: Account<'info, TokenAccount>,
 
-    #[account(
-        mut,
-        associated_token::mint = mint_y,
-        associated_token::authority = config,
-    )]
+    #[account(mut)]
     pub vault_y: Account<'info, TokenAccount>,
 
     #[account(
@@ -64,31 +59,42 @@ pub struct Deposit<'info> {
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), user.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
 }
 
 impl<'info> Deposit<'info> {
     pub fn deposit(&mut self, amount: u64, max_x: u64, max_y: u64) -> Result<()> {
-        require!(self.config.locked == false, AmmError::PoolLocked);
+        let mut state = self.pool_state.load_mut()?;
+        self.config.check_unlocked(&state)?;
         require!(amount > 0, AmmError::InvalidAmount);
+        self.config.check_access(self.allow_entry.as_deref())?;
 
+        let (reserve_x, reserve_y) = (state.reserve_x, state.reserve_y);
         let (x, y) = match
             self.mint_lp.supply == 0 &&
-            self.vault_x.amount == 0 &&
-            self.vault_y.amount == 0
+            reserve_x == 0 &&
+            reserve_y == 0
         {
             true => (max_x, max_y),
-            false => {
-                let amount = ConstantProduct::xy_deposit_amounts_from_l(
-                    self.vault_x.amount,
-                    self.vault_y.amount,
+            false =>
+                curves::deposit_amounts(
+                    &self.config,
+                    reserve_x,
+                    reserve_y,
                     self.mint_lp.supply,
                     amount,
-                    6
-                ).unwrap();
-                (amount.x, amount.y)
-            }
+                    curves::lp_precision(&self.mint_x, &self.mint_y, &self.mint_lp)
+                )?,
         };
         require!(x <= max_x && y <= max_y, AmmError::SlippageExceeded);
+        state.update_price_cumulative(Clock::get()?.unix_timestamp);
+        state.reserve_x = reserve_x.checked_add(x).ok_or(AmmError::Overflow)?;
+        state.reserve_y = reserve_y.checked_add(y).ok_or(AmmError::Overflow)?;
+        drop(state);
         self.deposit_tokens(true, x)?;
         self.deposit_tokens(false, y)?;
         self.mint_lp_token(amount)
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{mint_to, Mint, MintTo, Token, TokenAccount},
+};
+
+use crate::{curves::StableSwap, error::AmmError, states::MultiPool};
+
+/// Remaining accounts: the pool vaults followed by the user's token accounts, both in pool
+/// mint order.
+#[derive(Accounts)]
+pub struct DepositMulti<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"multi_pool", pool.seed.to_le_bytes().as_ref()],
+        bump = pool.pool_bump,
+    )]
+    pub pool: Account<'info, MultiPool>,
+    #[account(
+        mut,
+        seeds = [b"multi_lp", pool.key().as_ref()],
+        bump = pool.lp_bump,
+    )]
+    pub mint_lp: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+    )]
+    pub user_lp: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositMulti<'info> {
+    /// Deposits any mix of the pool tokens, including a single one; the first deposit must
+    /// include all of them.
+    pub fn deposit(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        amounts: Vec<u64>,
+        min_lp: u64,
+    ) -> Result<()> {
+        require!(!self.pool.locked, AmmError::PoolLocked);
+        require!(amounts.len() == self.pool.len(), AmmError::InvalidToken);
+        require!(amounts.iter().any(|a| *a > 0), AmmError::InvalidAmount);
+
+        let (vaults, rest) = self.pool.load_vaults(&self.pool.key(), accounts)?;
+        require!(rest.len() == self.pool.len(), AmmError::InvalidToken);
+        let user_accounts = rest
+            .iter()
+            .enumerate()
+            .map(|(i, info)| self.pool.load_user_account(i, info))
+            .collect::<Result<Vec<_>>>()?;
+
+        let supply = self.mint_lp.supply;
+        if supply == 0 {
+            require!(amounts.iter().all(|a| *a > 0), AmmError::ZeroBalance);
+        }
+        let balances = self.pool.balances()?;
+        let scaled_amounts = amounts
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| self.pool.scale(i, *amount))
+            .collect::<Result<Vec<_>>>()?;
+
+        let lp = StableSwap::new(self.pool.amp).deposit_lp_amount(
+            &balances,
+            &scaled_amounts,
+            supply as u128,
+            self.pool.fee,
+        )?;
+        let lp = if supply == 0 {
+            self.pool.invariant_to_lp(lp, self.mint_lp.decimals)?
+        } else {
+            u64::try_from(lp).map_err(|_| AmmError::Overflow)?
+        };
+        require!(lp > 0, AmmError::InvalidAmount);
+        require!(lp >= min_lp, AmmError::SlippageExceeded);
+
+        for (i, ((amount, from), vault)) in amounts.iter().zip(&user_accounts).zip(&vaults).enumerate() {
+            self.pool.reserves[i] = self.pool.reserves[i]
+                .checked_add(*amount)
+                .ok_or(AmmError::Overflow)?;
+            MultiPool::transfer_in(
+                self.user.to_account_info(),
+                self.token_program.to_account_info(),
+                from,
+                vault,
+                *amount,
+            )?;
+        }
+        self.mint_lp_tokens(lp)
+    }
+
+    fn mint_lp_tokens(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = MintTo {
+            mint: self.mint_lp.to_account_info(),
+            to: self.user_lp.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+        let seeds = &[
+            &b"multi_pool"[..],
+            &self.pool.seed.to_le_bytes(),
+            &[self.pool.pool_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        mint_to(cpi_ctx, amount)
+    }
+}
@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    states::{RewardPool, StakeAccount},
+};
+
+#[derive(Accounts)]
+pub struct Farm<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        seeds = [b"lp", reward_pool.config.as_ref()],
+        bump,
+    )]
+    pub mint_lp: Box<Account<'info, Mint>>,
+    #[account(address = reward_pool.reward_mint)]
+    pub reward_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.config.as_ref(), reward_mint.key().as_ref()],
+        bump = reward_pool.bump,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"stake", reward_pool.key().as_ref(), user.key().as_ref()],
+        bump,
+        space = 8 + StakeAccount::INIT_SPACE,
+    )]
+    pub stake_account: Box<Account<'info, StakeAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = reward_pool,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_pool,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+    )]
+    pub user_lp: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+    )]
+    pub user_reward: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Farm<'info> {
+    pub fn stake(&mut self, amount: u64, bumps: FarmBumps) -> Result<()> {
+        require!(amount > 0, AmmError::InvalidAmount);
+        require!(self.user_lp.amount >= amount, AmmError::InsufficientBalance);
+        self.settle(bumps)?;
+
+        self.stake_account.amount = self
+            .stake_account
+            .amount
+            .checked_add(amount)
+            .ok_or(AmmError::Overflow)?;
+        self.reward_pool.total_staked = self
+            .reward_pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(AmmError::Overflow)?;
+
+        let cpi_accounts = Transfer {
+            from: self.user_lp.to_account_info(),
+            to: self.stake_vault.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, amount)
+    }
+
+    pub fn unstake(&mut self, amount: u64, bumps: FarmBumps) -> Result<()> {
+        require!(amount > 0, AmmError::InvalidAmount);
+        require!(
+            self.stake_account.amount >= amount,
+            AmmError::InsufficientBalance
+        );
+        self.settle(bumps)?;
+
+        self.stake_account.amount -= amount;
+        self.reward_pool.total_staked -= amount;
+
+        self.pay_out(&self.stake_vault, &self.user_lp, amount)
+    }
+
+    pub fn claim(&mut self, bumps: FarmBumps) -> Result<()> {
+        self.settle(bumps)?;
+
+        let amount = self.stake_account.rewards_owed;
+        self.stake_account.rewards_owed = 0;
+        self.pay_out(&self.reward_vault, &self.user_reward, amount)
+    }
+
+    /// Brings the pool accumulator up to date and credits the stake with what it earned.
+    fn settle(&mut self, bumps: FarmBumps) -> Result<()> {
+        if self.stake_account.owner == Pubkey::default() {
+            self.stake_account.owner = self.user.key();
+            self.stake_account.reward_pool = self.reward_pool.key();
+            self.stake_account.bump = bumps.stake_account;
+        }
+
+        self.reward_pool.update(Clock::get()?.unix_timestamp)?;
+        self.stake_account.settle(&self.reward_pool)
+    }
+
+    fn pay_out(
+        &self,
+        from: &Account<'info, TokenAccount>,
+        to: &Account<'info, TokenAccount>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let cpi_accounts = Transfer {
+            from: from.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.reward_pool.to_account_info(),
+        };
+        let reward_mint = self.reward_mint.key();
+        let seeds = &[
+            &b"reward_pool"[..],
+            self.reward_pool.config.as_ref(),
+            reward_mint.as_ref(),
+            &[self.reward_pool.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Config, VolumeWindow, WalletVolume};
+
+#[derive(Accounts)]
+pub struct InitWalletVolume<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"wallet_volume", config.key().as_ref(), user.key().as_ref()],
+        bump,
+        space = 8 + WalletVolume::INIT_SPACE,
+    )]
+    pub wallet_volume: Account<'info, WalletVolume>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitWalletVolume<'info> {
+    pub fn init(&mut self, bumps: InitWalletVolumeBumps) -> Result<()> {
+        self.wallet_volume.set_inner(WalletVolume {
+            config: self.config.key(),
+            owner: self.user.key(),
+            window: VolumeWindow::default(),
+            bump: bumps.wallet_volume,
+        });
+        Ok(())
+    }
+}
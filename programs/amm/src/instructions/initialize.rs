@@ -1,13 +1,23 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::states::Config;
+use crate::{
+    constants::{
+        CONFIG_VERSION, MAX_AMP, MAX_PRECISION_DECIMALS, MIN_AMP, MIN_WEIGHT_BPS, TOTAL_WEIGHT_BPS,
+    },
+    error::AmmError,
+    states::{
+        AccessMode, AmmConfig, Config, CurveParams, CurveType, DynamicFeeParams, OracleParams, PoolIndex,
+        PoolState, VolumeLimits,
+    },
+};
 
 #[derive(Accounts)]
-#[instruction(seed:u64)]
+#[instruction(seed: u64, fee: u16, authority: Option<Pubkey>, lp_decimals: u8)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub initializer: Signer<'info>,
@@ -18,7 +28,7 @@ pub struct Initialize<'info> {
         payer= initializer,
         seeds = [b"lp",config.key().as_ref()],
         bump,
-        mint::decimals = 6,
+        mint::decimals = lp_decimals,
         mint::authority = config,
     )]
     pub mint_lp: Account<'info, Mint>,
@@ -30,6 +40,28 @@ pub struct Initialize<'info> {
         space = 8+Config::INIT_SPACE,
     )]
     pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"pool_state", config.key().as_ref()],
+        bump,
+        space = PoolState::SPACE,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(
+        seeds = [b"amm_config"],
+        bump = amm_config.bump,
+        has_one = fee_recipient,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    #[account(mut)]
+    pub fee_recipient: SystemAccount<'info>,
+    /// CHECK: created or grown by `PoolIndex::register`
+    #[account(mut, seeds = [b"pool_index", mint_x.key().as_ref()], bump)]
+    pub pool_index_x: UncheckedAccount<'info>,
+    /// CHECK: created or grown by `PoolIndex::register`
+    #[account(mut, seeds = [b"pool_index", mint_y.key().as_ref()], bump)]
+    pub pool_index_y: UncheckedAccount<'info>,
     #[account(
         init,
         payer = initializer,
@@ -57,9 +89,32 @@ impl<'info> Initialize<'info> {
         seed: u64,
         fee: u16,
         authority: Option<Pubkey>,
+        lp_decimals: u8,
+        curve: CurveParams,
         bumps: InitializeBumps,
     ) -> Result<()> {
+        require!(lp_decimals <= MAX_PRECISION_DECIMALS, AmmError::InvalidPrecision);
+        require!(self.amm_config.is_enabled_tier(fee), AmmError::InvalidFeeTier);
+        let (amp, weight_x, weight_y) = match curve.curve_type {
+            // Concentrated pools are priced by their `ConcentratedPool` account
+            CurveType::ConstantProduct | CurveType::Concentrated => (0, 0, 0),
+            CurveType::StableSwap => {
+                require!((MIN_AMP..=MAX_AMP).contains(&curve.amp), AmmError::InvalidAmp);
+                (curve.amp, 0, 0)
+            }
+            CurveType::Weighted => {
+                require!(
+                    curve.weight_x >= MIN_WEIGHT_BPS &&
+                        curve.weight_y >= MIN_WEIGHT_BPS &&
+                        curve.weight_x.checked_add(curve.weight_y) == Some(TOTAL_WEIGHT_BPS),
+                    AmmError::InvalidWeights
+                );
+                (0, curve.weight_x, curve.weight_y)
+            }
+        };
+
         self.config.set_inner(Config {
+            version: CONFIG_VERSION,
             seed,
             authority,
             mint_x: self.mint_x.key(),
@@ -68,7 +123,77 @@ impl<'info> Initialize<'info> {
             locked: false,
             config_bump: bumps.config,
             lp_bump: bumps.mint_lp,
+            curve_type: curve.curve_type,
+            initial_amp: amp,
+            target_amp: amp,
+            ramp_start_ts: 0,
+            ramp_stop_ts: 0,
+            weight_x,
+            weight_y,
+            dynamic_fee: DynamicFeeParams::default(),
+            // Concentrated swaps pay the whole fee to positions
+            protocol_fee_share: match curve.curve_type {
+                CurveType::Concentrated => 0,
+                _ => self.amm_config.protocol_fee_share,
+            },
+            max_price_impact_bps: 0,
+            max_slot_price_move_bps: 0,
+            oracle: None,
+            oracle_params: OracleParams::default(),
+            volume_limits: VolumeLimits::default(),
+            access_mode: AccessMode::Open,
+            max_referral_fee_bps: 0,
+            pauser: None,
+            fee_manager: None,
+            timelock_seconds: 0,
+            vault_x: self.vault_x.key(),
+            vault_y: self.vault_y.key(),
+            mint_lp: self.mint_lp.key(),
+            decimals_x: self.mint_x.decimals,
+            decimals_y: self.mint_y.decimals,
+            batch_window_slots: 0,
+            min_intent_amount: 0,
+            padding: [0; 14],
         });
-        Ok(())
+        self.pool_state.load_init()?.config = self.config.key();
+        self.register(bumps.pool_index_x, bumps.pool_index_y)?;
+        self.pay_creation_fee()
+    }
+
+    /// Adds the pool to the registry of both of its mints.
+    pub fn register(&self, bump_x: u8, bump_y: u8) -> Result<()> {
+        let payer = self.initializer.to_account_info();
+        let system_program = self.system_program.to_account_info();
+        PoolIndex::register(
+            &self.pool_index_x,
+            self.mint_x.key(),
+            self.config.key(),
+            bump_x,
+            &payer,
+            &system_program,
+        )?;
+        PoolIndex::register(
+            &self.pool_index_y,
+            self.mint_y.key(),
+            self.config.key(),
+            bump_y,
+            &payer,
+            &system_program,
+        )
+    }
+
+    pub fn pay_creation_fee(&self) -> Result<()> {
+        let amount = self.amm_config.pool_creation_fee;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let cpi_program = self.system_program.to_account_info();
+        let cpi_account = Transfer {
+            from: self.initializer.to_account_info(),
+            to: self.fee_recipient.to_account_info(),
+        };
+        let ctx = CpiContext::new(cpi_program, cpi_account);
+        transfer(ctx, amount)
     }
 }
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, program::Amm, states::AmmConfig};
+
+#[derive(Accounts)]
+pub struct InitializeAmmConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"amm_config"],
+        bump,
+        space = 8 + AmmConfig::INIT_SPACE,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    pub system_program: Program<'info, System>,
+    // Only the upgrade authority may claim the global config, so it cannot be front-run
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Amm>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ AmmError::InvalidAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+impl<'info> InitializeAmmConfig<'info> {
+    /// Creates the global config with the program's upgrade authority as admin and no fee
+    /// tiers.
+    pub fn init(
+        &mut self,
+        fee_recipient: Pubkey,
+        protocol_fee_share: u16,
+        pool_creation_fee: u64,
+        bumps: InitializeAmmConfigBumps,
+    ) -> Result<()> {
+        require!(protocol_fee_share <= 10_000, AmmError::InvalidFee);
+
+        self.amm_config.set_inner(AmmConfig {
+            admin: self.admin.key(),
+            fee_recipient,
+            protocol_fee_share,
+            pool_creation_fee,
+            fee_tiers: Vec::new(),
+            bump: bumps.amm_config,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_TICK_SPACING,
+    error::AmmError,
+    math::{tick_from_sqrt_price, MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64},
+    states::{ConcentratedPool, Config, CurveType},
+};
+
+#[derive(Accounts)]
+pub struct InitializeConcentrated<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+        constraint = config.curve_type == CurveType::Concentrated @ AmmError::UnsupportedCurve,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"clmm", config.key().as_ref()],
+        bump,
+        space = 8 + ConcentratedPool::INIT_SPACE,
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConcentrated<'info> {
+    /// Sets the tick spacing and opening price; the pool holds no liquidity yet, so anyone
+    /// may open it and arbitrage corrects a bad price before positions are added.
+    pub fn init(
+        &mut self,
+        tick_spacing: u16,
+        initial_sqrt_price: u128,
+        bumps: InitializeConcentratedBumps,
+    ) -> Result<()> {
+        require!(
+            (1..=MAX_TICK_SPACING).contains(&tick_spacing),
+            AmmError::InvalidTickSpacing
+        );
+        require!(self.config.fee < 10_000, AmmError::InvalidFee);
+        require!(
+            (MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64).contains(&initial_sqrt_price),
+            AmmError::InvalidSqrtPrice
+        );
+
+        self.pool.set_inner(ConcentratedPool {
+            config: self.config.key(),
+            tick_spacing,
+            sqrt_price: initial_sqrt_price,
+            tick_current: tick_from_sqrt_price(initial_sqrt_price)?,
+            liquidity: 0,
+            fee_growth_global_x: 0,
+            fee_growth_global_y: 0,
+            bump: bumps.pool,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create, get_associated_token_address, AssociatedToken, Create},
+    token::{Mint, Token},
+};
+
+use crate::{
+    constants::{MAX_AMP, MAX_MULTI_POOL_TOKENS, MAX_PRECISION_DECIMALS, MIN_AMP, MIN_MULTI_POOL_TOKENS},
+    error::AmmError,
+    states::MultiPool,
+};
+
+/// Remaining accounts: the pool mints followed by their vaults (the pool PDA's associated
+/// token accounts, created here), in the same order.
+#[derive(Accounts)]
+#[instruction(seed: u64, fee: u16, authority: Option<Pubkey>, amp: u64, lp_decimals: u8)]
+pub struct InitializeMultiPool<'info> {
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"multi_pool", seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + MultiPool::INIT_SPACE,
+    )]
+    pub pool: Account<'info, MultiPool>,
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"multi_lp", pool.key().as_ref()],
+        bump,
+        mint::decimals = lp_decimals,
+        mint::authority = pool,
+    )]
+    pub mint_lp: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeMultiPool<'info> {
+    pub fn init(
+        &mut self,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        amp: u64,
+        lp_decimals: u8,
+        bumps: InitializeMultiPoolBumps,
+    ) -> Result<()> {
+        require!(fee < 10_000, AmmError::InvalidFee);
+        require!((MIN_AMP..=MAX_AMP).contains(&amp), AmmError::InvalidAmp);
+        require!(lp_decimals <= MAX_PRECISION_DECIMALS, AmmError::InvalidPrecision);
+
+        self.pool.set_inner(MultiPool {
+            seed,
+            authority,
+            fee,
+            locked: false,
+            amp,
+            mints: Vec::new(),
+            decimals: Vec::new(),
+            reserves: Vec::new(),
+            pool_bump: bumps.pool,
+            lp_bump: bumps.mint_lp,
+        });
+        Ok(())
+    }
+
+    /// Registers the pool mints and creates a vault for each.
+    pub fn add_tokens(&mut self, accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let n = accounts.len() / 2;
+        require!(
+            n * 2 == accounts.len() && (MIN_MULTI_POOL_TOKENS..=MAX_MULTI_POOL_TOKENS).contains(&n),
+            AmmError::InvalidToken
+        );
+        let (mints, vaults) = accounts.split_at(n);
+
+        for (mint_info, vault_info) in mints.iter().zip(vaults) {
+            let mint = Account::<Mint>::try_from(mint_info)?;
+            require!(
+                !self.pool.mints.contains(&mint.key()),
+                AmmError::InvalidToken
+            );
+            require!(mint.decimals <= MAX_PRECISION_DECIMALS, AmmError::InvalidPrecision);
+            require_keys_eq!(
+                vault_info.key(),
+                get_associated_token_address(&self.pool.key(), &mint.key()),
+                AmmError::InvalidToken
+            );
+
+            create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                Create {
+                    payer: self.initializer.to_account_info(),
+                    associated_token: vault_info.clone(),
+                    authority: self.pool.to_account_info(),
+                    mint: mint_info.clone(),
+                    system_program: self.system_program.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                },
+            ))?;
+
+            self.pool.mints.push(mint.key());
+            self.pool.decimals.push(mint.decimals);
+            self.pool.reserves.push(0);
+        }
+        Ok(())
+    }
+}
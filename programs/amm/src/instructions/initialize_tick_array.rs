@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    states::{ConcentratedPool, TickArray},
+};
+
+#[derive(Accounts)]
+#[instruction(start_tick_index: i32)]
+pub struct InitializeTickArray<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [b"clmm", pool.config.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"tick_array", pool.key().as_ref(), start_tick_index.to_le_bytes().as_ref()],
+        bump,
+        space = TickArray::SPACE,
+    )]
+    pub tick_array: AccountLoader<'info, TickArray>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeTickArray<'info> {
+    pub fn init(&mut self, start_tick_index: i32) -> Result<()> {
+        require!(
+            TickArray::is_valid_start(start_tick_index, self.pool.tick_spacing),
+            AmmError::InvalidTickArray
+        );
+
+        let mut tick_array = self.tick_array.load_init()?;
+        tick_array.start_tick_index = start_tick_index;
+        tick_array.pool = self.pool.key();
+        Ok(())
+    }
+}
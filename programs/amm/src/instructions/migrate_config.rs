@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use bytemuck::Zeroable;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{Mint, TokenAccount},
+};
+
+use crate::{
+    error::AmmError,
+    states::{Config, ConfigV0, ConfigV1, PoolState},
+};
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: an old-layout config `Account<Config>` cannot decode; its discriminator, size
+    /// and address are checked in `migrate`
+    #[account(mut, owner = crate::ID)]
+    pub config: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_state", config.key().as_ref()],
+        bump,
+        space = PoolState::SPACE,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    pub mint_x: Account<'info, Mint>,
+    pub mint_y: Account<'info, Mint>,
+    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_y: Account<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateConfig<'info> {
+    /// Rewrites a version 0 or 1 config in the current layout, resizing the account to fit,
+    /// and creates its `PoolState`. Permissionless, since the result is fixed by the old account
+    /// and pools without an authority must migrate too; the payer covers any extra rent.
+    pub fn migrate(&mut self) -> Result<()> {
+        let (mut config, mut state) = {
+            let data = self.config.try_borrow_data()?;
+            require!(data.starts_with(Config::DISCRIMINATOR), AmmError::InvalidConfigLayout);
+            if data.len() == ConfigV0::LEN {
+                // v0 pools priced against the vault balances and owed no protocol fees
+                let state = PoolState {
+                    reserve_x: self.vault_x.amount,
+                    reserve_y: self.vault_y.amount,
+                    ..PoolState::zeroed()
+                };
+                (ConfigV0::deserialize(&mut &data[8..])?.migrate(), state)
+            } else if data.len() == ConfigV1::LEN && data[8] == 1 {
+                ConfigV1::deserialize(&mut &data[8..])?.migrate()
+            } else {
+                return err!(AmmError::InvalidConfigLayout);
+            }
+        };
+        let address = Pubkey::create_program_address(
+            &[b"config", config.seed.to_le_bytes().as_ref(), &[config.config_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| AmmError::InvalidConfigLayout)?;
+        require_keys_eq!(address, self.config.key(), AmmError::InvalidConfigLayout);
+        require_keys_eq!(self.mint_x.key(), config.mint_x, AmmError::InvalidToken);
+        require_keys_eq!(self.mint_y.key(), config.mint_y, AmmError::InvalidToken);
+        require_keys_eq!(
+            self.vault_x.key(),
+            get_associated_token_address(&address, &config.mint_x),
+            AmmError::InvalidToken
+        );
+        require_keys_eq!(
+            self.vault_y.key(),
+            get_associated_token_address(&address, &config.mint_y),
+            AmmError::InvalidToken
+        );
+
+        let mint_lp = Pubkey::create_program_address(
+            &[b"lp", address.as_ref(), &[config.lp_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| AmmError::InvalidConfigLayout)?;
+
+        config.vault_x = self.vault_x.key();
+        config.vault_y = self.vault_y.key();
+        config.mint_lp = mint_lp;
+        config.decimals_x = self.mint_x.decimals;
+        config.decimals_y = self.mint_y.decimals;
+
+        state.config = address;
+        *self.pool_state.load_init()? = state;
+
+        let space = 8 + Config::INIT_SPACE;
+        let missing = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(self.config.lamports());
+        if missing > 0 {
+            let ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.payer.to_account_info(),
+                    to: self.config.to_account_info(),
+                },
+            );
+            transfer(ctx, missing)?;
+        }
+        self.config.realloc(space, true)?;
+        config.try_serialize(&mut &mut self.config.try_borrow_mut_data()?[..])
+    }
+}
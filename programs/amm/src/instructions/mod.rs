@@ -6,3 +6,114 @@ pub use deposit::*;
 
 pub mod swap;
 pub use swap::*;
+
+pub mod swap_lean;
+pub use swap_lean::*;
+
+pub mod withdraw;
+pub use withdraw::*;
+
+pub mod ramp_amp;
+pub use ramp_amp::*;
+
+pub mod set_dynamic_fee;
+pub use set_dynamic_fee::*;
+
+pub mod initialize_concentrated;
+pub use initialize_concentrated::*;
+
+pub mod initialize_tick_array;
+pub use initialize_tick_array::*;
+
+pub mod open_position;
+pub use open_position::*;
+
+pub mod modify_liquidity;
+pub use modify_liquidity::*;
+
+pub mod swap_concentrated;
+pub use swap_concentrated::*;
+
+pub mod initialize_multi_pool;
+pub use initialize_multi_pool::*;
+
+pub mod deposit_multi;
+pub use deposit_multi::*;
+
+pub mod withdraw_multi;
+pub use withdraw_multi::*;
+
+pub mod swap_multi;
+pub use swap_multi::*;
+
+pub mod create_reward_pool;
+pub use create_reward_pool::*;
+
+pub mod farm;
+pub use farm::*;
+
+pub mod reclaim_rewards;
+pub use reclaim_rewards::*;
+
+pub mod initialize_amm_config;
+pub use initialize_amm_config::*;
+
+pub mod update_amm_config;
+pub use update_amm_config::*;
+
+pub mod update_fee_tier;
+pub use update_fee_tier::*;
+
+pub mod collect_protocol_fees;
+pub use collect_protocol_fees::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+
+pub mod sync_reserves;
+pub use sync_reserves::*;
+
+pub mod skim;
+pub use skim::*;
+
+pub mod set_circuit_breaker;
+pub use set_circuit_breaker::*;
+
+pub mod set_oracle;
+pub use set_oracle::*;
+
+pub mod init_wallet_volume;
+pub use init_wallet_volume::*;
+
+pub mod set_volume_limits;
+pub use set_volume_limits::*;
+
+pub mod allowlist;
+pub use allowlist::*;
+
+pub mod set_referral_fee;
+pub use set_referral_fee::*;
+
+pub mod set_fee;
+pub use set_fee::*;
+
+pub mod set_role;
+pub use set_role::*;
+
+pub mod timelock;
+pub use timelock::*;
+
+pub mod migrate_config;
+pub use migrate_config::*;
+
+pub mod orders;
+pub use orders::*;
+
+pub mod dca;
+pub use dca::*;
+
+pub mod set_batch_mode;
+pub use set_batch_mode::*;
+
+pub mod batch;
+pub use batch::*;
@@ -0,0 +1,218 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    math::{add_liquidity_delta, amounts_for_liquidity, sqrt_price_from_tick},
+    states::{AllowEntry, ConcentratedPool, Config, Position, Tick, TickArray},
+};
+
+#[derive(Accounts)]
+pub struct ModifyLiquidity<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint_x: Box<Account<'info, Mint>>,
+    pub mint_y: Box<Account<'info, Mint>>,
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [b"clmm", config.key().as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, ConcentratedPool>>,
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = pool,
+        seeds = [
+            b"position",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            position.tick_lower.to_le_bytes().as_ref(),
+            position.tick_upper.to_le_bytes().as_ref(),
+        ],
+        bump = position.bump,
+    )]
+    pub position: Box<Account<'info, Position>>,
+    /// Tick arrays holding the position's lower and upper ticks; may be the same account.
+    #[account(mut)]
+    pub tick_array_lower: AccountLoader<'info, TickArray>,
+    #[account(mut)]
+    pub tick_array_upper: AccountLoader<'info, TickArray>,
+    #[account(mut)]
+    pub vault_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_y: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = owner,
+    )]
+    pub user_x: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = owner,
+    )]
+    pub user_y: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// Needed to add liquidity to a permissioned pool; removing it and collecting fees stay
+    /// open to everyone.
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), owner.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+}
+
+impl<'info> ModifyLiquidity<'info> {
+    pub fn increase(&mut self, liquidity: u128, max_x: u64, max_y: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        self.config.check_access(self.allow_entry.as_deref())?;
+        require!(
+            liquidity > 0 && liquidity <= i128::MAX as u128,
+            AmmError::InvalidAmount
+        );
+
+        let (x, y) = self.modify(liquidity as i128)?;
+        require!(x <= max_x && y <= max_y, AmmError::SlippageExceeded);
+
+        self.to_vault(true, x)?;
+        self.to_vault(false, y)
+    }
+
+    pub fn decrease(&mut self, liquidity: u128, min_x: u64, min_y: u64) -> Result<()> {
+        require!(
+            liquidity > 0 && liquidity <= self.position.liquidity,
+            AmmError::InvalidAmount
+        );
+
+        let (x, y) = self.modify(-(liquidity as i128))?;
+        require!(x >= min_x && y >= min_y, AmmError::SlippageExceeded);
+
+        self.to_user(true, x)?;
+        self.to_user(false, y)
+    }
+
+    /// Pays out the swap fees the position has earned.
+    pub fn collect_fees(&mut self) -> Result<()> {
+        if self.position.liquidity > 0 {
+            self.modify(0)?;
+        }
+
+        let (fees_x, fees_y) = (self.position.fees_owed_x, self.position.fees_owed_y);
+        self.position.fees_owed_x = 0;
+        self.position.fees_owed_y = 0;
+
+        self.to_user(true, fees_x)?;
+        self.to_user(false, fees_y)
+    }
+
+    /// Applies `liquidity_delta` to the position, its ticks and the active liquidity, and
+    /// returns the token amounts backing it.
+    fn modify(&mut self, liquidity_delta: i128) -> Result<(u64, u64)> {
+        let (tick_lower, tick_upper) = (self.position.tick_lower, self.position.tick_upper);
+
+        if liquidity_delta != 0 {
+            with_tick(&self.tick_array_lower, &self.pool, tick_lower, |tick| {
+                tick.update(tick_lower, &self.pool, liquidity_delta, false)
+            })?;
+            with_tick(&self.tick_array_upper, &self.pool, tick_upper, |tick| {
+                tick.update(tick_upper, &self.pool, liquidity_delta, true)
+            })?;
+        }
+
+        let lower = with_tick(&self.tick_array_lower, &self.pool, tick_lower, |tick| Ok(*tick))?;
+        let upper = with_tick(&self.tick_array_upper, &self.pool, tick_upper, |tick| Ok(*tick))?;
+        let (inside_x, inside_y) = self
+            .pool
+            .fee_growth_inside(tick_lower, &lower, tick_upper, &upper);
+        self.position.update(liquidity_delta, inside_x, inside_y)?;
+
+        if (tick_lower..tick_upper).contains(&self.pool.tick_current) {
+            self.pool.liquidity =
+                add_liquidity_delta(self.pool.liquidity, liquidity_delta)?;
+        }
+
+        amounts_for_liquidity(
+            self.pool.sqrt_price,
+            sqrt_price_from_tick(tick_lower)?,
+            sqrt_price_from_tick(tick_upper)?,
+            liquidity_delta.unsigned_abs(),
+            liquidity_delta > 0,
+        )
+    }
+
+    fn to_vault(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let (from, to) = match is_x {
+            true => (self.user_x.to_account_info(), self.vault_x.to_account_info()),
+            false => (self.user_y.to_account_info(), self.vault_y.to_account_info()),
+        };
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: self.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        transfer(cpi_ctx, amount)
+    }
+
+    fn to_user(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let (from, to) = match is_x {
+            true => (self.vault_x.to_account_info(), self.user_x.to_account_info()),
+            false => (self.vault_y.to_account_info(), self.user_y.to_account_info()),
+        };
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: self.config.to_account_info(),
+        };
+        let seeds = &[
+            &b"config"[..],
+            &self.config.seed.to_le_bytes(),
+            &[self.config.config_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)
+    }
+}
+
+/// Runs `f` on `tick` inside `tick_array` after checking the array belongs to `pool`. Each
+/// call takes and releases its own borrow, so both bounds may live in the same array.
+fn with_tick<R>(
+    tick_array: &AccountLoader<TickArray>,
+    pool: &Account<ConcentratedPool>,
+    tick: i32,
+    f: impl FnOnce(&mut Tick) -> Result<R>,
+) -> Result<R> {
+    let mut tick_array = tick_array.load_mut()?;
+    require_keys_eq!(tick_array.pool, pool.key(), AmmError::InvalidTickArray);
+    f(tick_array.tick_mut(tick, pool.tick_spacing)?)
+}
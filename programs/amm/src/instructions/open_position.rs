@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    math::{MAX_TICK, MIN_TICK},
+    states::{ConcentratedPool, Position},
+};
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [b"clmm", pool.config.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            b"position",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            tick_lower.to_le_bytes().as_ref(),
+            tick_upper.to_le_bytes().as_ref(),
+        ],
+        bump,
+        space = 8 + Position::INIT_SPACE,
+    )]
+    pub position: Account<'info, Position>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenPosition<'info> {
+    pub fn open(&mut self, tick_lower: i32, tick_upper: i32, bumps: OpenPositionBumps) -> Result<()> {
+        let spacing = self.pool.tick_spacing as i32;
+        require!(
+            tick_lower < tick_upper
+                && tick_lower >= MIN_TICK
+                && tick_upper <= MAX_TICK
+                && tick_lower % spacing == 0
+                && tick_upper % spacing == 0,
+            AmmError::InvalidTick
+        );
+
+        self.position.set_inner(Position {
+            owner: self.owner.key(),
+            pool: self.pool.key(),
+            tick_lower,
+            tick_upper,
+            liquidity: 0,
+            fee_growth_inside_last_x: 0,
+            fee_growth_inside_last_y: 0,
+            fees_owed_x: 0,
+            fees_owed_y: 0,
+            bump: bumps.position,
+        });
+        Ok(())
+    }
+}
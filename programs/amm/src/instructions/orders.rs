@@ -0,0 +1,298 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    events::OrderFilledEvent,
+    instructions::SwapAccounts,
+    states::{AllowEntry, Config, LimitOrder, PoolState, WalletVolume},
+};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// The token sold; the order buys the pool's other token.
+    pub mint_in: Account<'info, Mint>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+        constraint = mint_in.key() == config.mint_x || mint_in.key() == config.mint_y
+            @ AmmError::InvalidToken,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"order", config.key().as_ref(), owner.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + LimitOrder::INIT_SPACE,
+    )]
+    pub order: Account<'info, LimitOrder>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint_in,
+        associated_token::authority = order,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = owner,
+    )]
+    pub owner_src: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), owner.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+}
+
+impl<'info> PlaceOrder<'info> {
+    /// Escrows `amount_in` of `mint_in` and `tip` lamports until the pool pays at least
+    /// `min_amount_out` for it.
+    pub fn place(
+        &mut self,
+        id: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+        tip: u64,
+        bumps: PlaceOrderBumps,
+    ) -> Result<()> {
+        self.config.check_access(self.allow_entry.as_deref())?;
+        require!(amount_in > 0 && min_amount_out > 0, AmmError::InvalidOrder);
+
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.owner_src.to_account_info(),
+                to: self.escrow.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        );
+        transfer(ctx, amount_in)?;
+        if tip > 0 {
+            let ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: self.owner.to_account_info(),
+                    to: self.order.to_account_info(),
+                },
+            );
+            system_program::transfer(ctx, tip)?;
+        }
+
+        self.order.set_inner(LimitOrder {
+            config: self.config.key(),
+            owner: self.owner.key(),
+            escrow: self.escrow.key(),
+            id,
+            x_to_y: self.mint_in.key() == self.config.mint_x,
+            amount_in,
+            min_amount_out,
+            tip,
+            bump: bumps.order,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct FillOrder<'info> {
+    /// Anyone may fill an order the pool can satisfy, and collects its tip.
+    #[account(mut)]
+    pub filler: Signer<'info>,
+    /// Receives the output, and the order's rent once it is closed.
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    #[account(
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = config,
+        has_one = owner,
+        has_one = escrow,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+    #[account(mut)]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::authority = owner,
+    )]
+    pub owner_dst: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_y: Box<Account<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: must be the oracle pinned in `config`; parsed as a Pyth price account
+    pub oracle: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"wallet_volume", config.key().as_ref(), owner.key().as_ref()],
+        bump = wallet_volume.bump,
+    )]
+    pub wallet_volume: Option<Account<'info, WalletVolume>>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), owner.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+}
+
+impl<'info> FillOrder<'info> {
+    /// Swaps the whole escrow through the pool on the owner's behalf, failing unless it pays
+    /// at least the order's `min_amount_out`, then pays the tip and closes the order.
+    pub fn fill(&mut self) -> Result<()> {
+        let (config, owner) = (self.config.key(), self.owner.key());
+        let (id, bump) = (self.order.id.to_le_bytes(), [self.order.bump]);
+        let seeds: &[&[u8]] = &[b"order", config.as_ref(), owner.as_ref(), &id, &bump];
+
+        let x_to_y = self.order.x_to_y;
+        // Anything donated to the escrow is sold too rather than stranded
+        let amount_in = self.escrow.amount;
+        let (user_x, user_y) = match x_to_y {
+            true => (&self.escrow, &self.owner_dst),
+            false => (&self.owner_dst, &self.escrow),
+        };
+        let order_info = self.order.to_account_info();
+        let amount_out = SwapAccounts {
+            user: &order_info,
+            user_seeds: seeds,
+            config: &self.config,
+            pool_state: &self.pool_state,
+            vault_x: &self.vault_x,
+            vault_y: &self.vault_y,
+            user_x,
+            user_y,
+            token_program: &self.token_program,
+            oracle: self.oracle.as_deref(),
+            wallet_volume: self.wallet_volume.as_deref_mut(),
+            allow_entry: self.allow_entry.as_deref(),
+            referrer: None,
+            decimals_x: self.config.decimals_x,
+            decimals_y: self.config.decimals_y,
+            min_amount_out: self.order.min_amount_out,
+        }
+        .swap(x_to_y, amount_in, 10_000, 0)?;
+
+        close_escrow(
+            &self.token_program,
+            &self.escrow,
+            &self.owner.to_account_info(),
+            &order_info,
+            seeds,
+        )?;
+        let tip = self.order.tip;
+        order_info.sub_lamports(tip)?;
+        self.filler.add_lamports(tip)?;
+
+        emit!(OrderFilledEvent {
+            config,
+            order: self.order.key(),
+            owner,
+            filler: self.filler.key(),
+            x_to_y,
+            amount_in,
+            amount_out,
+            tip,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = escrow,
+    )]
+    pub order: Account<'info, LimitOrder>,
+    #[account(mut)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = owner,
+    )]
+    pub owner_src: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CancelOrder<'info> {
+    /// Refunds the escrow to the owner and closes the order, returning its rent and tip.
+    pub fn cancel(&mut self) -> Result<()> {
+        let (config, owner) = (self.order.config, self.owner.key());
+        let (id, bump) = (self.order.id.to_le_bytes(), [self.order.bump]);
+        let seeds: &[&[u8]] = &[b"order", config.as_ref(), owner.as_ref(), &id, &bump];
+        let order_info = self.order.to_account_info();
+
+        if self.escrow.amount > 0 {
+            let signer_seeds = &[seeds];
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.escrow.to_account_info(),
+                    to: self.owner_src.to_account_info(),
+                    authority: order_info.clone(),
+                },
+                signer_seeds,
+            );
+            transfer(ctx, self.escrow.amount)?;
+        }
+        close_escrow(
+            &self.token_program,
+            &self.escrow,
+            &self.owner.to_account_info(),
+            &order_info,
+            seeds,
+        )
+    }
+}
+
+/// Closes an emptied escrow owned by a PDA (an order or a DCA vault), sending its rent to
+/// `destination`.
+pub(crate) fn close_escrow<'info>(
+    token_program: &Program<'info, Token>,
+    escrow: &Account<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let signer_seeds = &[seeds];
+    let ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: destination.clone(),
+            authority: authority.clone(),
+        },
+        signer_seeds,
+    );
+    close_account(ctx)
+}
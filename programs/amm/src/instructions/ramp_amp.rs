@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    states::{ Config, CurveType, Role },
+};
+
+#[derive(Accounts)]
+pub struct RampAmp<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+        constraint = config.curve_type == CurveType::StableSwap @ AmmError::UnsupportedCurve,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> RampAmp<'info> {
+    /// Starts moving the amplification linearly towards `target_amp`, reaching it at `stop_ts`.
+    pub fn ramp(&mut self, target_amp: u64, stop_ts: i64) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        self.config.check_timelock()?;
+        let now = Clock::get()?.unix_timestamp;
+        self.config.start_amp_ramp(target_amp, stop_ts, now)
+    }
+
+    /// Freezes the amplification at its current value. Like locking, this is never timelocked.
+    pub fn stop(&mut self) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        let now = Clock::get()?.unix_timestamp;
+        let current_amp = self.config.current_amp(now);
+
+        self.config.initial_amp = current_amp;
+        self.config.target_amp = current_amp;
+        self.config.ramp_start_ts = now;
+        self.config.ramp_stop_ts = now;
+        Ok(())
+    }
+}
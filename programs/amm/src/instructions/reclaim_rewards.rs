@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::states::{Config, RewardPool, Role};
+
+#[derive(Accounts)]
+pub struct ReclaimRewards<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    pub reward_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        has_one = config,
+        has_one = reward_mint,
+        seeds = [b"reward_pool", config.key().as_ref(), reward_mint.key().as_ref()],
+        bump = reward_pool.bump,
+    )]
+    pub reward_pool: Box<Account<'info, RewardPool>>,
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_pool,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = authority,
+    )]
+    pub authority_reward: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ReclaimRewards<'info> {
+    /// Returns the emissions accrued while nothing was staked to the pool authority.
+    pub fn reclaim(&mut self) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        self.reward_pool.update(Clock::get()?.unix_timestamp)?;
+
+        let amount = self.reward_pool.undistributed;
+        if amount == 0 {
+            return Ok(());
+        }
+        self.reward_pool.undistributed = 0;
+
+        let cpi_accounts = Transfer {
+            from: self.reward_vault.to_account_info(),
+            to: self.authority_reward.to_account_info(),
+            authority: self.reward_pool.to_account_info(),
+        };
+        let config = self.config.key();
+        let reward_mint = self.reward_mint.key();
+        let seeds = &[
+            &b"reward_pool"[..],
+            config.as_ref(),
+            reward_mint.as_ref(),
+            &[self.reward_pool.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)
+    }
+}
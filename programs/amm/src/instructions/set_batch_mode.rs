@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_BATCH_WINDOW_SLOTS,
+    error::AmmError,
+    states::{Config, CurveType, Role},
+};
+
+#[derive(Accounts)]
+pub struct SetBatchMode<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetBatchMode<'info> {
+    /// Collects swaps into batch auctions of `batch_window_slots` slots taking intents worth
+    /// at least `min_intent_amount` X, or returns the pool to instant swaps with 0. Batches
+    /// already open can still be settled after switching off.
+    pub fn set(&mut self, batch_window_slots: u64, min_intent_amount: u64) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        require!(
+            batch_window_slots <= MAX_BATCH_WINDOW_SLOTS,
+            AmmError::InvalidBatchWindow
+        );
+        // Batches clear with the constant-product closed form
+        if batch_window_slots > 0 {
+            require!(
+                self.config.curve_type == CurveType::ConstantProduct,
+                AmmError::UnsupportedCurve
+            );
+            require!(min_intent_amount > 0, AmmError::InvalidBatchWindow);
+        }
+        self.config.batch_window_slots = batch_window_slots;
+        self.config.min_intent_amount = min_intent_amount;
+        Ok(())
+    }
+}
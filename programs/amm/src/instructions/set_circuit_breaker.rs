@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Config, PoolState, Role};
+
+#[derive(Accounts)]
+pub struct SetCircuitBreaker<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+impl<'info> SetCircuitBreaker<'info> {
+    /// Sets the per-swap and per-slot price-move limits in basis points; 0 disables either.
+    pub fn set(&mut self, max_price_impact_bps: u16, max_slot_price_move_bps: u16) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        if max_price_impact_bps > 0 || max_slot_price_move_bps > 0 {
+            self.config.check_swap_guards()?;
+        }
+        self.config.max_price_impact_bps = max_price_impact_bps;
+        self.config.max_slot_price_move_bps = max_slot_price_move_bps;
+        self.pool_state.load_mut()?.slot_price_move_bps = 0;
+        Ok(())
+    }
+
+    /// Locks or unlocks the pool, clearing the movement that tripped the breaker. The pauser
+    /// may lock; only the admin may unlock.
+    pub fn set_locked(&mut self, locked: bool) -> Result<()> {
+        let role = if locked { Role::Pauser } else { Role::Admin };
+        self.config.check_role(&self.authority.key(), role)?;
+        self.config.locked = locked;
+
+        let mut state = self.pool_state.load_mut()?;
+        state.breaker_tripped = 0;
+        state.slot_price_move_bps = 0;
+        Ok(())
+    }
+}
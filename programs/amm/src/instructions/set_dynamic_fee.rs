@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Config, DynamicFeeParams, PoolState, Role};
+
+#[derive(Accounts)]
+pub struct SetDynamicFee<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+impl<'info> SetDynamicFee<'info> {
+    /// Replaces the dynamic-fee settings and restarts volatility tracking.
+    pub fn set(&mut self, params: DynamicFeeParams) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::FeeManager)?;
+        self.config.check_timelock()?;
+        self.config.set_dynamic_fee(params)?;
+        self.pool_state.load_mut()?.reset_volatility();
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{AmmConfig, Config, Role};
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"amm_config"],
+        bump = amm_config.bump,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetFee<'info> {
+    /// Moves the pool to another enabled fee tier.
+    pub fn set(&mut self, fee: u16) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::FeeManager)?;
+        self.config.check_timelock()?;
+        self.config.set_fee(fee, &self.amm_config)
+    }
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    states::{Config, OracleParams, Role},
+};
+
+#[derive(Accounts)]
+pub struct SetOracle<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetOracle<'info> {
+    /// Pins the Pyth price account swaps are checked against, or removes it with `None`.
+    pub fn set(&mut self, oracle: Option<Pubkey>, params: OracleParams) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        if oracle.is_some() {
+            self.config.check_swap_guards()?;
+            // A zero confidence bound would reject every price with any uncertainty
+            require!(
+                params.max_deviation_bps > 0
+                    && params.max_staleness > 0
+                    && params.max_confidence_bps > 0,
+                AmmError::InvalidOracleParams
+            );
+        }
+
+        self.config.oracle = oracle;
+        self.config.oracle_params = params;
+        Ok(())
+    }
+}
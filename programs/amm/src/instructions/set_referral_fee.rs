@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Config, Role};
+
+#[derive(Accounts)]
+pub struct SetReferralFee<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetReferralFee<'info> {
+    /// Caps the share of the swap fee, in basis points, integrators may claim.
+    pub fn set(&mut self, max_referral_fee_bps: u16) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::FeeManager)?;
+        self.config.check_timelock()?;
+        self.config.set_max_referral_fee(max_referral_fee_bps)
+    }
+}
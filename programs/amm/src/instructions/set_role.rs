@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Config, Role};
+
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetRole<'info> {
+    /// Assigns or revokes the holder of `role`. Passing `Role::Admin` hands the pool to a new
+    /// authority, or renounces it for good with `None`.
+    pub fn set(&mut self, role: Role, holder: Option<Pubkey>) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        match role {
+            Role::Admin => self.config.authority = holder,
+            Role::Pauser => self.config.pauser = holder,
+            Role::FeeManager => self.config.fee_manager = holder,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Config, PoolState, Role, VolumeLimits, VolumeWindow};
+
+#[derive(Accounts)]
+pub struct SetVolumeLimits<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+impl<'info> SetVolumeLimits<'info> {
+    /// Replaces the volume caps and starts a fresh pool window.
+    pub fn set(&mut self, limits: VolumeLimits) -> Result<()> {
+        self.config.check_role(&self.authority.key(), Role::Admin)?;
+        if limits != VolumeLimits::default() {
+            self.config.check_swap_guards()?;
+        }
+        self.config.volume_limits = limits;
+        self.pool_state.load_mut()?.volume_window = VolumeWindow::default();
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    error::AmmError,
+    states::{Config, CurveType, PoolState},
+};
+
+#[derive(Accounts)]
+pub struct Skim<'info> {
+    pub mint_x: Account<'info, Mint>,
+    pub mint_y: Account<'info, Mint>,
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+        constraint = config.curve_type != CurveType::Concentrated @ AmmError::UnsupportedCurve,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(has_one = config)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut)]
+    pub vault_x: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_y: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint_x)]
+    pub recipient_x: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint_y)]
+    pub recipient_y: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> Skim<'info> {
+    /// Sends vault tokens held beyond the reserves and owed protocol fees to the recipient
+    /// accounts, leaving the price untouched.
+    pub fn skim(&self) -> Result<()> {
+        let state = self.pool_state.load()?;
+        self.config.check_unlocked(&state)?;
+
+        let (excess_x, excess_y) = state.excess(self.vault_x.amount, self.vault_y.amount);
+        if excess_x > 0 {
+            self.transfer_out(true, excess_x)?;
+        }
+        if excess_y > 0 {
+            self.transfer_out(false, excess_y)?;
+        }
+        Ok(())
+    }
+
+    fn transfer_out(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to) = match is_x {
+            true => (self.vault_x.to_account_info(), self.recipient_x.to_account_info()),
+            false => (self.vault_y.to_account_info(), self.recipient_y.to_account_info()),
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_account = Transfer {
+            from,
+            to,
+            authority: self.config.to_account_info(),
+        };
+        let seeds = &[&b"config"[..], &self.config.seed.to_le_bytes(), &[self.config.config_bump]];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(cpi_program, cpi_account, signer_seeds);
+        transfer(ctx, amount)
+    }
+}
@@ -3,9 +3,13 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token::{transfer, Mint, Token, TokenAccount, Transfer},
 };
-use constant_product_curve::ConstantProduct;
 
-use crate::{error::AmmError, states::Config};
+use crate::{
+    curves,
+    error::AmmError,
+    events::SwapEvent,
+    states::{reserve_price, AllowEntry, Config, PoolState, WalletVolume},
+};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -16,21 +20,20 @@ pub struct Swap<'info> {
     #[account(
         has_one = mint_x,
         has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
         seeds =[b"config",config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump
     )]
     pub config: Account<'info, Config>,
     #[account(
         mut,
-        associated_token::mint = mint_x,
-        associated_token::authority = config,
+        has_one = config,
     )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut)]
     pub vault_x: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        associated_token::mint = mint_y,
-        associated_token::authority = config,
-    )]
+    #[account(mut)]
     pub vault_y: Account<'info, TokenAccount>,
     #[account(
         mut,
@@ -49,33 +52,160 @@ pub struct Swap<'info> {
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    /// CHECK: must be the oracle pinned in `config`; parsed as a Pyth price account
+    pub oracle: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"wallet_volume", config.key().as_ref(), user.key().as_ref()],
+        bump = wallet_volume.bump,
+    )]
+    pub wallet_volume: Option<Account<'info, WalletVolume>>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), user.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+    /// Integrator token account of the input mint receiving the referral fee.
+    #[account(mut)]
+    pub referrer: Option<Account<'info, TokenAccount>>,
 }
 
 impl<'info> Swap<'info> {
-    pub fn swap(&mut self, x_to_y: bool, amount_in: u64, slippage: u16) -> Result<()> {
-        require!(self.config.locked == false, AmmError::PoolLocked);
+    pub fn swap(
+        &mut self,
+        x_to_y: bool,
+        amount_in: u64,
+        slippage: u16,
+        referral_fee_bps: u16,
+    ) -> Result<()> {
+        SwapAccounts {
+            user: &self.user,
+            user_seeds: &[],
+            config: &self.config,
+            pool_state: &self.pool_state,
+            vault_x: &self.vault_x,
+            vault_y: &self.vault_y,
+            user_x: &self.user_x,
+            user_y: &self.user_y,
+            token_program: &self.token_program,
+            oracle: self.oracle.as_deref(),
+            wallet_volume: self.wallet_volume.as_deref_mut(),
+            allow_entry: self.allow_entry.as_deref(),
+            referrer: self.referrer.as_ref(),
+            decimals_x: self.mint_x.decimals,
+            decimals_y: self.mint_y.decimals,
+            min_amount_out: 0,
+        }
+        .swap(x_to_y, amount_in, slippage, referral_fee_bps)?;
+        Ok(())
+    }
+}
+
+/// The accounts a swap prices against and moves tokens between, borrowed from `Swap`,
+/// `SwapLean`, `FillOrder`, `ExecuteDca` or `SettleBatch` once each has validated them its
+/// own way.
+pub struct SwapAccounts<'a, 'info> {
+    /// Owner of `user_x` and `user_y`; a signer, or a PDA signed for with `user_seeds`.
+    pub user: &'a AccountInfo<'info>,
+    pub user_seeds: &'a [&'a [u8]],
+    pub config: &'a Account<'info, Config>,
+    pub pool_state: &'a AccountLoader<'info, PoolState>,
+    pub vault_x: &'a Account<'info, TokenAccount>,
+    pub vault_y: &'a Account<'info, TokenAccount>,
+    pub user_x: &'a Account<'info, TokenAccount>,
+    pub user_y: &'a Account<'info, TokenAccount>,
+    pub token_program: &'a Program<'info, Token>,
+    pub oracle: Option<&'a AccountInfo<'info>>,
+    pub wallet_volume: Option<&'a mut WalletVolume>,
+    pub allow_entry: Option<&'a AllowEntry>,
+    pub referrer: Option<&'a Account<'info, TokenAccount>>,
+    pub decimals_x: u8,
+    pub decimals_y: u8,
+    /// Output floor applied on top of `slippage`, 0 for none.
+    pub min_amount_out: u64,
+}
+
+impl<'info> SwapAccounts<'_, 'info> {
+    /// Prices the swap, records it in `PoolState` and moves the tokens; returns the output.
+    pub fn swap(
+        self,
+        x_to_y: bool,
+        amount_in: u64,
+        slippage: u16,
+        referral_fee_bps: u16,
+    ) -> Result<u64> {
+        require!(self.config.batch_window_slots == 0, AmmError::BatchAuctionOnly);
+        self.config.check_access(self.allow_entry)?;
+        require!(
+            self.config.volume_limits.max_wallet_volume == 0 || self.wallet_volume.is_some(),
+            AmmError::MissingWalletVolume
+        );
+        self.execute(x_to_y, amount_in, slippage, referral_fee_bps)
+    }
+
+    /// `swap` without the batch-mode, access and wallet-volume checks, for the net trade of a
+    /// batch whose intents were checked as they were submitted.
+    pub(crate) fn execute(
+        mut self,
+        x_to_y: bool,
+        amount_in: u64,
+        slippage: u16,
+        referral_fee_bps: u16,
+    ) -> Result<u64> {
+        let mut state = self.pool_state.load_mut()?;
+        self.config.check_unlocked(&state)?;
         require!(amount_in != 0, AmmError::InvalidAmount);
 
         let (user_src, user_dst, vault_src, vault_dst) = if x_to_y {
-            (&self.user_x, &self.user_y, &self.vault_y, &self.vault_x)
+            (self.user_x, self.user_y, self.vault_y, self.vault_x)
         } else {
-            (&self.user_y, &self.user_x, &self.vault_x, &self.vault_y)
+            (self.user_y, self.user_x, self.vault_x, self.vault_y)
         };
 
+        let (reserve_x, reserve_y) = (state.reserve_x, state.reserve_y);
         require!(user_src.amount >= amount_in, AmmError::InsufficientBalance);
         require!(
-            vault_src.amount > 0 && vault_dst.amount > 0,
+            reserve_x > 0 && reserve_y > 0,
             AmmError::NoLiquidityInPool
         );
 
-        let amount_in_with_fee = (amount_in as u128 * (10_000 - self.config.fee as u128)) / 10_000;
+        let clock = Clock::get()?;
+        let price_before = reserve_price(reserve_x, reserve_y);
+        let fee = self.config.swap_fee(&mut state, clock.slot, price_before);
+        let amount_in_with_fee = (amount_in as u128 * (10_000 - fee as u128)) / 10_000;
+        // The referrer's share comes out of the fee first, the protocol's out of the remainder
+        let fee_amount = amount_in - amount_in_with_fee as u64;
+        let referral_fee = match self.referrer {
+            Some(referrer) => {
+                require!(
+                    referral_fee_bps <= self.config.max_referral_fee_bps,
+                    AmmError::InvalidReferralFee
+                );
+                require_keys_eq!(referrer.mint, user_src.mint, AmmError::InvalidReferrer);
+                // Otherwise a trader could refer themselves and pocket part of the LP fee
+                require_keys_neq!(referrer.owner, self.user.key(), AmmError::SelfReferral);
+                (fee_amount as u128 * referral_fee_bps as u128 / 10_000) as u64
+            }
+            None => 0,
+        };
+        let protocol_fee = ((fee_amount - referral_fee) as u128
+            * self.config.protocol_fee_share as u128
+            / 10_000) as u64;
+        let amount_to_vault = amount_in - referral_fee;
 
-        let amount_out = ConstantProduct::delta_x_from_y_swap_amount(
-            vault_dst.amount,
-            vault_src.amount,
+        let reserves = curves::Reserves {
+            x: reserve_x,
+            y: reserve_y,
+            decimals_x: self.decimals_x,
+            decimals_y: self.decimals_y,
+        };
+        let amount_out = curves::swap_amount_out(
+            self.config,
+            &reserves,
+            x_to_y,
             amount_in_with_fee as u64,
-        )
-        .unwrap();
+            clock.unix_timestamp,
+        )?;
 
         require!(amount_out != 0, AmmError::InvalidAmount);
 
@@ -86,12 +216,59 @@ impl<'info> Swap<'info> {
 
         let min_amount_out = (amount_in_with_fee * (10_000 - slippage as u128)) / 10_000;
         require!(
-            amount_out as u128 >= min_amount_out,
+            amount_out as u128 >= min_amount_out && amount_out >= self.min_amount_out,
             AmmError::SlippageExceeded
         );
 
-        self.to_vault(user_src, vault_dst, amount_in)?;
-        self.to_user(user_dst, vault_src, amount_out)
+        // The protocol's cut of the fee stays in the input vault but not in the reserves
+        let (reserve_x, reserve_y) = if x_to_y {
+            state.protocol_fees_x += protocol_fee;
+            (reserve_x + amount_to_vault - protocol_fee, reserve_y - amount_out)
+        } else {
+            state.protocol_fees_y += protocol_fee;
+            (reserve_x - amount_out, reserve_y + amount_to_vault - protocol_fee)
+        };
+        self.config.check_oracle(
+            self.oracle,
+            &reserves,
+            &curves::Reserves { x: reserve_x, y: reserve_y, ..reserves },
+            clock.unix_timestamp,
+        )?;
+        let volume = if x_to_y { amount_in } else { amount_out };
+        self.config.record_volume(
+            &mut state,
+            clock.slot,
+            volume,
+            self.wallet_volume.as_deref_mut(),
+        )?;
+        let price_after = reserve_price(reserve_x, reserve_y);
+        self.config
+            .check_price_impact(&mut state, clock.slot, price_before, price_after)?;
+        state.update_price_cumulative(clock.unix_timestamp);
+        state.reserve_x = reserve_x;
+        state.reserve_y = reserve_y;
+        self.config.update_volatility(&mut state, clock.slot, price_after);
+        drop(state);
+
+        self.to_vault(user_src, vault_dst, amount_to_vault)?;
+        if let Some(referrer) = self.referrer {
+            if referral_fee > 0 {
+                self.to_vault(user_src, referrer, referral_fee)?;
+            }
+        }
+        self.to_user(user_dst, vault_src, amount_out)?;
+
+        emit!(SwapEvent {
+            config: self.config.key(),
+            user: self.user.key(),
+            x_to_y,
+            amount_in,
+            amount_out,
+            fee: fee_amount,
+            referrer: self.referrer.map(|referrer| referrer.key()),
+            referral_fee,
+        });
+        Ok(amount_out)
     }
 
     pub fn to_vault(
@@ -106,7 +283,11 @@ impl<'info> Swap<'info> {
             authority: self.user.to_account_info(),
         };
         let cpi_program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        let signer_seeds = &[self.user_seeds];
+        let cpi_ctx = match self.user_seeds.is_empty() {
+            true => CpiContext::new(cpi_program, cpi_accounts),
+            false => CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+        };
 
         transfer(cpi_ctx, amount)
     }
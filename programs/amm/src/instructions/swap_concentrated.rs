@@ -0,0 +1,229 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::AmmError,
+    events::SwapEvent,
+    math::{
+        add_liquidity_delta, compute_swap_step, sqrt_price_from_tick, tick_from_sqrt_price,
+        MAX_SQRT_PRICE_X64, MAX_TICK, MIN_SQRT_PRICE_X64, MIN_TICK,
+    },
+    states::{AllowEntry, ConcentratedPool, Config, TickArray},
+};
+
+/// Swaps against a concentrated-liquidity pool. The tick arrays the price may move through
+/// are passed as writable remaining accounts, in any order, after `allow_entry` (the program
+/// id in its place when there is none).
+#[derive(Accounts)]
+pub struct SwapConcentrated<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub mint_x: Box<Account<'info, Mint>>,
+    pub mint_y: Box<Account<'info, Mint>>,
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        has_one = config,
+        seeds = [b"clmm", config.key().as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, ConcentratedPool>>,
+    #[account(mut)]
+    pub vault_x: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_y: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+    )]
+    pub user_x: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+    )]
+    pub user_y: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), user.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+}
+
+impl<'info> SwapConcentrated<'info> {
+    /// Swaps through the pool's ticks. Only the lock and the allowlist apply here; the
+    /// `PoolState` guards cannot be turned on for concentrated pools.
+    pub fn swap(
+        &mut self,
+        tick_arrays: &'info [AccountInfo<'info>],
+        x_to_y: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+        sqrt_price_limit: u128,
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(amount_in != 0, AmmError::InvalidAmount);
+        self.config.check_access(self.allow_entry.as_deref())?;
+        let pool = &mut self.pool;
+        require!(
+            if x_to_y {
+                sqrt_price_limit < pool.sqrt_price && sqrt_price_limit >= MIN_SQRT_PRICE_X64
+            } else {
+                sqrt_price_limit > pool.sqrt_price && sqrt_price_limit <= MAX_SQRT_PRICE_X64
+            },
+            AmmError::InvalidSqrtPrice
+        );
+
+        let tick_arrays = tick_arrays
+            .iter()
+            .map(|info| {
+                let tick_array = AccountLoader::<TickArray>::try_from(info)?;
+                let (pool_key, start_tick_index) = {
+                    let data = tick_array.load()?;
+                    (data.pool, data.start_tick_index)
+                };
+                require_keys_eq!(pool_key, pool.key(), AmmError::InvalidTickArray);
+                Ok((start_tick_index, tick_array))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let spacing = pool.tick_spacing;
+        let mut amount_remaining = amount_in;
+        let mut amount_out: u64 = 0;
+        let mut fee: u64 = 0;
+
+        while amount_remaining > 0 && pool.sqrt_price != sqrt_price_limit {
+            let search_tick = if x_to_y {
+                pool.tick_current
+            } else {
+                pool.tick_current + spacing as i32
+            };
+            let start_tick_index = TickArray::start_index(search_tick, spacing);
+            let (_, loader) = tick_arrays
+                .iter()
+                .find(|(start, _)| *start == start_tick_index)
+                .ok_or(AmmError::MissingTickArray)?;
+            let mut tick_array = loader.load_mut()?;
+
+            let (next_tick, initialized) =
+                tick_array.next_initialized_tick(search_tick, spacing, x_to_y)?;
+            let next_tick = next_tick.clamp(MIN_TICK, MAX_TICK);
+            let sqrt_price_next_tick = sqrt_price_from_tick(next_tick)?;
+            let sqrt_price_target = if x_to_y {
+                sqrt_price_next_tick.max(sqrt_price_limit)
+            } else {
+                sqrt_price_next_tick.min(sqrt_price_limit)
+            };
+
+            let step = compute_swap_step(
+                pool.sqrt_price,
+                sqrt_price_target,
+                pool.liquidity,
+                amount_remaining,
+                self.config.fee,
+                x_to_y,
+            )?;
+            amount_remaining -= step.amount_in + step.fee_amount;
+            fee += step.fee_amount;
+            amount_out = amount_out
+                .checked_add(step.amount_out)
+                .ok_or(AmmError::Overflow)?;
+
+            if let Some(growth) = ((step.fee_amount as u128) << 64).checked_div(pool.liquidity) {
+                if x_to_y {
+                    pool.fee_growth_global_x = pool.fee_growth_global_x.wrapping_add(growth);
+                } else {
+                    pool.fee_growth_global_y = pool.fee_growth_global_y.wrapping_add(growth);
+                }
+            }
+
+            pool.sqrt_price = step.sqrt_price_next;
+            if step.sqrt_price_next == sqrt_price_next_tick {
+                if initialized {
+                    let liquidity_net = tick_array
+                        .tick_mut(next_tick, spacing)?
+                        .cross(pool.fee_growth_global_x, pool.fee_growth_global_y);
+                    let liquidity_delta = if x_to_y { -liquidity_net } else { liquidity_net };
+                    pool.liquidity = add_liquidity_delta(pool.liquidity, liquidity_delta)?;
+                }
+                pool.tick_current = if x_to_y { next_tick - 1 } else { next_tick };
+            } else {
+                pool.tick_current = tick_from_sqrt_price(step.sqrt_price_next)?;
+            }
+        }
+
+        require!(amount_out >= min_amount_out, AmmError::SlippageExceeded);
+        require!(amount_out != 0, AmmError::InvalidAmount);
+
+        let amount_in = amount_in - amount_remaining;
+        self.to_vault(x_to_y, amount_in)?;
+        self.to_user(!x_to_y, amount_out)?;
+
+        emit!(SwapEvent {
+            config: self.config.key(),
+            user: self.user.key(),
+            x_to_y,
+            amount_in,
+            amount_out,
+            fee,
+            referrer: None,
+            referral_fee: 0,
+        });
+        Ok(())
+    }
+
+    fn to_vault(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to) = match is_x {
+            true => (self.user_x.to_account_info(), self.vault_x.to_account_info()),
+            false => (self.user_y.to_account_info(), self.vault_y.to_account_info()),
+        };
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: self.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        transfer(cpi_ctx, amount)
+    }
+
+    fn to_user(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to) = match is_x {
+            true => (self.vault_x.to_account_info(), self.user_x.to_account_info()),
+            false => (self.vault_y.to_account_info(), self.user_y.to_account_info()),
+        };
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: self.config.to_account_info(),
+        };
+        let seeds = &[
+            &b"config"[..],
+            &self.config.seed.to_le_bytes(),
+            &[self.config.config_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)
+    }
+}
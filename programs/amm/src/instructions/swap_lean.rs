@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    instructions::SwapAccounts,
+    states::{AllowEntry, Config, PoolState, WalletVolume},
+};
+
+/// `Swap` without the mints and the programs it never calls. The config is trusted by owner,
+/// the user accounts are checked by owner and mint rather than as ATAs, and the decimals come
+/// from `Config`, so no address is derived.
+#[derive(Accounts)]
+pub struct SwapLean<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        has_one = vault_x,
+        has_one = vault_y,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut)]
+    pub vault_x: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_y: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = config.mint_x,
+        token::authority = user,
+    )]
+    pub user_x: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = config.mint_y,
+        token::authority = user,
+    )]
+    pub user_y: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    /// CHECK: must be the oracle pinned in `config`; parsed as a Pyth price account
+    pub oracle: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"wallet_volume", config.key().as_ref(), user.key().as_ref()],
+        bump = wallet_volume.bump,
+    )]
+    pub wallet_volume: Option<Account<'info, WalletVolume>>,
+    #[account(
+        seeds = [b"allow", config.key().as_ref(), user.key().as_ref()],
+        bump = allow_entry.bump,
+    )]
+    pub allow_entry: Option<Account<'info, AllowEntry>>,
+    /// Integrator token account of the input mint receiving the referral fee.
+    #[account(mut)]
+    pub referrer: Option<Account<'info, TokenAccount>>,
+}
+
+impl<'info> SwapLean<'info> {
+    pub fn swap(
+        &mut self,
+        x_to_y: bool,
+        amount_in: u64,
+        slippage: u16,
+        referral_fee_bps: u16,
+    ) -> Result<()> {
+        SwapAccounts {
+            user: &self.user,
+            user_seeds: &[],
+            config: &self.config,
+            pool_state: &self.pool_state,
+            vault_x: &self.vault_x,
+            vault_y: &self.vault_y,
+            user_x: &self.user_x,
+            user_y: &self.user_y,
+            token_program: &self.token_program,
+            oracle: self.oracle.as_deref(),
+            wallet_volume: self.wallet_volume.as_deref_mut(),
+            allow_entry: self.allow_entry.as_deref(),
+            referrer: self.referrer.as_ref(),
+            decimals_x: self.config.decimals_x,
+            decimals_y: self.config.decimals_y,
+            min_amount_out: 0,
+        }
+        .swap(x_to_y, amount_in, slippage, referral_fee_bps)?;
+        Ok(())
+    }
+}
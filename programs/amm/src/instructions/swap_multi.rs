@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::{curves::StableSwap, error::AmmError, states::MultiPool};
+
+/// Remaining accounts: the pool vaults in pool mint order, then the user's token accounts
+/// for token `i` (paid in) and token `j` (paid out).
+#[derive(Accounts)]
+pub struct SwapMulti<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"multi_pool", pool.seed.to_le_bytes().as_ref()],
+        bump = pool.pool_bump,
+    )]
+    pub pool: Account<'info, MultiPool>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> SwapMulti<'info> {
+    pub fn swap(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        i: u8,
+        j: u8,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require!(!self.pool.locked, AmmError::PoolLocked);
+        require!(amount_in != 0, AmmError::InvalidAmount);
+        let (i, j) = (i as usize, j as usize);
+        require!(
+            i != j && i < self.pool.len() && j < self.pool.len(),
+            AmmError::InvalidToken
+        );
+
+        let (vaults, rest) = self.pool.load_vaults(&self.pool.key(), accounts)?;
+        require!(rest.len() == 2, AmmError::InvalidToken);
+        let user_in = self.pool.load_user_account(i, &rest[0])?;
+        let user_out = self.pool.load_user_account(j, &rest[1])?;
+        require!(user_in.amount >= amount_in, AmmError::InsufficientBalance);
+
+        let amount_in_with_fee = (amount_in as u128 * (10_000 - self.pool.fee as u128)) / 10_000;
+        let amount_out = StableSwap::new(self.pool.amp).swap_amount_out(
+            &self.pool.balances()?,
+            i,
+            j,
+            amount_in_with_fee * self.pool.rate(i),
+        )?;
+        let amount_out = self.pool.unscale(j, amount_out)?;
+        require!(amount_out != 0, AmmError::InvalidAmount);
+        require!(amount_out >= min_amount_out, AmmError::SlippageExceeded);
+        // The fee stays in the pool for the LPs
+        self.pool.reserves[i] = self.pool.reserves[i]
+            .checked_add(amount_in)
+            .ok_or(AmmError::Overflow)?;
+        self.pool.reserves[j] -= amount_out;
+
+        MultiPool::transfer_in(
+            self.user.to_account_info(),
+            self.token_program.to_account_info(),
+            &user_in,
+            &vaults[i],
+            amount_in,
+        )?;
+        self.pool.transfer_out(
+            self.pool.to_account_info(),
+            self.token_program.to_account_info(),
+            &vaults[j],
+            &user_out,
+            amount_out,
+        )
+    }
+}
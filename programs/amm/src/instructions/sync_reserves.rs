@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::{
+    error::AmmError,
+    states::{Config, CurveType, PoolState},
+};
+
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    pub mint_x: Account<'info, Mint>,
+    pub mint_y: Account<'info, Mint>,
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+        constraint = config.curve_type != CurveType::Concentrated @ AmmError::UnsupportedCurve,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_y: Account<'info, TokenAccount>,
+}
+
+impl<'info> SyncReserves<'info> {
+    /// Sets the reserves to the vault balances less the owed protocol fees, absorbing any
+    /// tokens donated to the vaults into the pool.
+    pub fn sync(&mut self) -> Result<()> {
+        let mut state = self.pool_state.load_mut()?;
+        self.config.check_unlocked(&state)?;
+
+        state.update_price_cumulative(Clock::get()?.unix_timestamp);
+        state.reserve_x = self.vault_x.amount.saturating_sub(state.protocol_fees_x);
+        state.reserve_y = self.vault_y.amount.saturating_sub(state.protocol_fees_y);
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    states::{AmmConfig, Config, ConfigChange, PendingChange, PoolState},
+};
+
+#[derive(Accounts)]
+pub struct ProposeChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"amm_config"],
+        bump = amm_config.bump,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pending_change", config.key().as_ref()],
+        bump,
+        space = 8 + PendingChange::INIT_SPACE,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeChange<'info> {
+    /// Queues `change`, executable once the pool's timelock has passed. The change is
+    /// validated against the current config now, and again when it is executed.
+    pub fn propose(&mut self, change: ConfigChange, bumps: ProposeChangeBumps) -> Result<()> {
+        self.config.check_role(&self.authority.key(), change.role())?;
+        let now = Clock::get()?.unix_timestamp;
+        change.apply(&mut (*self.config).clone(), &self.amm_config, now)?;
+
+        self.pending_change.set_inner(PendingChange {
+            config: self.config.key(),
+            proposer: self.authority.key(),
+            change,
+            executable_ts: now
+                .checked_add(self.config.timelock_seconds)
+                .ok_or(AmmError::Overflow)?,
+            bump: bumps.pending_change,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    /// CHECK: rent refund destination, checked against the pending change
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"amm_config"],
+        bump = amm_config.bump,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = config,
+        has_one = proposer,
+        seeds = [b"pending_change", config.key().as_ref()],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+}
+
+impl<'info> ExecuteChange<'info> {
+    /// Applies the queued change once its timelock has passed. Anyone may execute it, as long
+    /// as the proposer still holds the role the change needs.
+    pub fn execute(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= self.pending_change.executable_ts,
+            AmmError::TimelockNotElapsed
+        );
+        let change = self.pending_change.change;
+        self.config.check_role(&self.pending_change.proposer, change.role())?;
+        change.apply(&mut self.config, &self.amm_config, now)?;
+        if let ConfigChange::DynamicFee(_) = change {
+            self.pool_state.load_mut()?.reset_volatility();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    pub authority: Signer<'info>,
+    /// CHECK: rent refund destination, checked against the pending change
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = config,
+        has_one = proposer,
+        seeds = [b"pending_change", config.key().as_ref()],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+}
+
+impl<'info> CancelChange<'info> {
+    /// Drops the queued change. Allowed to the admin and to the role that may propose it.
+    pub fn cancel(&mut self) -> Result<()> {
+        self.config.check_role(&self.authority.key(), self.pending_change.change.role())
+    }
+}
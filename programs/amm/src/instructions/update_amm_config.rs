@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, states::AmmConfig};
+
+#[derive(Accounts)]
+pub struct UpdateAmmConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"amm_config"],
+        bump = amm_config.bump,
+        has_one = admin @ AmmError::InvalidAuthority,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+impl<'info> UpdateAmmConfig<'info> {
+    /// Replaces the admin, fee recipient and fees. The protocol fee share and creation fee
+    /// apply to pools created from now on.
+    pub fn update(
+        &mut self,
+        admin: Pubkey,
+        fee_recipient: Pubkey,
+        protocol_fee_share: u16,
+        pool_creation_fee: u64,
+    ) -> Result<()> {
+        require!(protocol_fee_share <= 10_000, AmmError::InvalidFee);
+
+        let amm_config = &mut self.amm_config;
+        amm_config.admin = admin;
+        amm_config.fee_recipient = fee_recipient;
+        amm_config.protocol_fee_share = protocol_fee_share;
+        amm_config.pool_creation_fee = pool_creation_fee;
+        Ok(())
+    }
+}
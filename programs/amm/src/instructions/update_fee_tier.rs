@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_FEE_TIERS,
+    error::AmmError,
+    states::{AmmConfig, FeeTier},
+};
+
+#[derive(Accounts)]
+pub struct UpdateFeeTier<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"amm_config"],
+        bump = amm_config.bump,
+        has_one = admin @ AmmError::InvalidAuthority,
+    )]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+impl<'info> UpdateFeeTier<'info> {
+    /// Allows new pools to use `fee`, re-enabling the tier if it was disabled.
+    pub fn add(&mut self, fee: u16) -> Result<()> {
+        require!(fee < 10_000, AmmError::InvalidFee);
+
+        if let Some(tier) = self.amm_config.tier_mut(fee) {
+            tier.enabled = true;
+            return Ok(());
+        }
+        require!(
+            self.amm_config.fee_tiers.len() < MAX_FEE_TIERS,
+            AmmError::TooManyFeeTiers
+        );
+        self.amm_config.fee_tiers.push(FeeTier { fee, enabled: true });
+        Ok(())
+    }
+
+    /// Stops new pools from using `fee`; pools already created with it are unaffected.
+    pub fn disable(&mut self, fee: u16) -> Result<()> {
+        let tier = self.amm_config.tier_mut(fee).ok_or(AmmError::InvalidFeeTier)?;
+        tier.enabled = false;
+        Ok(())
+    }
+}
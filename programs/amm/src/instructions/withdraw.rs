@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{ burn, transfer, Burn, Mint, Token, TokenAccount, Transfer },
+};
+
+use crate::{ curves, error::AmmError, states::{ Config, PoolState } };
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub mint_x: Account<'info, Mint>,
+    pub mint_y: Account<'info, Mint>,
+    #[account(mut)]
+    pub mint_lp: Account<'info, Mint>,
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        has_one = vault_x,
+        has_one = vault_y,
+        has_one = mint_lp,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        has_one = config,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    #[account(mut)]
+    pub vault_x: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault_y: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+    )]
+    pub user_x: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+    )]
+    pub user_y: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+    )]
+    pub user_lp: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Withdraw<'info> {
+    pub fn withdraw(&mut self, amount: u64, min_x: u64, min_y: u64) -> Result<()> {
+        let mut state = self.pool_state.load_mut()?;
+        self.config.check_unlocked(&state)?;
+        require!(amount > 0, AmmError::InvalidAmount);
+        require!(self.user_lp.amount >= amount, AmmError::InsufficientBalance);
+        require!(self.mint_lp.supply >= amount, AmmError::InvalidAmount);
+
+        let (reserve_x, reserve_y) = (state.reserve_x, state.reserve_y);
+        let (x, y) = curves::withdraw_amounts(
+            &self.config,
+            reserve_x,
+            reserve_y,
+            self.mint_lp.supply,
+            amount,
+            curves::lp_precision(&self.mint_x, &self.mint_y, &self.mint_lp)
+        )?;
+        require!(x >= min_x && y >= min_y, AmmError::SlippageExceeded);
+        state.update_price_cumulative(Clock::get()?.unix_timestamp);
+        state.reserve_x = reserve_x.checked_sub(x).ok_or(AmmError::Underflow)?;
+        state.reserve_y = reserve_y.checked_sub(y).ok_or(AmmError::Underflow)?;
+        drop(state);
+
+        self.withdraw_tokens(true, x)?;
+        self.withdraw_tokens(false, y)?;
+        self.burn_lp_token(amount)
+    }
+
+    pub fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to) = match is_x {
+            true => (self.vault_x.to_account_info(), self.user_x.to_account_info()),
+            false => (self.vault_y.to_account_info(), self.user_y.to_account_info()),
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_account = Transfer {
+            from,
+            to,
+            authority: self.config.to_account_info(),
+        };
+        let seeds = &[&b"config"[..], &self.config.seed.to_le_bytes(), &[self.config.config_bump]];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(cpi_program, cpi_account, signer_seeds);
+        transfer(ctx, amount)
+    }
+
+    pub fn burn_lp_token(&self, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_account = Burn {
+            mint: self.mint_lp.to_account_info(),
+            from: self.user_lp.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(cpi_program, cpi_account);
+        burn(ctx, amount)
+    }
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+
+use crate::{curves::StableSwap, error::AmmError, states::MultiPool};
+
+/// Remaining accounts: the pool vaults in pool mint order, followed by the user's token
+/// accounts receiving the withdrawal (all of them, or just the one for `withdraw_one`).
+#[derive(Accounts)]
+pub struct WithdrawMulti<'info> {
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"multi_pool", pool.seed.to_le_bytes().as_ref()],
+        bump = pool.pool_bump,
+    )]
+    pub pool: Account<'info, MultiPool>,
+    #[account(
+        mut,
+        seeds = [b"multi_lp", pool.key().as_ref()],
+        bump = pool.lp_bump,
+    )]
+    pub mint_lp: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+    )]
+    pub user_lp: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawMulti<'info> {
+    /// Burns `amount` LP tokens for a pro-rata share of every pool token.
+    pub fn withdraw(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        amount: u64,
+        min_amounts: Vec<u64>,
+    ) -> Result<()> {
+        self.check_amount(amount)?;
+        require!(min_amounts.len() == self.pool.len(), AmmError::InvalidToken);
+
+        let (vaults, rest) = self.pool.load_vaults(&self.pool.key(), accounts)?;
+        require!(rest.len() == self.pool.len(), AmmError::InvalidToken);
+
+        let supply = self.mint_lp.supply as u128;
+        for (i, (vault, info)) in vaults.iter().zip(rest).enumerate() {
+            let to = self.pool.load_user_account(i, info)?;
+            let out = (self.pool.reserves[i] as u128 * amount as u128 / supply) as u64;
+            require!(out >= min_amounts[i], AmmError::SlippageExceeded);
+            self.pool.reserves[i] -= out;
+            self.pool.transfer_out(
+                self.pool.to_account_info(),
+                self.token_program.to_account_info(),
+                vault,
+                &to,
+                out,
+            )?;
+        }
+        self.burn_lp_tokens(amount)
+    }
+
+    /// Burns `amount` LP tokens for pool token `index` alone.
+    pub fn withdraw_one(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        amount: u64,
+        index: u8,
+        min_amount: u64,
+    ) -> Result<()> {
+        self.check_amount(amount)?;
+        let index = index as usize;
+        require!(index < self.pool.len(), AmmError::InvalidToken);
+
+        let (vaults, rest) = self.pool.load_vaults(&self.pool.key(), accounts)?;
+        require!(rest.len() == 1, AmmError::InvalidToken);
+        let to = self.pool.load_user_account(index, &rest[0])?;
+
+        let out = StableSwap::new(self.pool.amp).withdraw_one_amount(
+            &self.pool.balances()?,
+            amount as u128,
+            self.mint_lp.supply as u128,
+            index,
+            self.pool.fee,
+        )?;
+        let out = self.pool.unscale(index, out)?;
+        require!(out > 0, AmmError::InvalidAmount);
+        require!(out >= min_amount, AmmError::SlippageExceeded);
+        self.pool.reserves[index] -= out;
+
+        self.pool.transfer_out(
+            self.pool.to_account_info(),
+            self.token_program.to_account_info(),
+            &vaults[index],
+            &to,
+            out,
+        )?;
+        self.burn_lp_tokens(amount)
+    }
+
+    fn check_amount(&self, amount: u64) -> Result<()> {
+        require!(!self.pool.locked, AmmError::PoolLocked);
+        require!(amount > 0, AmmError::InvalidAmount);
+        require!(self.user_lp.amount >= amount, AmmError::InsufficientBalance);
+        Ok(())
+    }
+
+    fn burn_lp_tokens(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Burn {
+            mint: self.mint_lp.to_account_info(),
+            from: self.user_lp.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        burn(cpi_ctx, amount)
+    }
+}
@@ -1,8 +1,11 @@
 #![allow(deprecated)]
 #![allow(unexpected_cfgs)]
 pub mod constants;
+pub mod curves;
 pub mod error;
+pub mod events;
 pub mod instructions;
+pub mod math;
 pub mod states;
 
 use anchor_lang::prelude::*;
@@ -22,15 +25,350 @@ pub mod amm {
         seed: u64,
         fee: u16,
         authority: Option<Pubkey>,
+        lp_decimals: u8,
+        curve: CurveParams,
     ) -> Result<()> {
-        ctx.accounts.init(seed, fee, authority, ctx.bumps)
+        ctx.accounts.init(seed, fee, authority, lp_decimals, curve, ctx.bumps)
     }
 
     pub fn deposit(ctx: Context<Deposit>, amount: u64, max_x: u64, max_y: u64) -> Result<()> {
         ctx.accounts.deposit(amount, max_x, max_y)
     }
 
-    pub fn swap(ctx: Context<Swap>, x_to_y: bool, amount_in: u64, slippage: u16) -> Result<()> {
-        ctx.accounts.swap(x_to_y, amount_in, slippage)
+    pub fn swap(
+        ctx: Context<Swap>,
+        x_to_y: bool,
+        amount_in: u64,
+        slippage: u16,
+        referral_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.swap(x_to_y, amount_in, slippage, referral_fee_bps)
+    }
+
+    pub fn swap_lean(
+        ctx: Context<SwapLean>,
+        x_to_y: bool,
+        amount_in: u64,
+        slippage: u16,
+        referral_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.swap(x_to_y, amount_in, slippage, referral_fee_bps)
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64, min_x: u64, min_y: u64) -> Result<()> {
+        ctx.accounts.withdraw(amount, min_x, min_y)
+    }
+
+    pub fn ramp_amp(ctx: Context<RampAmp>, target_amp: u64, stop_ts: i64) -> Result<()> {
+        ctx.accounts.ramp(target_amp, stop_ts)
+    }
+
+    pub fn stop_ramp_amp(ctx: Context<RampAmp>) -> Result<()> {
+        ctx.accounts.stop()
+    }
+
+    pub fn set_dynamic_fee(ctx: Context<SetDynamicFee>, params: DynamicFeeParams) -> Result<()> {
+        ctx.accounts.set(params)
+    }
+
+    pub fn initialize_concentrated(
+        ctx: Context<InitializeConcentrated>,
+        tick_spacing: u16,
+        initial_sqrt_price: u128,
+    ) -> Result<()> {
+        ctx.accounts.init(tick_spacing, initial_sqrt_price, ctx.bumps)
+    }
+
+    pub fn initialize_tick_array(ctx: Context<InitializeTickArray>, start_tick_index: i32) -> Result<()> {
+        ctx.accounts.init(start_tick_index)
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>, tick_lower: i32, tick_upper: i32) -> Result<()> {
+        ctx.accounts.open(tick_lower, tick_upper, ctx.bumps)
+    }
+
+    pub fn increase_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        liquidity: u128,
+        max_x: u64,
+        max_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.increase(liquidity, max_x, max_y)
+    }
+
+    pub fn decrease_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        liquidity: u128,
+        min_x: u64,
+        min_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.decrease(liquidity, min_x, min_y)
+    }
+
+    pub fn collect_fees(ctx: Context<ModifyLiquidity>) -> Result<()> {
+        ctx.accounts.collect_fees()
+    }
+
+    pub fn swap_concentrated<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapConcentrated<'info>>,
+        x_to_y: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+        sqrt_price_limit: u128,
+    ) -> Result<()> {
+        ctx.accounts.swap(ctx.remaining_accounts, x_to_y, amount_in, min_amount_out, sqrt_price_limit)
+    }
+
+    pub fn initialize_multi_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitializeMultiPool<'info>>,
+        seed: u64,
+        fee: u16,
+        authority: Option<Pubkey>,
+        amp: u64,
+        lp_decimals: u8,
+    ) -> Result<()> {
+        ctx.accounts.init(seed, fee, authority, amp, lp_decimals, ctx.bumps)?;
+        ctx.accounts.add_tokens(ctx.remaining_accounts)
+    }
+
+    pub fn deposit_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositMulti<'info>>,
+        amounts: Vec<u64>,
+        min_lp: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit(ctx.remaining_accounts, amounts, min_lp)
+    }
+
+    pub fn withdraw_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawMulti<'info>>,
+        amount: u64,
+        min_amounts: Vec<u64>,
+    ) -> Result<()> {
+        ctx.accounts.withdraw(ctx.remaining_accounts, amount, min_amounts)
+    }
+
+    pub fn withdraw_multi_one<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawMulti<'info>>,
+        amount: u64,
+        index: u8,
+        min_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_one(ctx.remaining_accounts, amount, index, min_amount)
+    }
+
+    pub fn swap_multi<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapMulti<'info>>,
+        i: u8,
+        j: u8,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.swap(ctx.remaining_accounts, i, j, amount_in, min_amount_out)
+    }
+
+    pub fn create_reward_pool(
+        ctx: Context<CreateRewardPool>,
+        emission_per_second: u64,
+        end_ts: i64,
+    ) -> Result<()> {
+        ctx.accounts.create(emission_per_second, end_ts, ctx.bumps)
+    }
+
+    pub fn stake_lp(ctx: Context<Farm>, amount: u64) -> Result<()> {
+        ctx.accounts.stake(amount, ctx.bumps)
+    }
+
+    pub fn unstake_lp(ctx: Context<Farm>, amount: u64) -> Result<()> {
+        ctx.accounts.unstake(amount, ctx.bumps)
+    }
+
+    pub fn claim_rewards(ctx: Context<Farm>) -> Result<()> {
+        ctx.accounts.claim(ctx.bumps)
+    }
+
+    pub fn reclaim_rewards(ctx: Context<ReclaimRewards>) -> Result<()> {
+        ctx.accounts.reclaim()
+    }
+
+    pub fn initialize_amm_config(
+        ctx: Context<InitializeAmmConfig>,
+        fee_recipient: Pubkey,
+        protocol_fee_share: u16,
+        pool_creation_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .init(fee_recipient, protocol_fee_share, pool_creation_fee, ctx.bumps)
+    }
+
+    pub fn update_amm_config(
+        ctx: Context<UpdateAmmConfig>,
+        admin: Pubkey,
+        fee_recipient: Pubkey,
+        protocol_fee_share: u16,
+        pool_creation_fee: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .update(admin, fee_recipient, protocol_fee_share, pool_creation_fee)
+    }
+
+    pub fn add_fee_tier(ctx: Context<UpdateFeeTier>, fee: u16) -> Result<()> {
+        ctx.accounts.add(fee)
+    }
+
+    pub fn disable_fee_tier(ctx: Context<UpdateFeeTier>, fee: u16) -> Result<()> {
+        ctx.accounts.disable(fee)
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        ctx.accounts.collect()
+    }
+
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        ctx.accounts.close()
+    }
+
+    pub fn sync_reserves(ctx: Context<SyncReserves>) -> Result<()> {
+        ctx.accounts.sync()
+    }
+
+    pub fn skim(ctx: Context<Skim>) -> Result<()> {
+        ctx.accounts.skim()
+    }
+
+    pub fn set_circuit_breaker(
+        ctx: Context<SetCircuitBreaker>,
+        max_price_impact_bps: u16,
+        max_slot_price_move_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set(max_price_impact_bps, max_slot_price_move_bps)
+    }
+
+    pub fn set_locked(ctx: Context<SetCircuitBreaker>, locked: bool) -> Result<()> {
+        ctx.accounts.set_locked(locked)
+    }
+
+    pub fn set_oracle(
+        ctx: Context<SetOracle>,
+        oracle: Option<Pubkey>,
+        params: OracleParams,
+    ) -> Result<()> {
+        ctx.accounts.set(oracle, params)
+    }
+
+    pub fn set_volume_limits(ctx: Context<SetVolumeLimits>, limits: VolumeLimits) -> Result<()> {
+        ctx.accounts.set(limits)
+    }
+
+    pub fn init_wallet_volume(ctx: Context<InitWalletVolume>) -> Result<()> {
+        ctx.accounts.init(ctx.bumps)
+    }
+
+    pub fn set_access_mode(ctx: Context<SetAccessMode>, access_mode: AccessMode) -> Result<()> {
+        ctx.accounts.set(access_mode)
+    }
+
+    pub fn add_allow_entry(ctx: Context<AddAllowEntry>, wallet: Pubkey) -> Result<()> {
+        ctx.accounts.add(wallet, ctx.bumps)
+    }
+
+    pub fn remove_allow_entry(ctx: Context<RemoveAllowEntry>) -> Result<()> {
+        ctx.accounts.remove()
+    }
+
+    pub fn set_referral_fee(ctx: Context<SetReferralFee>, max_referral_fee_bps: u16) -> Result<()> {
+        ctx.accounts.set(max_referral_fee_bps)
+    }
+
+    pub fn set_fee(ctx: Context<SetFee>, fee: u16) -> Result<()> {
+        ctx.accounts.set(fee)
+    }
+
+    pub fn set_role(ctx: Context<SetRole>, role: Role, holder: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.set(role, holder)
+    }
+
+    pub fn propose_change(ctx: Context<ProposeChange>, change: ConfigChange) -> Result<()> {
+        ctx.accounts.propose(change, ctx.bumps)
+    }
+
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        ctx.accounts.execute()
+    }
+
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        ctx.accounts.migrate()
+    }
+
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        id: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+        tip: u64,
+    ) -> Result<()> {
+        ctx.accounts.place(id, amount_in, min_amount_out, tip, ctx.bumps)
+    }
+
+    pub fn fill_order(ctx: Context<FillOrder>) -> Result<()> {
+        ctx.accounts.fill()
+    }
+
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    pub fn create_dca(
+        ctx: Context<CreateDca>,
+        id: u64,
+        amount: u64,
+        amount_per_cycle: u64,
+        interval: i64,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.create(id, amount, amount_per_cycle, interval, max_slippage_bps, ctx.bumps)
+    }
+
+    pub fn execute_dca(ctx: Context<ExecuteDca>) -> Result<()> {
+        ctx.accounts.execute()
+    }
+
+    pub fn withdraw_dca(ctx: Context<WithdrawDca>) -> Result<()> {
+        ctx.accounts.withdraw()
+    }
+
+    pub fn close_dca(ctx: Context<CloseDca>) -> Result<()> {
+        ctx.accounts.close()
+    }
+
+    pub fn set_batch_mode(
+        ctx: Context<SetBatchMode>,
+        batch_window_slots: u64,
+        min_intent_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.set(batch_window_slots, min_intent_amount)
+    }
+
+    pub fn submit_intent(
+        ctx: Context<SubmitIntent>,
+        batch_id: u64,
+        x_to_y: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.submit(batch_id, x_to_y, amount_in, min_amount_out, ctx.bumps)
+    }
+
+    pub fn settle_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleBatch<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.settle(ctx.remaining_accounts)
+    }
+
+    pub fn claim_intent(ctx: Context<ClaimIntent>) -> Result<()> {
+        ctx.accounts.claim()
     }
 }
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+use crate::error::AmmError;
+
+/// Fixed-point scale (18 decimals) used by the weighted-curve math.
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+const WAD_I: i128 = WAD as i128;
+/// ln(2) scaled by `WAD`.
+const LN_2: i128 = 693_147_180_559_945_309;
+/// Series terms used by `ln` and `exp`; enough for 18 decimals on the reduced ranges.
+const SERIES_TERMS: i128 = 32;
+
+/// `a · b / WAD`, rounded down.
+pub fn mul_wad(a: u128, b: u128) -> Result<u128> {
+    a.checked_mul(b)
+        .map(|v| v / WAD)
+        .ok_or(AmmError::Overflow.into())
+}
+
+/// `a · WAD / b`, rounded down.
+pub fn div_wad(a: u128, b: u128) -> Result<u128> {
+    require!(b != 0, AmmError::ZeroBalance);
+    a.checked_mul(WAD)
+        .map(|v| v / b)
+        .ok_or(AmmError::Overflow.into())
+}
+
+/// Natural logarithm of a positive `WAD` value.
+pub fn ln(x: u128) -> Result<i128> {
+    require!(x > 0, AmmError::InvalidAmount);
+
+    // x = m · 2^k with m in [1, 2)
+    let mut k: i128 = 0;
+    let mut m = x;
+    while m >= 2 * WAD {
+        m /= 2;
+        k += 1;
+    }
+    while m < WAD {
+        m *= 2;
+        k -= 1;
+    }
+
+    // ln(m) = 2·atanh(z) = 2·(z + z³/3 + z⁵/5 + …) with z = (m - 1) / (m + 1) ≤ 1/3
+    let m = m as i128;
+    let z = ((m - WAD_I) * WAD_I) / (m + WAD_I);
+    let z_squared = (z * z) / WAD_I;
+    let mut term = z;
+    let mut sum = z;
+    let mut n = 3;
+    while n < 2 * SERIES_TERMS {
+        term = (term * z_squared) / WAD_I;
+        if term == 0 {
+            break;
+        }
+        sum += term / n;
+        n += 2;
+    }
+
+    Ok(k * LN_2 + 2 * sum)
+}
+
+/// `e^x` for a non-positive `WAD` exponent; results below 1e-18 round to zero.
+pub fn exp_neg(x: i128) -> Result<u128> {
+    require!(x <= 0, AmmError::InvalidAmount);
+
+    // x = -k·ln(2) + r with r in (-ln(2), 0]
+    let k = (-x) / LN_2;
+    if k >= 64 {
+        return Ok(0);
+    }
+    let r = x + k * LN_2;
+
+    // e^r = Σ rⁿ / n!
+    let mut term = WAD_I;
+    let mut sum = WAD_I;
+    for n in 1..SERIES_TERMS {
+        term = (term * r) / (WAD_I * n);
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+
+    Ok((sum as u128) >> k)
+}
+
+/// `base^exponent` for a `base` in (0, 1] and a non-negative `exponent`, both in `WAD`.
+pub fn pow_frac(base: u128, exponent: u128) -> Result<u128> {
+    require!(base <= WAD, AmmError::InvalidAmount);
+    if base == WAD || exponent == 0 {
+        return Ok(WAD);
+    }
+    if base == 0 {
+        return Ok(0);
+    }
+    let exponent = i128::try_from(exponent).map_err(|_| AmmError::Overflow)?;
+    let log = ln(base)?;
+    // Split the exponent so `log · exponent` stays within i128
+    let whole = log
+        .checked_mul(exponent / WAD_I)
+        .ok_or(AmmError::Overflow)?;
+    let fraction = log
+        .checked_mul(exponent % WAD_I)
+        .ok_or(AmmError::Overflow)?
+        / WAD_I;
+    exp_neg(whole.checked_add(fraction).ok_or(AmmError::Overflow)?)
+}
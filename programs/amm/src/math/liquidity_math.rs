@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    math::{get_amount_x_delta, get_amount_y_delta, to_u64},
+};
+
+/// Applies a signed liquidity change.
+pub fn add_liquidity_delta(liquidity: u128, delta: i128) -> Result<u128> {
+    if delta >= 0 {
+        liquidity
+            .checked_add(delta.unsigned_abs())
+            .ok_or(AmmError::Overflow.into())
+    } else {
+        liquidity
+            .checked_sub(delta.unsigned_abs())
+            .ok_or(AmmError::Underflow.into())
+    }
+}
+
+/// Token amounts backing `liquidity` over `[sqrt_price_lower, sqrt_price_upper)` at the
+/// current sqrt price. Rounded up when depositing and down when withdrawing.
+pub fn amounts_for_liquidity(
+    sqrt_price: u128,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<(u64, u64)> {
+    let (x, y) = if sqrt_price < sqrt_price_lower {
+        (
+            get_amount_x_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+            0.into(),
+        )
+    } else if sqrt_price < sqrt_price_upper {
+        (
+            get_amount_x_delta(sqrt_price, sqrt_price_upper, liquidity, round_up)?,
+            get_amount_y_delta(sqrt_price_lower, sqrt_price, liquidity, round_up)?,
+        )
+    } else {
+        (
+            0.into(),
+            get_amount_y_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+        )
+    };
+    Ok((to_u64(x)?, to_u64(y)?))
+}
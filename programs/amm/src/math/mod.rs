@@ -0,0 +1,17 @@
+pub mod fixed_point;
+pub use fixed_point::*;
+
+pub mod liquidity_math;
+pub use liquidity_math::*;
+
+pub mod sqrt_price_math;
+pub use sqrt_price_math::*;
+
+pub mod swap_math;
+pub use swap_math::*;
+
+pub mod tick_math;
+pub use tick_math::*;
+
+pub mod u256;
+pub use u256::*;
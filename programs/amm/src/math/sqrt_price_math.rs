@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, math::U256};
+
+/// `Δx = L · (√b − √a) · 2^64 / (√a · √b)` between two Q64.64 sqrt prices.
+pub fn get_amount_x_delta(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256> {
+    let (lower, upper) = sorted(sqrt_price_a, sqrt_price_b);
+    require!(lower > 0, AmmError::InvalidSqrtPrice);
+
+    let numerator_1 = U256::from(liquidity) << 64;
+    let numerator_2 = U256::from(upper - lower);
+    if round_up {
+        let amount = mul_div_round_up(numerator_1, numerator_2, U256::from(upper))?;
+        Ok(div_round_up(amount, U256::from(lower)))
+    } else {
+        let amount = mul_div(numerator_1, numerator_2, U256::from(upper))?;
+        Ok(amount / U256::from(lower))
+    }
+}
+
+/// `Δy = L · (√b − √a) / 2^64` between two Q64.64 sqrt prices.
+pub fn get_amount_y_delta(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256> {
+    let (lower, upper) = sorted(sqrt_price_a, sqrt_price_b);
+    let product = U256::from(liquidity) * U256::from(upper - lower);
+    let amount = product >> 64;
+    let remainder = product & ((U256::one() << 64) - 1);
+    Ok(if round_up && !remainder.is_zero() {
+        amount + 1
+    } else {
+        amount
+    })
+}
+
+/// Sqrt price after `amount` of X is added at `liquidity`; rounded up so the price never
+/// moves further than the input pays for.
+pub fn get_next_sqrt_price_from_x_in(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+) -> Result<u128> {
+    if amount == 0 {
+        return Ok(sqrt_price);
+    }
+    let numerator = U256::from(liquidity) << 64;
+    let denominator = numerator + U256::from(amount) * U256::from(sqrt_price);
+    to_u128(mul_div_round_up(numerator, U256::from(sqrt_price), denominator)?)
+}
+
+/// Sqrt price after `amount` of Y is added at `liquidity`; rounded down.
+pub fn get_next_sqrt_price_from_y_in(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u64,
+) -> Result<u128> {
+    require!(liquidity > 0, AmmError::NoLiquidityInPool);
+    let quotient = (U256::from(amount) << 64) / U256::from(liquidity);
+    to_u128(U256::from(sqrt_price) + quotient)
+}
+
+pub fn to_u64(value: U256) -> Result<u64> {
+    require!(value <= U256::from(u64::MAX), AmmError::Overflow);
+    Ok(value.as_u64())
+}
+
+pub fn to_u128(value: U256) -> Result<u128> {
+    require!(value <= U256::from(u128::MAX), AmmError::Overflow);
+    Ok(value.as_u128())
+}
+
+fn sorted(a: u128, b: u128) -> (u128, u128) {
+    if a > b {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    require!(!denominator.is_zero(), AmmError::Overflow);
+    a.checked_mul(b)
+        .map(|product| product / denominator)
+        .ok_or(AmmError::Overflow.into())
+}
+
+fn mul_div_round_up(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    require!(!denominator.is_zero(), AmmError::Overflow);
+    a.checked_mul(b)
+        .map(|product| div_round_up(product, denominator))
+        .ok_or(AmmError::Overflow.into())
+}
+
+fn div_round_up(a: U256, b: U256) -> U256 {
+    let quotient = a / b;
+    if (a % b).is_zero() {
+        quotient
+    } else {
+        quotient + 1
+    }
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::math::{
+    get_amount_x_delta, get_amount_y_delta, get_next_sqrt_price_from_x_in,
+    get_next_sqrt_price_from_y_in, to_u64, U256,
+};
+
+/// Result of swapping within a single tick range.
+#[derive(Clone, Copy, Debug)]
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// Exact-input swap of up to `amount_remaining` (fee inclusive) from `sqrt_price_current`
+/// towards `sqrt_price_target` at constant `liquidity`.
+pub fn compute_swap_step(
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_bps: u16,
+    x_to_y: bool,
+) -> Result<SwapStep> {
+    let amount_remaining_less_fee =
+        ((amount_remaining as u128) * (10_000 - fee_bps as u128) / 10_000) as u64;
+
+    let amount_in_to_target = if x_to_y {
+        get_amount_x_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?
+    } else {
+        get_amount_y_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?
+    };
+
+    let reaches_target = U256::from(amount_remaining_less_fee) >= amount_in_to_target;
+    let sqrt_price_next = if reaches_target {
+        sqrt_price_target
+    } else if x_to_y {
+        get_next_sqrt_price_from_x_in(sqrt_price_current, liquidity, amount_remaining_less_fee)?
+    } else {
+        get_next_sqrt_price_from_y_in(sqrt_price_current, liquidity, amount_remaining_less_fee)?
+    };
+
+    let (amount_in, amount_out) = if x_to_y {
+        (
+            get_amount_x_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?,
+            get_amount_y_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?,
+        )
+    } else {
+        (
+            get_amount_y_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?,
+            get_amount_x_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?,
+        )
+    };
+    let amount_in = to_u64(amount_in)?;
+    let amount_out = to_u64(amount_out)?;
+
+    // Whatever input is left over when the step stops short of the target is all fee
+    let fee_amount = if !reaches_target {
+        amount_remaining - amount_in
+    } else {
+        let fee = (amount_in as u128) * (fee_bps as u128);
+        fee.div_ceil(10_000 - fee_bps as u128) as u64
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
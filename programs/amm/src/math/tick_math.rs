@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, math::U256};
+
+/// Tick bounds; prices are `1.0001^tick` and sqrt prices are Q64.64.
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_017;
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_062;
+
+/// `2^128 / sqrt(1.0001)^(2^i)`, rounded up, for `i` in `0..20`.
+const SQRT_RATIOS_X128: [u128; 20] = [
+    0xfffcb933bd6fad37aa2d162d1a594001,
+    0xfff97272373d413259a46990580e213a,
+    0xfff2e50f5f656932ef12357cf3c7fdcc,
+    0xffe5caca7e10e4e61c3624eaa0941cd0,
+    0xffcb9843d60f6159c9db58835c926644,
+    0xff973b41fa98c081472e6896dfb254c0,
+    0xff2ea16466c96a3843ec78b326b52861,
+    0xfe5dee046a99a2a811c461f1969c3053,
+    0xfcbe86c7900a88aedcffc83b479aa3a4,
+    0xf987a7253ac413176f2b074cf7815e54,
+    0xf3392b0822b70005940c7a398e4b70f3,
+    0xe7159475a2c29b7443b29c7fa6e889d9,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e5,
+    0x70d869a156d2a1b890bb3df62baf32f7,
+    0x31be135f97d08fd981231505542fcfa6,
+    0x9aa508b5b7a84e1c677de54f3e99bc9,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe98,
+    0x48a170391f7dc42444e8fa2,
+];
+
+/// `1 / log2(sqrt(1.0001))` as a Q32 fixed-point number.
+const TICKS_PER_LOG2_X32: i128 = 59_543_866_431_248;
+/// Fractional bits of `log2` computed by `tick_from_sqrt_price`.
+const LOG2_PRECISION_BITS: u32 = 24;
+
+/// `sqrt(1.0001^tick)` as Q64.64, rounded up.
+pub fn sqrt_price_from_tick(tick: i32) -> Result<u128> {
+    require!((MIN_TICK..=MAX_TICK).contains(&tick), AmmError::InvalidTick);
+
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = U256::one() << 128;
+    for (i, sqrt_ratio) in SQRT_RATIOS_X128.iter().enumerate() {
+        if abs_tick & (1 << i) != 0 {
+            ratio = (ratio * U256::from(*sqrt_ratio)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // Q128.128 -> Q64.64
+    let remainder = ratio & ((U256::one() << 64) - 1);
+    let sqrt_price = (ratio >> 64) + if remainder.is_zero() { 0 } else { 1 };
+    Ok(sqrt_price.as_u128())
+}
+
+/// Greatest tick whose sqrt price is at or below `sqrt_price_x64`.
+pub fn tick_from_sqrt_price(sqrt_price_x64: u128) -> Result<i32> {
+    require!(
+        (MIN_SQRT_PRICE_X64..=MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64),
+        AmmError::InvalidSqrtPrice
+    );
+
+    // log2(sqrt price) as Q64.64: integer part from the most significant bit, fraction by
+    // repeatedly squaring the mantissa normalised to [1, 2) as Q63
+    let msb = 127 - sqrt_price_x64.leading_zeros();
+    let mut r = if msb >= 63 {
+        sqrt_price_x64 >> (msb - 63)
+    } else {
+        sqrt_price_x64 << (63 - msb)
+    };
+    let mut log2_x64 = (msb as i128 - 64) << 64;
+    for bit in (64 - LOG2_PRECISION_BITS..64).rev() {
+        r = (r * r) >> 63;
+        let f = r >> 64;
+        log2_x64 |= (f as i128) << bit;
+        r >>= f;
+    }
+
+    // The estimate is within one tick of the answer; settle it against the exact prices
+    let estimate = ((log2_x64 * TICKS_PER_LOG2_X32) >> 96) as i32;
+    let tick = estimate.clamp(MIN_TICK, MAX_TICK);
+    if tick < MAX_TICK && sqrt_price_from_tick(tick + 1)? <= sqrt_price_x64 {
+        Ok(tick + 1)
+    } else if sqrt_price_from_tick(tick)? > sqrt_price_x64 {
+        Ok(tick - 1)
+    } else {
+        Ok(tick)
+    }
+}
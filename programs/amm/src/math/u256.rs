@@ -0,0 +1,10 @@
+#![allow(clippy::assign_op_pattern)]
+#![allow(clippy::manual_div_ceil)]
+#![allow(clippy::ptr_offset_with_cast)]
+
+use uint::construct_uint;
+
+construct_uint! {
+    /// 256-bit unsigned integer used for intermediate curve math that overflows `u128`.
+    pub struct U256(4);
+}
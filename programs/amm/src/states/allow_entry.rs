@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, states::Config};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum AccessMode {
+    #[default]
+    Open,
+    /// Only wallets with an `AllowEntry` may deposit or swap; withdrawals stay open.
+    Permissioned,
+}
+
+/// Marks `wallet` as allowed to use a permissioned pool, at `[b"allow", config, wallet]`.
+#[account]
+#[derive(InitSpace)]
+pub struct AllowEntry {
+    pub config: Pubkey,
+    pub wallet: Pubkey,
+    pub bump: u8,
+}
+
+impl Config {
+    /// Rejects users without an allowlist entry when the pool is permissioned. The entry's
+    /// address is checked by the caller's account constraints.
+    pub fn check_access(&self, entry: Option<&AllowEntry>) -> Result<()> {
+        if self.access_mode == AccessMode::Permissioned {
+            require!(entry.is_some(), AmmError::NotAllowlisted);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_FEE_TIERS;
+
+/// A swap fee, in basis points, that new pools may be created with.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct FeeTier {
+    pub fee: u16,
+    pub enabled: bool,
+}
+
+/// Protocol-wide settings every `initialize` is checked against.
+#[account]
+#[derive(InitSpace)]
+pub struct AmmConfig {
+    pub admin: Pubkey,
+    /// Receives pool-creation fees and collected protocol fees.
+    pub fee_recipient: Pubkey,
+    /// Share of each swap fee, in basis points, kept for the protocol by pools created from now on.
+    pub protocol_fee_share: u16,
+    /// Lamports charged to the initializer of a new pool.
+    pub pool_creation_fee: u64,
+    #[max_len(MAX_FEE_TIERS)]
+    pub fee_tiers: Vec<FeeTier>,
+    pub bump: u8,
+}
+
+impl AmmConfig {
+    pub fn is_enabled_tier(&self, fee: u16) -> bool {
+        self.fee_tiers.iter().any(|tier| tier.fee == fee && tier.enabled)
+    }
+
+    pub fn tier_mut(&mut self, fee: u16) -> Option<&mut FeeTier> {
+        self.fee_tiers.iter_mut().find(|tier| tier.fee == fee)
+    }
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::BATCH_SETTLEMENT_SLOTS,
+    math::{to_u64, U256},
+};
+
+/// Swap intents submitted during one window of `Config::batch_window_slots` slots, at
+/// `[b"batch", config, id]` where `id` is the submission slot divided by the window. The
+/// inputs sit in `escrow_x` and `escrow_y`, the batch's ATAs, until `settle_batch` clears
+/// them at a single price and each intent claims its share. A batch left unsettled past its
+/// deadline refunds every intent.
+#[account]
+#[derive(InitSpace)]
+pub struct Batch {
+    pub config: Pubkey,
+    pub id: u64,
+    /// First slot the batch may be settled in; intents are accepted before it.
+    pub end_slot: u64,
+    pub escrow_x: Pubkey,
+    pub escrow_y: Pubkey,
+    pub intent_count: u32,
+    /// Inputs submitted on each side.
+    pub amount_x_in: u64,
+    pub amount_y_in: u64,
+    pub settled: bool,
+    pub bump: u8,
+}
+
+impl Batch {
+    /// First slot the batch can no longer be settled in, from which its intents are refunded.
+    pub fn settle_deadline(&self) -> u64 {
+        self.end_slot.saturating_add(BATCH_SETTLEMENT_SLOTS)
+    }
+
+    /// The trade with the pool that clears `amount_x_in` X sold against `amount_y_in` Y sold
+    /// at one price: `Some((x_to_y, amount))` of the heavier side, or `None` when the two sides
+    /// meet within the fee and match against each other alone.
+    ///
+    /// On a constant-product pool, `a` X sold with fee factor `g = 1 − fee / 10 000` averages
+    /// `g·y / (x + g·a)` Y per X. Setting that equal to the price the X sellers get, the Y
+    /// sold plus the pool's output over the X sold, gives `a = (g·sx·y − sy·x) / (g·(y + sy))`.
+    /// The Y sellers receive the X not sold to the pool, `sx − a`, at the same price.
+    pub fn net_trade(
+        amount_x_in: u64,
+        amount_y_in: u64,
+        reserve_x: u64,
+        reserve_y: u64,
+        fee: u16,
+    ) -> Result<Option<(bool, u64)>> {
+        let g = U256::from(10_000 - fee as u64);
+        let bps = U256::from(10_000u64);
+        let (sx, sy) = (U256::from(amount_x_in), U256::from(amount_y_in));
+        let (x, y) = (U256::from(reserve_x), U256::from(reserve_y));
+
+        let (x_weight, y_weight) = (g * sx * y, bps * sy * x);
+        if x_weight > y_weight {
+            let amount = (x_weight - y_weight) / (g * (y + sy));
+            return Ok(Some((true, to_u64(amount)?)));
+        }
+        let (y_weight, x_weight) = (g * sy * x, bps * sx * y);
+        if y_weight > x_weight {
+            let amount = (y_weight - x_weight) / (g * (x + sx));
+            return Ok(Some((false, to_u64(amount)?)));
+        }
+        Ok(None)
+    }
+}
+
+/// One owner's swap in a batch, at `[b"intent", batch, owner]`. `amount_out` is written at
+/// settlement; an intent whose `min_amount_out` the clearing price misses is left out of the
+/// batch with `amount_out` 0 and refunded its input, as is every intent of an expired batch.
+#[account]
+#[derive(InitSpace)]
+pub struct SwapIntent {
+    pub batch: Pubkey,
+    pub owner: Pubkey,
+    pub x_to_y: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub amount_out: u64,
+    pub bump: u8,
+}
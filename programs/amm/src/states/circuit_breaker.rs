@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, states::{price_move_bps, Config, PoolState}};
+
+impl Config {
+    /// Rejects a swap moving the reserve price from `before` to `after` by more than
+    /// `max_price_impact_bps`, and trips the breaker once the moves within `slot` add up to
+    /// more than `max_slot_price_move_bps`. The swap that trips it still settles; the lock
+    /// would be rolled back with it otherwise.
+    pub fn check_price_impact(
+        &self,
+        state: &mut PoolState,
+        slot: u64,
+        before: u128,
+        after: u128,
+    ) -> Result<()> {
+        let price_move = price_move_bps(before, after);
+        if self.max_price_impact_bps > 0 {
+            require!(
+                price_move <= self.max_price_impact_bps as u128,
+                AmmError::PriceImpactExceeded
+            );
+        }
+
+        if self.max_slot_price_move_bps > 0 {
+            if slot != state.price_move_slot {
+                state.price_move_slot = slot;
+                state.slot_price_move_bps = 0;
+            }
+            state.slot_price_move_bps = (state.slot_price_move_bps as u128 + price_move)
+                .min(u32::MAX as u128) as u32;
+            if state.slot_price_move_bps > self.max_slot_price_move_bps as u32 {
+                state.breaker_tripped = 1;
+            }
+        }
+        Ok(())
+    }
+}
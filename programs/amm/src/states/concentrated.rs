@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::TICK_ARRAY_SIZE,
+    error::AmmError,
+    math::{add_liquidity_delta, to_u64, MAX_TICK, MIN_TICK, U256},
+};
+
+/// Concentrated-liquidity state of a pool created with `CurveType::Concentrated`. Tokens
+/// stay in the config-owned vaults; this account only tracks price and liquidity.
+#[account]
+#[derive(InitSpace)]
+pub struct ConcentratedPool {
+    pub config: Pubkey,
+    pub tick_spacing: u16,
+    /// Current sqrt price as Q64.64.
+    pub sqrt_price: u128,
+    pub tick_current: i32,
+    /// Liquidity of the positions whose range contains the current price.
+    pub liquidity: u128,
+    /// Fees collected per unit of liquidity over the life of the pool, Q64.64.
+    pub fee_growth_global_x: u128,
+    pub fee_growth_global_y: u128,
+    pub bump: u8,
+}
+
+impl ConcentratedPool {
+    /// Fee growth per unit of liquidity inside `[tick_lower, tick_upper)`, Q64.64.
+    pub fn fee_growth_inside(
+        &self,
+        tick_lower: i32,
+        lower: &Tick,
+        tick_upper: i32,
+        upper: &Tick,
+    ) -> (u128, u128) {
+        let inside = |global: u128, lower_outside: u128, upper_outside: u128| {
+            let below = if self.tick_current >= tick_lower {
+                lower_outside
+            } else {
+                global.wrapping_sub(lower_outside)
+            };
+            let above = if self.tick_current < tick_upper {
+                upper_outside
+            } else {
+                global.wrapping_sub(upper_outside)
+            };
+            global.wrapping_sub(below).wrapping_sub(above)
+        };
+        (
+            inside(
+                self.fee_growth_global_x,
+                lower.fee_growth_outside_x,
+                upper.fee_growth_outside_x,
+            ),
+            inside(
+                self.fee_growth_global_y,
+                lower.fee_growth_outside_y,
+                upper.fee_growth_outside_y,
+            ),
+        )
+    }
+}
+
+#[zero_copy]
+#[derive(Default)]
+pub struct Tick {
+    /// Liquidity added when the price crosses this tick upwards (removed downwards).
+    pub liquidity_net: i128,
+    /// Total liquidity of the positions referencing this tick.
+    pub liquidity_gross: u128,
+    /// Fee growth on the other side of this tick from the current price, Q64.64.
+    pub fee_growth_outside_x: u128,
+    pub fee_growth_outside_y: u128,
+    pub initialized: u8,
+    pub _padding: [u8; 15],
+}
+
+impl Tick {
+    /// Applies a position's liquidity change to this tick; `upper` is set when the tick is
+    /// the position's upper bound.
+    pub fn update(
+        &mut self,
+        tick: i32,
+        pool: &ConcentratedPool,
+        liquidity_delta: i128,
+        upper: bool,
+    ) -> Result<()> {
+        let liquidity_gross = add_liquidity_delta(self.liquidity_gross, liquidity_delta)?;
+        if liquidity_gross == 0 {
+            *self = Tick::default();
+            return Ok(());
+        }
+
+        if self.liquidity_gross == 0 {
+            // By convention all growth before the tick was initialized happened below it
+            if tick <= pool.tick_current {
+                self.fee_growth_outside_x = pool.fee_growth_global_x;
+                self.fee_growth_outside_y = pool.fee_growth_global_y;
+            }
+            self.initialized = 1;
+        }
+
+        self.liquidity_gross = liquidity_gross;
+        self.liquidity_net = if upper {
+            self.liquidity_net.checked_sub(liquidity_delta)
+        } else {
+            self.liquidity_net.checked_add(liquidity_delta)
+        }
+        .ok_or(AmmError::Overflow)?;
+        Ok(())
+    }
+
+    /// Flips the outside fee growth as the price crosses this tick and returns its net
+    /// liquidity.
+    pub fn cross(&mut self, fee_growth_global_x: u128, fee_growth_global_y: u128) -> i128 {
+        self.fee_growth_outside_x = fee_growth_global_x.wrapping_sub(self.fee_growth_outside_x);
+        self.fee_growth_outside_y = fee_growth_global_y.wrapping_sub(self.fee_growth_outside_y);
+        self.liquidity_net
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized != 0
+    }
+}
+
+/// `TICK_ARRAY_SIZE` consecutive ticks, `tick_spacing` apart, starting at `start_tick_index`.
+#[account(zero_copy)]
+pub struct TickArray {
+    pub start_tick_index: i32,
+    pub _padding: [u8; 12],
+    pub pool: Pubkey,
+    pub ticks: [Tick; TICK_ARRAY_SIZE],
+}
+
+impl TickArray {
+    pub const SPACE: usize = 8 + std::mem::size_of::<TickArray>();
+
+    /// Start index of the array holding `tick`.
+    pub fn start_index(tick: i32, tick_spacing: u16) -> i32 {
+        let ticks_per_array = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
+        tick.div_euclid(ticks_per_array) * ticks_per_array
+    }
+
+    /// Whether an array starting at `start_tick_index` is aligned and covers usable ticks.
+    pub fn is_valid_start(start_tick_index: i32, tick_spacing: u16) -> bool {
+        let ticks_per_array = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
+        Self::start_index(start_tick_index, tick_spacing) == start_tick_index
+            && start_tick_index + ticks_per_array > MIN_TICK
+            && start_tick_index <= MAX_TICK
+    }
+
+    pub fn tick(&self, tick: i32, tick_spacing: u16) -> Result<&Tick> {
+        let offset = self.offset(tick, tick_spacing)?;
+        Ok(&self.ticks[offset])
+    }
+
+    pub fn tick_mut(&mut self, tick: i32, tick_spacing: u16) -> Result<&mut Tick> {
+        let offset = self.offset(tick, tick_spacing)?;
+        Ok(&mut self.ticks[offset])
+    }
+
+    /// Nearest initialized tick at or beyond `search_tick` in the swap direction within this
+    /// array. When there is none, returns the array's last tick in that direction, flagged
+    /// as uninitialized.
+    pub fn next_initialized_tick(
+        &self,
+        search_tick: i32,
+        tick_spacing: u16,
+        x_to_y: bool,
+    ) -> Result<(i32, bool)> {
+        let spacing = tick_spacing as i32;
+        let aligned = search_tick.div_euclid(spacing) * spacing;
+        let offset = self.offset(aligned, tick_spacing)?;
+
+        let found = if x_to_y {
+            (0..=offset).rev().find(|&i| self.ticks[i].is_initialized())
+        } else {
+            (offset..TICK_ARRAY_SIZE).find(|&i| self.ticks[i].is_initialized())
+        };
+        Ok(match found {
+            Some(i) => (self.start_tick_index + i as i32 * spacing, true),
+            None if x_to_y => (self.start_tick_index, false),
+            None => (
+                self.start_tick_index + (TICK_ARRAY_SIZE as i32 - 1) * spacing,
+                false,
+            ),
+        })
+    }
+
+    fn offset(&self, tick: i32, tick_spacing: u16) -> Result<usize> {
+        let spacing = tick_spacing as i32;
+        require!(tick % spacing == 0, AmmError::InvalidTick);
+        let offset = (tick - self.start_tick_index).div_euclid(spacing);
+        require!(
+            tick >= self.start_tick_index && offset < TICK_ARRAY_SIZE as i32,
+            AmmError::InvalidTickArray
+        );
+        Ok(offset as usize)
+    }
+}
+
+/// Liquidity provided by `owner` over `[tick_lower, tick_upper)`.
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    /// Fee growth inside the range as of the last update, Q64.64.
+    pub fee_growth_inside_last_x: u128,
+    pub fee_growth_inside_last_y: u128,
+    pub fees_owed_x: u64,
+    pub fees_owed_y: u64,
+    pub bump: u8,
+}
+
+impl Position {
+    /// Credits fees earned since the last update and applies `liquidity_delta`.
+    pub fn update(
+        &mut self,
+        liquidity_delta: i128,
+        fee_growth_inside_x: u128,
+        fee_growth_inside_y: u128,
+    ) -> Result<()> {
+        let earned = |growth: u128, last: u128| -> Result<u64> {
+            to_u64((U256::from(self.liquidity) * U256::from(growth.wrapping_sub(last))) >> 64)
+        };
+        let earned_x = earned(fee_growth_inside_x, self.fee_growth_inside_last_x)?;
+        let earned_y = earned(fee_growth_inside_y, self.fee_growth_inside_last_y)?;
+
+        self.fees_owed_x = self.fees_owed_x.checked_add(earned_x).ok_or(AmmError::Overflow)?;
+        self.fees_owed_y = self.fees_owed_y.checked_add(earned_y).ok_or(AmmError::Overflow)?;
+        self.fee_growth_inside_last_x = fee_growth_inside_x;
+        self.fee_growth_inside_last_y = fee_growth_inside_y;
+        self.liquidity = add_liquidity_delta(self.liquidity, liquidity_delta)?;
+        Ok(())
+    }
+}
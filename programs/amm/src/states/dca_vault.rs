@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    math::{to_u64, U256},
+};
+
+/// Recurring swap of `amount_per_cycle` out of `input_escrow` every `interval` seconds, at
+/// `[b"dca", config, owner, id]`. Both escrows are ATAs of the vault; the output accumulates
+/// in `output_escrow` until the owner withdraws it.
+#[account]
+#[derive(InitSpace)]
+pub struct DcaVault {
+    pub config: Pubkey,
+    pub owner: Pubkey,
+    pub input_escrow: Pubkey,
+    pub output_escrow: Pubkey,
+    pub id: u64,
+    pub x_to_y: bool,
+    pub amount_per_cycle: u64,
+    pub interval: i64,
+    pub next_cycle_ts: i64,
+    /// Largest shortfall, in basis points, a cycle accepts against the TWAP quote.
+    pub max_slippage_bps: u16,
+    /// `PoolState::price_cumulative` when the TWAP window opened, and when that was.
+    pub price_cumulative: u128,
+    pub price_cumulative_ts: i64,
+    pub bump: u8,
+}
+
+impl DcaVault {
+    /// Least output a cycle of `amount_in` accepts: its value at the pool's average price
+    /// since the last cycle after the `fee` in effect, less `max_slippage_bps`. Closes the
+    /// TWAP window at `now`.
+    pub fn min_amount_out(
+        &mut self,
+        amount_in: u64,
+        fee: u16,
+        price_cumulative: u128,
+        now: i64,
+    ) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.price_cumulative_ts);
+        require!(elapsed > 0, AmmError::DcaNotDue);
+        let twap = price_cumulative.wrapping_sub(self.price_cumulative) / elapsed as u128;
+        require!(twap > 0, AmmError::NoLiquidityInPool);
+        self.price_cumulative = price_cumulative;
+        self.price_cumulative_ts = now;
+
+        // TWAP is Y per X, Q64.64
+        let amount_in = U256::from(amount_in) * U256::from(10_000 - fee as u64) / U256::from(10_000u64);
+        let quote = match self.x_to_y {
+            true => (amount_in * U256::from(twap)) >> 64,
+            false => (amount_in << 64) / U256::from(twap),
+        };
+        to_u64(quote * U256::from(10_000 - self.max_slippage_bps as u64) / U256::from(10_000u64))
+    }
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::VARIABLE_FEE_PRECISION,
+    error::AmmError,
+    math::U256,
+    states::{Config, PoolState},
+};
+
+/// Volatility-driven fee settings, in the spirit of Meteora DLMM. Volatility is measured
+/// in basis points of reserve-ratio movement and periods are in slots.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct DynamicFeeParams {
+    pub enabled: bool,
+    /// Cap on the total fee in basis points.
+    pub max_fee: u16,
+    pub variable_fee_control: u32,
+    pub max_volatility_accumulator: u32,
+    /// Swaps closer together than this keep accumulating against the same reference.
+    pub filter_period: u64,
+    /// Past this, volatility resets to zero; in between it decays by `reduction_factor`.
+    pub decay_period: u64,
+    /// Share of the accumulator carried into a new reference, in basis points.
+    pub reduction_factor: u16,
+}
+
+impl Config {
+    /// Replaces the dynamic-fee settings. Callers restart volatility tracking with
+    /// `PoolState::reset_volatility`.
+    pub fn set_dynamic_fee(&mut self, params: DynamicFeeParams) -> Result<()> {
+        if params.enabled {
+            self.check_swap_guards()?;
+            require!(
+                params.max_fee >= self.fee && params.max_fee < 10_000,
+                AmmError::InvalidFee
+            );
+            require!(
+                params.filter_period <= params.decay_period && params.reduction_factor <= 10_000,
+                AmmError::InvalidDynamicFee
+            );
+        }
+
+        self.dynamic_fee = params;
+        Ok(())
+    }
+
+    /// Fee in basis points for a swap at `slot` against reserves priced at `price`.
+    pub fn swap_fee(&self, state: &mut PoolState, slot: u64, price: u128) -> u16 {
+        if !self.dynamic_fee.enabled {
+            return self.fee;
+        }
+
+        let elapsed = slot.saturating_sub(state.last_swap_slot);
+        if elapsed >= self.dynamic_fee.filter_period {
+            state.reference_price = price;
+            state.volatility_reference = if elapsed < self.dynamic_fee.decay_period {
+                (state.volatility_accumulator as u64 * self.dynamic_fee.reduction_factor as u64
+                    / 10_000) as u32
+            } else {
+                0
+            };
+        }
+        self.update_volatility(state, slot, price);
+
+        let volatility = state.volatility_accumulator as u128;
+        let variable_fee = self.dynamic_fee.variable_fee_control as u128 * volatility * volatility
+            / VARIABLE_FEE_PRECISION;
+        (self.fee as u128 + variable_fee).min(self.dynamic_fee.max_fee as u128) as u16
+    }
+
+    /// Folds a price seen at `slot` into the volatility accumulator.
+    pub fn update_volatility(&self, state: &mut PoolState, slot: u64, price: u128) {
+        if !self.dynamic_fee.enabled {
+            return;
+        }
+        let volatility = state.volatility_reference as u128 + price_move_bps(state.reference_price, price);
+        state.volatility_accumulator =
+            volatility.min(self.dynamic_fee.max_volatility_accumulator as u128) as u32;
+        state.last_swap_slot = slot;
+    }
+}
+
+/// Reserve ratio `y / x` as Q64.64.
+pub fn reserve_price(reserve_x: u64, reserve_y: u64) -> u128 {
+    if reserve_x == 0 {
+        return 0;
+    }
+    ((reserve_y as u128) << 64) / reserve_x as u128
+}
+
+/// Relative move from `reference` to `price` in basis points.
+pub(crate) fn price_move_bps(reference: u128, price: u128) -> u128 {
+    if reference == 0 {
+        return 0;
+    }
+    let diff = U256::from(reference.abs_diff(price));
+    let bps = diff * U256::from(10_000) / U256::from(reference);
+    bps.min(U256::from(u32::MAX)).as_u128()
+}
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+/// A resting order selling `amount_in` of one pool token for at least `min_amount_out` of the
+/// other, at `[b"order", config, owner, id]`. The input sits in `escrow`, the order's ATA, and
+/// the account holds `tip` lamports on top of its rent for whoever fills it.
+#[account]
+#[derive(InitSpace)]
+pub struct LimitOrder {
+    pub config: Pubkey,
+    pub owner: Pubkey,
+    pub escrow: Pubkey,
+    pub id: u64,
+    pub x_to_y: bool,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub tip: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+
+use bytemuck::Zeroable;
+
+use crate::{
+    constants::CONFIG_VERSION,
+    states::{
+        AccessMode, Config, CurveType, DynamicFeeParams, OracleParams, PoolState, VolumeLimits,
+        VolumeWindow,
+    },
+};
+
+/// The original `Config` layout, from before pools carried a version. Stored under the same
+/// discriminator as `Config`, so it is told apart by its account size.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct ConfigV0 {
+    pub seed: u64,
+    pub authority: Option<Pubkey>,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fee: u16,
+    pub locked: bool,
+    pub config_bump: u8,
+    pub lp_bump: u8,
+}
+
+impl ConfigV0 {
+    /// Size of a version 0 config account, discriminator included.
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Carries the v0 fields over and fills everything added since with the defaults of a
+    /// constant-product pool. The vaults, LP mint and decimals are left for the caller to record.
+    pub fn migrate(self) -> Config {
+        Config {
+            version: CONFIG_VERSION,
+            seed: self.seed,
+            authority: self.authority,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            fee: self.fee,
+            locked: self.locked,
+            config_bump: self.config_bump,
+            lp_bump: self.lp_bump,
+            curve_type: CurveType::ConstantProduct,
+            initial_amp: 0,
+            target_amp: 0,
+            ramp_start_ts: 0,
+            ramp_stop_ts: 0,
+            weight_x: 0,
+            weight_y: 0,
+            dynamic_fee: DynamicFeeParams::default(),
+            protocol_fee_share: 0,
+            max_price_impact_bps: 0,
+            max_slot_price_move_bps: 0,
+            oracle: None,
+            oracle_params: OracleParams::default(),
+            volume_limits: VolumeLimits::default(),
+            access_mode: AccessMode::Open,
+            max_referral_fee_bps: 0,
+            pauser: None,
+            fee_manager: None,
+            timelock_seconds: 0,
+            vault_x: Pubkey::default(),
+            vault_y: Pubkey::default(),
+            mint_lp: Pubkey::default(),
+            decimals_x: 0,
+            decimals_y: 0,
+            batch_window_slots: 0,
+            min_intent_amount: 0,
+            padding: [0; 14],
+        }
+    }
+}
+
+/// The version 1 `Config` layout, which still held the swap state that now lives in
+/// `PoolState`. Told apart from the current layout by its account size and version byte.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct ConfigV1 {
+    pub version: u8,
+    pub seed: u64,
+    pub authority: Option<Pubkey>,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fee: u16,
+    pub locked: bool,
+    pub config_bump: u8,
+    pub lp_bump: u8,
+    pub curve_type: CurveType,
+    pub initial_amp: u64,
+    pub target_amp: u64,
+    pub ramp_start_ts: i64,
+    pub ramp_stop_ts: i64,
+    pub weight_x: u16,
+    pub weight_y: u16,
+    pub dynamic_fee: DynamicFeeParams,
+    pub volatility_accumulator: u32,
+    pub volatility_reference: u32,
+    pub reference_price: u128,
+    pub last_swap_slot: u64,
+    pub protocol_fee_share: u16,
+    pub protocol_fees_x: u64,
+    pub protocol_fees_y: u64,
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+    pub max_price_impact_bps: u16,
+    pub max_slot_price_move_bps: u16,
+    pub slot_price_move_bps: u32,
+    pub price_move_slot: u64,
+    pub oracle: Option<Pubkey>,
+    pub oracle_params: OracleParams,
+    pub volume_limits: VolumeLimits,
+    pub volume_window: VolumeWindow,
+    pub access_mode: AccessMode,
+    pub max_referral_fee_bps: u16,
+    pub pauser: Option<Pubkey>,
+    pub fee_manager: Option<Pubkey>,
+    pub timelock_seconds: i64,
+    pub padding: [u8; 128],
+}
+
+impl ConfigV1 {
+    /// Size of a version 1 config account, discriminator included.
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Splits a v1 config into the current `Config` and the `PoolState` holding its swap state.
+    /// The vaults, LP mint and decimals are left for the caller to record, as is
+    /// `PoolState::config`.
+    pub fn migrate(self) -> (Config, PoolState) {
+        let config = Config {
+            version: CONFIG_VERSION,
+            seed: self.seed,
+            authority: self.authority,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            fee: self.fee,
+            locked: self.locked,
+            config_bump: self.config_bump,
+            lp_bump: self.lp_bump,
+            curve_type: self.curve_type,
+            initial_amp: self.initial_amp,
+            target_amp: self.target_amp,
+            ramp_start_ts: self.ramp_start_ts,
+            ramp_stop_ts: self.ramp_stop_ts,
+            weight_x: self.weight_x,
+            weight_y: self.weight_y,
+            dynamic_fee: self.dynamic_fee,
+            protocol_fee_share: self.protocol_fee_share,
+            max_price_impact_bps: self.max_price_impact_bps,
+            max_slot_price_move_bps: self.max_slot_price_move_bps,
+            oracle: self.oracle,
+            oracle_params: self.oracle_params,
+            volume_limits: self.volume_limits,
+            access_mode: self.access_mode,
+            max_referral_fee_bps: self.max_referral_fee_bps,
+            pauser: self.pauser,
+            fee_manager: self.fee_manager,
+            timelock_seconds: self.timelock_seconds,
+            vault_x: Pubkey::default(),
+            vault_y: Pubkey::default(),
+            mint_lp: Pubkey::default(),
+            decimals_x: 0,
+            decimals_y: 0,
+            batch_window_slots: 0,
+            min_intent_amount: 0,
+            padding: [0; 14],
+        };
+        let state = PoolState {
+            reference_price: self.reference_price,
+            reserve_x: self.reserve_x,
+            reserve_y: self.reserve_y,
+            protocol_fees_x: self.protocol_fees_x,
+            protocol_fees_y: self.protocol_fees_y,
+            last_swap_slot: self.last_swap_slot,
+            price_move_slot: self.price_move_slot,
+            volume_window: self.volume_window,
+            volatility_accumulator: self.volatility_accumulator,
+            volatility_reference: self.volatility_reference,
+            slot_price_move_bps: self.slot_price_move_bps,
+            ..PoolState::zeroed()
+        };
+        (config, state)
+    }
+}
@@ -1,8 +1,84 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::{MAX_AMP, MAX_AMP_CHANGE, MIN_AMP, MIN_RAMP_DURATION},
+    error::AmmError,
+};
+
+pub mod allow_entry;
+pub use allow_entry::*;
+
+pub mod amm_config;
+pub use amm_config::*;
+
+pub mod batch_auction;
+pub use batch_auction::*;
+
+pub mod circuit_breaker;
+
+pub mod concentrated;
+pub use concentrated::*;
+
+pub mod dca_vault;
+pub use dca_vault::*;
+
+pub mod dynamic_fee;
+pub use dynamic_fee::*;
+
+pub mod limit_order;
+pub use limit_order::*;
+
+pub mod migration;
+pub use migration::*;
+
+pub mod reward_pool;
+pub use reward_pool::*;
+
+pub mod volume_limit;
+pub use volume_limit::*;
+
+pub mod multi_pool;
+pub use multi_pool::*;
+
+pub mod oracle;
+pub use oracle::*;
+
+pub mod pool_index;
+pub use pool_index::*;
+
+pub mod pool_state;
+pub use pool_state::*;
+
+pub mod roles;
+pub use roles::*;
+
+pub mod pending_change;
+pub use pending_change::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    ConstantProduct,
+    StableSwap,
+    Weighted,
+    Concentrated,
+}
+
+/// Curve selection passed to `initialize`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct CurveParams {
+    pub curve_type: CurveType,
+    /// StableSwap amplification coefficient, ignored by other curves.
+    pub amp: u64,
+    /// Weighted-curve token weights in basis points summing to 10 000, ignored by other curves.
+    pub weight_x: u16,
+    pub weight_y: u16,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
+    // Layout version, `CONFIG_VERSION` for accounts created or migrated by this program
+    pub version: u8,
     pub seed: u64,
     pub authority: Option<Pubkey>,
     pub mint_x: Pubkey,
@@ -11,4 +87,110 @@ pub struct Config {
     pub locked: bool,
     pub config_bump: u8,
     pub lp_bump: u8,
+    pub curve_type: CurveType,
+    // StableSwap amplification, ramped linearly from `initial_amp` to `target_amp`
+    pub initial_amp: u64,
+    pub target_amp: u64,
+    pub ramp_start_ts: i64,
+    pub ramp_stop_ts: i64,
+    // Weighted-curve token weights in basis points
+    pub weight_x: u16,
+    pub weight_y: u16,
+    pub dynamic_fee: DynamicFeeParams,
+    // Protocol share of the swap fee, fixed at creation
+    pub protocol_fee_share: u16,
+    // Circuit breaker limits in basis points of reserve-ratio movement, 0 when disabled
+    pub max_price_impact_bps: u16,
+    pub max_slot_price_move_bps: u16,
+    // Pyth price account the pool price is checked against on every swap, if any
+    pub oracle: Option<Pubkey>,
+    pub oracle_params: OracleParams,
+    pub volume_limits: VolumeLimits,
+    pub access_mode: AccessMode,
+    // Largest share of the swap fee, in basis points, a referrer may take
+    pub max_referral_fee_bps: u16,
+    // Optional delegates for locking the pool and managing fees, alongside `authority`
+    pub pauser: Option<Pubkey>,
+    pub fee_manager: Option<Pubkey>,
+    // Delay, in seconds, before a queued fee or curve change may be executed
+    pub timelock_seconds: i64,
+    // Pool vaults and LP mint, recorded at creation so instructions check them with `has_one`
+    // rather than re-deriving their addresses; the vaults need not be ATAs
+    pub vault_x: Pubkey,
+    pub vault_y: Pubkey,
+    pub mint_lp: Pubkey,
+    // Decimals of `mint_x` and `mint_y`, for swaps that do not load the mints
+    pub decimals_x: u8,
+    pub decimals_y: u8,
+    // Length of a batch-auction window in slots; while non-zero the pool trades only through
+    // `settle_batch`
+    pub batch_window_slots: u64,
+    // Smallest intent a batch accepts, valued in X at the pool price, so a handful of dust
+    // intents cannot fill a batch
+    pub min_intent_amount: u64,
+    // Reserved so later fields can be carved out of the existing allocation; zeroed bytes
+    // read back as the default of any new field placed just before it
+    pub padding: [u8; 14],
+}
+
+impl Config {
+    /// Amplification coefficient in effect at `now`.
+    pub fn current_amp(&self, now: i64) -> u64 {
+        if now >= self.ramp_stop_ts || self.ramp_stop_ts <= self.ramp_start_ts {
+            return self.target_amp;
+        }
+        let elapsed = now.saturating_sub(self.ramp_start_ts).max(0) as u128;
+        let duration = (self.ramp_stop_ts - self.ramp_start_ts) as u128;
+        let (initial, target) = (self.initial_amp as u128, self.target_amp as u128);
+        let amp = if target > initial {
+            initial + ((target - initial) * elapsed) / duration
+        } else {
+            initial - ((initial - target) * elapsed) / duration
+        };
+        amp as u64
+    }
+
+    /// Starts moving the amplification linearly towards `target_amp`, reaching it at `stop_ts`.
+    pub fn start_amp_ramp(&mut self, target_amp: u64, stop_ts: i64, now: i64) -> Result<()> {
+        require!(self.curve_type == CurveType::StableSwap, AmmError::UnsupportedCurve);
+        require!((MIN_AMP..=MAX_AMP).contains(&target_amp), AmmError::InvalidAmp);
+        require!(
+            stop_ts >= now.checked_add(MIN_RAMP_DURATION).ok_or(AmmError::Overflow)?,
+            AmmError::InvalidRampTime
+        );
+
+        let current_amp = self.current_amp(now);
+        require!(
+            target_amp <= current_amp.saturating_mul(MAX_AMP_CHANGE) &&
+                target_amp.saturating_mul(MAX_AMP_CHANGE) >= current_amp,
+            AmmError::InvalidAmp
+        );
+
+        self.initial_amp = current_amp;
+        self.target_amp = target_amp;
+        self.ramp_start_ts = now;
+        self.ramp_stop_ts = stop_ts;
+        Ok(())
+    }
+
+    /// Moves the pool to another enabled fee tier. With dynamic fees on, the new base fee may
+    /// not exceed their cap.
+    pub fn set_fee(&mut self, fee: u16, amm_config: &AmmConfig) -> Result<()> {
+        require!(amm_config.is_enabled_tier(fee), AmmError::InvalidFeeTier);
+        if self.dynamic_fee.enabled {
+            require!(fee <= self.dynamic_fee.max_fee, AmmError::InvalidFee);
+        }
+        self.fee = fee;
+        Ok(())
+    }
+
+    /// Caps the share of the swap fee, in basis points, integrators may claim.
+    pub fn set_max_referral_fee(&mut self, max_referral_fee_bps: u16) -> Result<()> {
+        require!(max_referral_fee_bps <= 10_000, AmmError::InvalidReferralFee);
+        if max_referral_fee_bps > 0 {
+            self.check_swap_guards()?;
+        }
+        self.max_referral_fee_bps = max_referral_fee_bps;
+        Ok(())
+    }
 }
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{transfer, TokenAccount, Transfer},
+};
+
+use crate::error::AmmError;
+
+/// StableSwap pool over 3 to 8 tokens. Vaults are the pool PDA's associated token accounts
+/// and are passed as remaining accounts in `mints` order.
+#[account]
+#[derive(InitSpace)]
+pub struct MultiPool {
+    pub seed: u64,
+    pub authority: Option<Pubkey>,
+    pub fee: u16,
+    pub locked: bool,
+    pub amp: u64,
+    #[max_len(8)]
+    pub mints: Vec<Pubkey>,
+    #[max_len(8)]
+    pub decimals: Vec<u8>,
+    /// Balances the curve prices against, in `mints` order; tokens sent straight to a vault
+    /// are not counted, so a donation cannot move the price or the LP value.
+    #[max_len(8)]
+    pub reserves: Vec<u64>,
+    pub pool_bump: u8,
+    pub lp_bump: u8,
+}
+
+impl MultiPool {
+    pub fn len(&self) -> usize {
+        self.mints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mints.is_empty()
+    }
+
+    /// Decimals every balance is normalised to before it reaches the curve.
+    pub fn precision(&self) -> u8 {
+        self.decimals.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Multiplier that normalises token `i` to `precision` decimals.
+    pub fn rate(&self, i: usize) -> u128 {
+        10u128.pow((self.precision() - self.decimals[i]) as u32)
+    }
+
+    pub fn scale(&self, i: usize, amount: u64) -> Result<u128> {
+        (amount as u128)
+            .checked_mul(self.rate(i))
+            .ok_or(AmmError::Overflow.into())
+    }
+
+    pub fn unscale(&self, i: usize, amount: u128) -> Result<u64> {
+        u64::try_from(amount / self.rate(i)).map_err(|_| AmmError::Overflow.into())
+    }
+
+    /// Converts an invariant expressed in `precision` decimals to LP tokens of `lp_decimals`.
+    pub fn invariant_to_lp(&self, invariant: u128, lp_decimals: u8) -> Result<u64> {
+        let precision = self.precision();
+        let lp = if lp_decimals >= precision {
+            invariant.checked_mul(10u128.pow((lp_decimals - precision) as u32))
+        } else {
+            Some(invariant / 10u128.pow((precision - lp_decimals) as u32))
+        };
+        lp.and_then(|lp| u64::try_from(lp).ok())
+            .ok_or(AmmError::Overflow.into())
+    }
+
+    /// Normalised reserves.
+    pub fn balances(&self) -> Result<Vec<u128>> {
+        self.reserves
+            .iter()
+            .enumerate()
+            .map(|(i, reserve)| self.scale(i, *reserve))
+            .collect()
+    }
+
+    /// Loads the pool vaults from the head of `accounts`, one per mint, and returns them with
+    /// the accounts that follow.
+    pub fn load_vaults<'info>(
+        &self,
+        pool: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+    ) -> Result<(Vec<Account<'info, TokenAccount>>, &'info [AccountInfo<'info>])> {
+        require!(accounts.len() >= self.len(), AmmError::InvalidToken);
+        let (vaults, rest) = accounts.split_at(self.len());
+        let vaults = vaults
+            .iter()
+            .zip(&self.mints)
+            .map(|(info, mint)| {
+                require_keys_eq!(
+                    info.key(),
+                    get_associated_token_address(pool, mint),
+                    AmmError::InvalidToken
+                );
+                Account::<TokenAccount>::try_from(info)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((vaults, rest))
+    }
+
+    /// Loads a user token account for token `i`.
+    pub fn load_user_account<'info>(
+        &self,
+        i: usize,
+        info: &'info AccountInfo<'info>,
+    ) -> Result<Account<'info, TokenAccount>> {
+        let account = Account::<TokenAccount>::try_from(info)?;
+        require_keys_eq!(account.mint, self.mints[i], AmmError::InvalidToken);
+        Ok(account)
+    }
+
+    /// Transfers `amount` from a user token account into a vault.
+    pub fn transfer_in<'info>(
+        user: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        from: &Account<'info, TokenAccount>,
+        vault: &Account<'info, TokenAccount>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let cpi_accounts = Transfer {
+            from: from.to_account_info(),
+            to: vault.to_account_info(),
+            authority: user,
+        };
+        let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
+
+        transfer(cpi_ctx, amount)
+    }
+
+    /// Transfers `amount` out of a vault, signed by the pool PDA.
+    pub fn transfer_out<'info>(
+        &self,
+        pool: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        vault: &Account<'info, TokenAccount>,
+        to: &Account<'info, TokenAccount>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let cpi_accounts = Transfer {
+            from: vault.to_account_info(),
+            to: to.to_account_info(),
+            authority: pool,
+        };
+        let seeds = &[
+            &b"multi_pool"[..],
+            &self.seed.to_le_bytes(),
+            &[self.pool_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+
+        transfer(cpi_ctx, amount)
+    }
+}
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    curves::{self, Reserves},
+    error::AmmError,
+    math::U256,
+    states::Config,
+};
+
+/// Limits applied to the external oracle pinned in `Config::oracle`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct OracleParams {
+    /// Largest divergence, in basis points, between the pool and oracle prices after a swap.
+    pub max_deviation_bps: u16,
+    /// Oldest oracle price accepted, in seconds.
+    pub max_staleness: u64,
+    /// Widest confidence interval accepted, in basis points of the price.
+    pub max_confidence_bps: u16,
+}
+
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_VERSION: u32 = 2;
+const PYTH_PRICE_ACCOUNT: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+/// Offsets into a Pyth v2 price account.
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+const PYTH_ACCOUNT_MIN_LEN: usize = 240;
+
+/// Aggregate price of X in Y read from an oracle, worth `price · 10^expo`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Parses a Pyth v2 price account, rejecting anything that is not a trading price.
+    pub fn from_pyth(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= PYTH_ACCOUNT_MIN_LEN, AmmError::InvalidOracle);
+        require!(
+            read_u32(data, 0) == PYTH_MAGIC &&
+                read_u32(data, 4) == PYTH_VERSION &&
+                read_u32(data, 8) == PYTH_PRICE_ACCOUNT,
+            AmmError::InvalidOracle
+        );
+        require!(
+            read_u32(data, PYTH_AGG_STATUS_OFFSET) == PYTH_STATUS_TRADING,
+            AmmError::InvalidOracle
+        );
+
+        let oracle_price = OraclePrice {
+            price: read_u64(data, PYTH_AGG_PRICE_OFFSET) as i64,
+            conf: read_u64(data, PYTH_AGG_CONF_OFFSET),
+            expo: read_u32(data, PYTH_EXPO_OFFSET) as i32,
+            publish_time: read_u64(data, PYTH_TIMESTAMP_OFFSET) as i64,
+        };
+        require!(
+            oracle_price.price > 0 && (-18..=18).contains(&oracle_price.expo),
+            AmmError::InvalidOracle
+        );
+        Ok(oracle_price)
+    }
+
+    /// Price as a raw `(x, y)` amount ratio between mints of the given decimals.
+    fn ratio(&self, decimals_x: u8, decimals_y: u8) -> (U256, U256) {
+        let ten = U256::from(10);
+        let mut x = ten.pow(U256::from(decimals_x));
+        let mut y = U256::from(self.price as u64) * ten.pow(U256::from(decimals_y));
+        if self.expo >= 0 {
+            y *= ten.pow(U256::from(self.expo));
+        } else {
+            x *= ten.pow(U256::from(-self.expo));
+        }
+        (x, y)
+    }
+}
+
+impl Config {
+    /// Checks a swap moving the reserves from `before` to `after` against the pinned oracle.
+    /// Swaps that leave the pool closer to the oracle than they found it always pass, so
+    /// arbitrage can restore a diverged pool.
+    pub fn check_oracle(
+        &self,
+        oracle: Option<&AccountInfo>,
+        before: &Reserves,
+        after: &Reserves,
+        now: i64,
+    ) -> Result<()> {
+        let Some(price) = self.oracle_price(oracle, now)? else {
+            return Ok(());
+        };
+
+        let deviation_after = self.oracle_deviation_bps(&price, after, now)?;
+        if deviation_after <= self.oracle_params.max_deviation_bps as u128 {
+            return Ok(());
+        }
+        require!(
+            deviation_after <= self.oracle_deviation_bps(&price, before, now)?,
+            AmmError::OracleDeviationExceeded
+        );
+        Ok(())
+    }
+
+    /// Checks the pool price at `reserves` against the pinned oracle, for a batch that clears
+    /// at that price without trading with the pool.
+    pub fn check_oracle_price(
+        &self,
+        oracle: Option<&AccountInfo>,
+        reserves: &Reserves,
+        now: i64,
+    ) -> Result<()> {
+        let Some(price) = self.oracle_price(oracle, now)? else {
+            return Ok(());
+        };
+        require!(
+            self.oracle_deviation_bps(&price, reserves, now)? <=
+                self.oracle_params.max_deviation_bps as u128,
+            AmmError::OracleDeviationExceeded
+        );
+        Ok(())
+    }
+
+    /// The pinned oracle's price, once checked to be fresh and tight enough; `None` when the
+    /// pool has no oracle.
+    fn oracle_price(&self, oracle: Option<&AccountInfo>, now: i64) -> Result<Option<OraclePrice>> {
+        let Some(oracle_key) = self.oracle else {
+            return Ok(None);
+        };
+        let oracle = oracle.ok_or(AmmError::MissingOracle)?;
+        require_keys_eq!(oracle.key(), oracle_key, AmmError::InvalidOracle);
+
+        let price = OraclePrice::from_pyth(&oracle.try_borrow_data()?)?;
+        require!(
+            now.saturating_sub(price.publish_time) <= self.oracle_params.max_staleness as i64,
+            AmmError::StaleOracle
+        );
+        require!(
+            price.conf as u128 * 10_000 <=
+                price.price as u128 * self.oracle_params.max_confidence_bps as u128,
+            AmmError::OracleConfidenceTooWide
+        );
+        Ok(Some(price))
+    }
+
+    /// Divergence of the pool spot price at `reserves` from the oracle in basis points.
+    fn oracle_deviation_bps(&self, price: &OraclePrice, reserves: &Reserves, now: i64) -> Result<u128> {
+        let (pool_x, pool_y) = curves::spot_price(self, reserves, now)?;
+        let (oracle_x, oracle_y) = price.ratio(reserves.decimals_x, reserves.decimals_y);
+
+        // pool_y / pool_x against oracle_y / oracle_x, cross-multiplied
+        let pool = U256::from(pool_y) * oracle_x;
+        let reference = oracle_y * U256::from(pool_x);
+        let diff = if pool > reference { pool - reference } else { reference - pool };
+        let bps = diff * U256::from(10_000) / reference;
+        Ok(bps.min(U256::from(u32::MAX)).as_u128())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_TIMELOCK_SECONDS,
+    error::AmmError,
+    states::{AmmConfig, Config, DynamicFeeParams, Role},
+};
+
+/// A fee or curve change that has to wait out `Config::timelock_seconds`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    Fee(u16),
+    DynamicFee(DynamicFeeParams),
+    MaxReferralFee(u16),
+    /// Ramps the amplification to `target_amp` over `duration` seconds from execution.
+    RampAmp {
+        target_amp: u64,
+        duration: i64,
+    },
+    TimelockSeconds(i64),
+}
+
+/// The change queued on a pool, at `[b"pending_change", config]`. One may be pending at a time.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingChange {
+    pub config: Pubkey,
+    /// Refunded the account's rent when the change is executed or cancelled.
+    pub proposer: Pubkey,
+    pub change: ConfigChange,
+    pub executable_ts: i64,
+    pub bump: u8,
+}
+
+impl ConfigChange {
+    /// Role allowed to propose or cancel the change.
+    pub fn role(&self) -> Role {
+        match self {
+            ConfigChange::Fee(_)
+            | ConfigChange::DynamicFee(_)
+            | ConfigChange::MaxReferralFee(_) => Role::FeeManager,
+            ConfigChange::RampAmp { .. } | ConfigChange::TimelockSeconds(_) => Role::Admin,
+        }
+    }
+
+    pub fn apply(&self, config: &mut Config, amm_config: &AmmConfig, now: i64) -> Result<()> {
+        match *self {
+            ConfigChange::Fee(fee) => config.set_fee(fee, amm_config),
+            ConfigChange::DynamicFee(params) => config.set_dynamic_fee(params),
+            ConfigChange::MaxReferralFee(max_referral_fee_bps) => {
+                config.set_max_referral_fee(max_referral_fee_bps)
+            }
+            ConfigChange::RampAmp {
+                target_amp,
+                duration,
+            } => {
+                let stop_ts = now.checked_add(duration).ok_or(AmmError::Overflow)?;
+                config.start_amp_ramp(target_amp, stop_ts, now)
+            }
+            ConfigChange::TimelockSeconds(timelock_seconds) => {
+                require!(
+                    (0..=MAX_TIMELOCK_SECONDS).contains(&timelock_seconds),
+                    AmmError::InvalidTimelock
+                );
+                config.timelock_seconds = timelock_seconds;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Rejects immediate fee and curve changes once a timelock is set; they must be queued.
+    pub fn check_timelock(&self) -> Result<()> {
+        require!(self.timelock_seconds == 0, AmmError::TimelockActive);
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{allocate, assign, transfer, Allocate, Assign, Transfer};
+use anchor_lang::Discriminator;
+
+/// Every pool created over `mint`, so clients can list them with a single account read.
+/// Lives at `[b"pool_index", mint]` and grows by one key per pool. The program edits the
+/// `pools` entries in place rather than decoding the list, so registering a pool costs the
+/// same however many the mint already has.
+#[account]
+pub struct PoolIndex {
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub pools: Vec<Pubkey>,
+}
+
+impl PoolIndex {
+    /// Offset of the `pools` length prefix in the account data.
+    const LEN_OFFSET: usize = 8 + 32 + 1;
+
+    /// Account size holding `len` pool keys.
+    pub fn space(len: usize) -> usize {
+        8 + 32 + 1 + 4 + 32 * len
+    }
+
+    /// Appends `pool` to the index at `index`, creating the account on first use and
+    /// reallocating it by one entry otherwise. `payer` covers the extra rent.
+    pub fn register<'info>(
+        index: &AccountInfo<'info>,
+        mint: Pubkey,
+        pool: Pubkey,
+        bump: u8,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        if index.data_is_empty() {
+            let seeds: &[&[u8]] = &[b"pool_index", mint.as_ref(), &[bump]];
+            let signer_seeds = &[seeds];
+            let space = Self::space(0);
+            Self::fund(index, space, payer, system_program)?;
+            allocate(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    Allocate { account_to_allocate: index.clone() },
+                    signer_seeds,
+                ),
+                space as u64,
+            )?;
+            assign(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    Assign { account_to_assign: index.clone() },
+                    signer_seeds,
+                ),
+                &crate::ID,
+            )?;
+            let pool_index = PoolIndex { mint, bump, pools: Vec::new() };
+            pool_index.try_serialize(&mut &mut index.try_borrow_mut_data()?[..])?;
+        }
+
+        let len = Self::len(index)?;
+        let space = Self::space(len + 1);
+        Self::fund(index, space, payer, system_program)?;
+        index.realloc(space, false)?;
+
+        let mut data = index.try_borrow_mut_data()?;
+        data[space - 32..space].copy_from_slice(pool.as_ref());
+        Self::set_len(&mut data, len + 1);
+        Ok(())
+    }
+
+    /// Removes `pool` from the index at `index`, moving the last entry into its place,
+    /// shrinking the account and paying the freed rent to `recipient`. Indexes that were
+    /// never created are left alone.
+    pub fn unregister<'info>(
+        index: &AccountInfo<'info>,
+        pool: Pubkey,
+        recipient: &AccountInfo<'info>,
+    ) -> Result<()> {
+        if index.data_is_empty() {
+            return Ok(());
+        }
+        let len = Self::len(index)?;
+        let end = Self::space(len);
+        {
+            let mut data = index.try_borrow_mut_data()?;
+            let Some(position) = data[Self::space(0)..end]
+                .chunks_exact(32)
+                .position(|key| key == pool.as_ref())
+            else {
+                return Ok(());
+            };
+            data.copy_within(end - 32..end, Self::space(0) + 32 * position);
+            Self::set_len(&mut data, len - 1);
+        }
+
+        let space = Self::space(len - 1);
+        index.realloc(space, false)?;
+        let excess = index.lamports().saturating_sub(Rent::get()?.minimum_balance(space));
+        index.sub_lamports(excess)?;
+        recipient.add_lamports(excess)?;
+        Ok(())
+    }
+
+    /// Number of pools in the index at `index`, after checking it is one.
+    fn len(index: &AccountInfo) -> Result<usize> {
+        require_keys_eq!(*index.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let data = index.try_borrow_data()?;
+        require!(
+            data.len() >= Self::space(0) && data.starts_with(PoolIndex::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        let prefix = &data[Self::LEN_OFFSET..Self::LEN_OFFSET + 4];
+        Ok(u32::from_le_bytes(prefix.try_into().unwrap()) as usize)
+    }
+
+    fn set_len(data: &mut [u8], len: usize) {
+        data[Self::LEN_OFFSET..Self::LEN_OFFSET + 4].copy_from_slice(&(len as u32).to_le_bytes());
+    }
+
+    /// Tops `index` up to the rent-exempt minimum for `space` bytes.
+    fn fund<'info>(
+        index: &AccountInfo<'info>,
+        space: usize,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let required = Rent::get()?.minimum_balance(space);
+        let missing = required.saturating_sub(index.lamports());
+        if missing == 0 {
+            return Ok(());
+        }
+        let ctx = CpiContext::new(
+            system_program.clone(),
+            Transfer { from: payer.clone(), to: index.clone() },
+        );
+        transfer(ctx, missing)
+    }
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    states::{reserve_price, Config, CurveType, VolumeWindow},
+};
+
+/// Pool state written on every swap, kept out of `Config` in a zero-copy account so the
+/// swap path reads the config without re-serialising it. Lives at `[b"pool_state", config]`.
+#[account(zero_copy)]
+pub struct PoolState {
+    pub config: Pubkey,
+    /// Dynamic-fee reference price, Q64.64.
+    pub reference_price: u128,
+    /// Running sum of the reserve price, Q64.64, times the seconds it held; wraps on overflow.
+    /// Sampled twice, it gives the time-weighted average price in between.
+    pub price_cumulative: u128,
+    /// Balances the curves price against; vault tokens beyond these and the protocol fees
+    /// are donations that `sync_reserves` absorbs or `skim` pays out.
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+    /// Protocol share of the swap fees owed so far.
+    pub protocol_fees_x: u64,
+    pub protocol_fees_y: u64,
+    pub last_swap_slot: u64,
+    /// Timestamp `price_cumulative` was last brought up to date.
+    pub price_updated_ts: i64,
+    /// Slot the circuit breaker's `slot_price_move_bps` was accumulated in.
+    pub price_move_slot: u64,
+    pub volume_window: VolumeWindow,
+    /// Dynamic-fee volatility state, in basis points of reserve-ratio movement.
+    pub volatility_accumulator: u32,
+    pub volatility_reference: u32,
+    pub slot_price_move_bps: u32,
+    /// Set when the circuit breaker trips; cleared by the admin with `set_locked(false)`.
+    pub breaker_tripped: u8,
+    pub _padding: [u8; 11],
+}
+
+impl PoolState {
+    pub const SPACE: usize = 8 + std::mem::size_of::<PoolState>();
+
+    /// Vault tokens held beyond the reserves and the owed protocol fees.
+    pub fn excess(&self, vault_x: u64, vault_y: u64) -> (u64, u64) {
+        (
+            vault_x.saturating_sub(self.reserve_x.saturating_add(self.protocol_fees_x)),
+            vault_y.saturating_sub(self.reserve_y.saturating_add(self.protocol_fees_y)),
+        )
+    }
+
+    /// `price_cumulative` as of `now`, carrying the current reserve price forward.
+    pub fn price_cumulative_at(&self, now: i64) -> u128 {
+        let elapsed = now.saturating_sub(self.price_updated_ts).max(0) as u128;
+        self.price_cumulative
+            .wrapping_add(reserve_price(self.reserve_x, self.reserve_y).wrapping_mul(elapsed))
+    }
+
+    /// Folds the current reserve price into `price_cumulative`; called before the reserves
+    /// change.
+    pub fn update_price_cumulative(&mut self, now: i64) {
+        self.price_cumulative = self.price_cumulative_at(now);
+        self.price_updated_ts = now;
+    }
+
+    pub fn reset_volatility(&mut self) {
+        self.volatility_accumulator = 0;
+        self.volatility_reference = 0;
+        self.reference_price = 0;
+        self.last_swap_slot = 0;
+    }
+}
+
+impl Config {
+    /// Fails if the pool was locked by its authority or by the circuit breaker.
+    pub fn check_unlocked(&self, state: &PoolState) -> Result<()> {
+        require!(!self.locked && state.breaker_tripped == 0, AmmError::PoolLocked);
+        Ok(())
+    }
+
+    /// Fails on concentrated pools, whose swaps do not run the `PoolState` guards (circuit
+    /// breaker, oracle, volume caps, dynamic and referral fees), so turning one on would be
+    /// a no-op.
+    pub fn check_swap_guards(&self) -> Result<()> {
+        require!(self.curve_type != CurveType::Concentrated, AmmError::UnsupportedCurve);
+        Ok(())
+    }
+}
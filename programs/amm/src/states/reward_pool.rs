@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, math::{to_u64, U256}};
+
+/// Liquidity-mining pool paying `reward_mint` to stakers of a pool's LP token until `end_ts`.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub config: Pubkey,
+    pub reward_mint: Pubkey,
+    pub emission_per_second: u64,
+    pub end_ts: i64,
+    pub last_update_ts: i64,
+    /// Rewards accrued per staked LP token since creation, Q64.64.
+    pub reward_per_share: u128,
+    pub total_staked: u64,
+    /// Emissions for time nothing was staked, held back for the authority to reclaim.
+    pub undistributed: u64,
+    pub bump: u8,
+}
+
+impl RewardPool {
+    /// Accrues emissions up to `now`, capped at `end_ts`. Emissions for time nothing was
+    /// staked go to `undistributed` rather than to whoever stakes next.
+    pub fn update(&mut self, now: i64) -> Result<()> {
+        let until = now.min(self.end_ts);
+        if until <= self.last_update_ts {
+            return Ok(());
+        }
+        let elapsed = (until - self.last_update_ts) as u128;
+        let emitted = self.emission_per_second as u128 * elapsed;
+        if self.total_staked == 0 {
+            self.undistributed = self
+                .undistributed
+                .checked_add(u64::try_from(emitted).map_err(|_| AmmError::Overflow)?)
+                .ok_or(AmmError::Overflow)?;
+        } else {
+            let growth = (U256::from(emitted) << 64) / U256::from(self.total_staked);
+            self.reward_per_share = self
+                .reward_per_share
+                .checked_add(growth.try_into().map_err(|_| AmmError::Overflow)?)
+                .ok_or(AmmError::Overflow)?;
+        }
+        self.last_update_ts = until;
+        Ok(())
+    }
+}
+
+/// LP tokens staked by `owner` in a reward pool.
+#[account]
+#[derive(InitSpace)]
+pub struct StakeAccount {
+    pub owner: Pubkey,
+    pub reward_pool: Pubkey,
+    pub amount: u64,
+    /// `reward_per_share` of the pool when rewards were last settled, Q64.64.
+    pub reward_per_share_paid: u128,
+    pub rewards_owed: u64,
+    pub bump: u8,
+}
+
+impl StakeAccount {
+    /// Credits rewards earned since the last settlement; the pool must be updated first.
+    pub fn settle(&mut self, reward_pool: &RewardPool) -> Result<()> {
+        let growth = reward_pool.reward_per_share - self.reward_per_share_paid;
+        let earned = to_u64((U256::from(self.amount) * U256::from(growth)) >> 64)?;
+        self.rewards_owed = self
+            .rewards_owed
+            .checked_add(earned)
+            .ok_or(AmmError::Overflow)?;
+        self.reward_per_share_paid = reward_pool.reward_per_share;
+        Ok(())
+    }
+}
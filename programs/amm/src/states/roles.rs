@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, states::Config};
+
+/// Privileged roles on a pool. The admin is `Config::authority` and holds every role.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    /// May lock the pool; unlocking stays with the admin.
+    Pauser,
+    /// May change the swap, dynamic and referral fees within their usual bounds.
+    FeeManager,
+}
+
+impl Config {
+    /// Fails unless `signer` is the admin or the holder of `role`. A pool without an admin
+    /// is immutable, whatever roles were assigned before it was renounced.
+    pub fn check_role(&self, signer: &Pubkey, role: Role) -> Result<()> {
+        let admin = self.authority.ok_or(AmmError::NoAuthoritySet)?;
+        if *signer == admin {
+            return Ok(());
+        }
+        let holder = match role {
+            Role::Admin => None,
+            Role::Pauser => self.pauser,
+            Role::FeeManager => self.fee_manager,
+        };
+        require!(holder == Some(*signer), AmmError::InvalidAuthority);
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    error::AmmError,
+    states::{Config, PoolState},
+};
+
+/// Swap volume caps, measured in X on either side of the trade; 0 disables a cap.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct VolumeLimits {
+    pub max_swap_volume: u64,
+    /// Cap on the pool's volume within each window of `window_slots` slots.
+    pub max_window_volume: u64,
+    /// Cap on a single wallet's volume within each window; requires a `WalletVolume` account.
+    pub max_wallet_volume: u64,
+    pub window_slots: u64,
+}
+
+/// Volume traded in the window that started at `start_slot`. Also held in the zero-copy
+/// `PoolState`, hence `Pod`.
+#[repr(C)]
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace, Pod, Zeroable,
+)]
+pub struct VolumeWindow {
+    pub start_slot: u64,
+    pub volume: u64,
+}
+
+impl VolumeWindow {
+    /// Adds `amount` to the window covering `slot` and returns the window's total.
+    pub fn record(&mut self, slot: u64, window_slots: u64, amount: u64) -> u64 {
+        if slot >= self.start_slot.saturating_add(window_slots.max(1)) {
+            self.start_slot = slot;
+            self.volume = 0;
+        }
+        self.volume = self.volume.saturating_add(amount);
+        self.volume
+    }
+}
+
+/// Per-wallet volume tracking for a pool, at `[b"wallet_volume", config, owner]`.
+#[account]
+#[derive(InitSpace)]
+pub struct WalletVolume {
+    pub config: Pubkey,
+    pub owner: Pubkey,
+    pub window: VolumeWindow,
+    pub bump: u8,
+}
+
+impl Config {
+    /// Charges a swap of `volume` X against the per-swap and pool caps, and the wallet cap
+    /// when `wallet` is given. Only a batch's net trade goes without one, its intents having
+    /// been charged to their owners' wallets as they were submitted.
+    pub fn record_volume(
+        &self,
+        state: &mut PoolState,
+        slot: u64,
+        volume: u64,
+        wallet: Option<&mut WalletVolume>,
+    ) -> Result<()> {
+        let limits = self.volume_limits;
+        if limits.max_swap_volume > 0 {
+            require!(volume <= limits.max_swap_volume, AmmError::SwapVolumeLimitExceeded);
+        }
+        if limits.max_window_volume > 0 {
+            require!(
+                state.volume_window.record(slot, limits.window_slots, volume) <= limits.max_window_volume,
+                AmmError::PoolVolumeLimitExceeded
+            );
+        }
+        match wallet {
+            Some(wallet) => self.record_wallet_volume(slot, volume, Some(wallet)),
+            None => Ok(()),
+        }
+    }
+
+    /// Charges `volume` X against the wallet cap, which needs the owner's `WalletVolume`.
+    pub fn record_wallet_volume(
+        &self,
+        slot: u64,
+        volume: u64,
+        wallet: Option<&mut WalletVolume>,
+    ) -> Result<()> {
+        let limits = self.volume_limits;
+        if limits.max_wallet_volume > 0 {
+            let wallet = wallet.ok_or(AmmError::MissingWalletVolume)?;
+            require!(
+                wallet.window.record(slot, limits.window_slots, volume) <= limits.max_wallet_volume,
+                AmmError::WalletVolumeLimitExceeded
+            );
+        }
+        Ok(())
+    }
+}
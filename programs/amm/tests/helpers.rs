@@ -1,7 +1,12 @@
 #![allow(dead_code)]
 #![allow(deprecated)]
 
+use amm::{ instruction as amm_ix, AmmConfig, Config, CurveParams, PoolIndex, PoolState };
+use anchor_lang::{ AccountDeserialize, InstructionData };
+use anchor_spl::{ associated_token, token };
+use litesvm::{ types::TransactionResult, LiteSVM };
 use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
 use solana_sdk::message::Message;
 use solana_sdk::program_option::COption;
 use solana_sdk::program_pack::Pack;
@@ -9,7 +14,14 @@ use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
 use solana_sdk::transaction::Transaction;
 use solana_sdk::rent::Rent;
-use solana_sdk::{ instruction::{ AccountMeta, Instruction }, pubkey::Pubkey, system_instruction };
+use solana_sdk::{
+    bpf_loader_upgradeable,
+    instruction::{ AccountMeta, Instruction },
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program,
+};
 use solana_sdk::hash::Hash;
 
 use spl_associated_token_account_client::address::get_associated_token_address;
@@ -18,7 +30,7 @@ use spl_associated_token_account_client::instruction::{
     create_associated_token_account_idempotent,
 };
 use spl_token::instruction::TokenInstruction;
-use spl_token::{ ID as TOKEN_PROGRAM_ID, state::{ Mint as SPLMint } };
+use spl_token::{ ID as TOKEN_PROGRAM_ID, state::{ Account as SPLTokenAccount, Mint as SPLMint } };
 
 // Function to build a mock token mint with a specified supply and decimals
 pub fn build_token_mint_account(
@@ -180,3 +192,455 @@ pub fn create_mint_to_transaction(
     );
     (tx, ata)
 }
+
+/// Builds a Pyth v2 price account quoting `price · 10^expo` with the given confidence,
+/// publish time and aggregate status (1 = trading)
+pub fn build_pyth_price_account(
+    price: i64,
+    conf: u64,
+    expo: i32,
+    publish_time: i64,
+    status: u32
+) -> Account {
+    let mut data = vec![0u8; 3312];
+    data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+    data[4..8].copy_from_slice(&2u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&status.to_le_bytes());
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Upgrade authority `setup_svm` deploys the program under, the only key that may create
+/// the global `AmmConfig`
+pub fn upgrade_authority() -> Keypair {
+    Keypair::new_from_array([7; 32])
+}
+
+pub fn program_data_pda() -> Pubkey {
+    bpf_loader_upgradeable::get_program_data_address(&amm::id())
+}
+
+/// Creates a LiteSVM instance with the compiled AMM program deployed through the upgradeable
+/// loader, as on a real cluster
+pub fn setup_svm() -> LiteSVM {
+    let mut svm = LiteSVM::new();
+    let bytes = include_bytes!("../../../target/deploy/amm.so");
+
+    // ProgramData: tag 3, deploy slot, Some(upgrade authority), then the ELF
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(upgrade_authority().pubkey().as_ref());
+    data.extend_from_slice(bytes);
+    let program_data = Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: bpf_loader_upgradeable::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(program_data_pda(), program_data).unwrap();
+
+    // Program: tag 2, then its ProgramData address
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(program_data_pda().as_ref());
+    let program = Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: bpf_loader_upgradeable::ID,
+        executable: true,
+        rent_epoch: 0,
+    };
+    svm.set_account(amm::id(), program).unwrap();
+    svm
+}
+
+/// Creates a keypair funded with 100 SOL
+pub fn funded_keypair(svm: &mut LiteSVM) -> Keypair {
+    let keypair = Keypair::new();
+    svm.airdrop(&keypair.pubkey(), 100 * LAMPORTS_PER_SOL).unwrap();
+    keypair
+}
+
+/// Sends `ixs` in one transaction; the first signer pays the fees
+pub fn send_ixs(svm: &mut LiteSVM, ixs: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+    let tx = Transaction::new(
+        signers,
+        Message::new(ixs, Some(&signers[0].pubkey())),
+        svm.latest_blockhash()
+    );
+    svm.send_transaction(tx)
+}
+
+/// Moves the clock to `unix_timestamp` and starts a new blockhash so repeated
+/// transactions are not rejected as duplicates
+pub fn set_unix_timestamp(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar::<Clock>(&clock);
+    svm.expire_blockhash();
+}
+
+pub fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+    let account = svm.get_account(token_account).unwrap();
+    SPLTokenAccount::unpack(&account.data).unwrap().amount
+}
+
+pub fn mint_supply(svm: &LiteSVM, mint: &Pubkey) -> u64 {
+    let account = svm.get_account(mint).unwrap();
+    SPLMint::unpack(&account.data).unwrap().supply
+}
+
+pub fn fetch_config(svm: &LiteSVM, config: &Pubkey) -> Config {
+    let account = svm.get_account(config).unwrap();
+    Config::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+/// Fee tiers registered by `ensure_amm_config`, covering every fee the tests create pools with
+pub const TEST_FEE_TIERS: [u16; 5] = [0, 4, 30, 100, 1000];
+
+pub fn amm_config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"amm_config".as_slice()], &amm::id()).0
+}
+
+pub fn fetch_amm_config(svm: &LiteSVM) -> AmmConfig {
+    let account = svm.get_account(&amm_config_pda()).unwrap();
+    AmmConfig::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+pub fn initialize_amm_config_ix(
+    admin: &Pubkey,
+    fee_recipient: &Pubkey,
+    protocol_fee_share: u16,
+    pool_creation_fee: u64
+) -> Instruction {
+    Instruction {
+        program_id: amm::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(amm_config_pda(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(amm::id(), false),
+            AccountMeta::new_readonly(program_data_pda(), false)
+        ],
+        data: (amm_ix::InitializeAmmConfig {
+            fee_recipient: *fee_recipient,
+            protocol_fee_share,
+            pool_creation_fee,
+        }).data(),
+    }
+}
+
+pub fn add_fee_tier_ix(admin: &Pubkey, fee: u16) -> Instruction {
+    Instruction {
+        program_id: amm::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(amm_config_pda(), false)
+        ],
+        data: (amm_ix::AddFeeTier { fee }).data(),
+    }
+}
+
+pub fn disable_fee_tier_ix(admin: &Pubkey, fee: u16) -> Instruction {
+    Instruction {
+        program_id: amm::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(amm_config_pda(), false)
+        ],
+        data: (amm_ix::DisableFeeTier { fee }).data(),
+    }
+}
+
+/// Creates the global `AmmConfig` with `TEST_FEE_TIERS`, no protocol fee and no creation fee,
+/// unless it already exists
+pub fn ensure_amm_config(svm: &mut LiteSVM) -> AmmConfig {
+    if svm.get_account(&amm_config_pda()).is_none() {
+        let admin = upgrade_authority();
+        svm.airdrop(&admin.pubkey(), 100 * LAMPORTS_PER_SOL).unwrap();
+        let mut ixs = vec![initialize_amm_config_ix(&admin.pubkey(), &Pubkey::new_unique(), 0, 0)];
+        ixs.extend(TEST_FEE_TIERS.iter().map(|fee| add_fee_tier_ix(&admin.pubkey(), *fee)));
+        let result = send_ixs(svm, &ixs, &[&admin]);
+        assert!(result.is_ok(), "InitializeAmmConfig Instruction Transaction failed: {:?}", result);
+    }
+    fetch_amm_config(svm)
+}
+
+pub fn pool_index_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_index".as_slice(), mint.as_ref()], &amm::id()).0
+}
+
+pub fn fetch_pool_index(svm: &LiteSVM, mint: &Pubkey) -> PoolIndex {
+    let account = svm.get_account(&pool_index_pda(mint)).unwrap();
+    PoolIndex::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+pub fn pool_state_pda(config: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_state".as_slice(), config.as_ref()], &amm::id()).0
+}
+
+/// Reads a zero-copy `PoolState`; account data carries no alignment guarantee
+pub fn fetch_pool_state(svm: &LiteSVM, pool_state: &Pubkey) -> PoolState {
+    let account = svm.get_account(pool_state).unwrap();
+    bytemuck::pod_read_unaligned(&account.data[8..8 + std::mem::size_of::<PoolState>()])
+}
+
+/// An initialized pool together with the keys needed to drive it
+pub struct TestPool {
+    pub program_id: Pubkey,
+    pub seed: u64,
+    pub authority: Keypair,
+    pub mint_x_authority: Keypair,
+    pub mint_y_authority: Keypair,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub config: Pubkey,
+    pub pool_state: Pubkey,
+    pub mint_lp: Pubkey,
+    pub vault_x: Pubkey,
+    pub vault_y: Pubkey,
+    pub fee_recipient: Pubkey,
+}
+
+impl TestPool {
+    /// Creates X and Y mints with the given decimals and derives the pool addresses for `seed`
+    pub fn new(svm: &mut LiteSVM, seed: u64, decimals_x: u8, decimals_y: u8) -> Self {
+        let (mint_x_authority, mint_x, _, mint_x_account) = build_token_mint_account(0, decimals_x);
+        svm.set_account(mint_x, mint_x_account).unwrap();
+        let (mint_y_authority, mint_y, _, mint_y_account) = build_token_mint_account(0, decimals_y);
+        svm.set_account(mint_y, mint_y_account).unwrap();
+
+        TestPool::from_mints(svm, seed, (mint_x_authority, mint_x), (mint_y_authority, mint_y))
+    }
+
+    /// Derives the pool addresses for `seed` over existing mints, given with their authorities
+    pub fn from_mints(
+        svm: &mut LiteSVM,
+        seed: u64,
+        (mint_x_authority, mint_x): (Keypair, Pubkey),
+        (mint_y_authority, mint_y): (Keypair, Pubkey)
+    ) -> Self {
+        let program_id = amm::id();
+        let authority = funded_keypair(svm);
+
+        let (config, _) = Pubkey::find_program_address(
+            &[b"config".as_slice(), seed.to_le_bytes().as_ref()],
+            &program_id
+        );
+        let (mint_lp, _) = Pubkey::find_program_address(
+            &[b"lp".as_slice(), &config.to_bytes()],
+            &program_id
+        );
+        let vault_x = get_associated_token_address(&config, &mint_x);
+        let vault_y = get_associated_token_address(&config, &mint_y);
+        let fee_recipient = ensure_amm_config(svm).fee_recipient;
+
+        TestPool {
+            program_id,
+            seed,
+            authority,
+            mint_x_authority,
+            mint_y_authority,
+            mint_x,
+            mint_y,
+            config,
+            pool_state: pool_state_pda(&config),
+            mint_lp,
+            vault_x,
+            vault_y,
+            fee_recipient,
+        }
+    }
+
+    /// Creates X and Y mints with the given decimals and initializes a pool over them
+    pub fn create(
+        svm: &mut LiteSVM,
+        seed: u64,
+        fee: u16,
+        decimals: (u8, u8, u8), // (x, y, lp)
+        curve: CurveParams
+    ) -> Self {
+        let pool = TestPool::new(svm, seed, decimals.0, decimals.1);
+        let initializer = funded_keypair(svm);
+
+        let ix = pool.initialize_ix(&initializer.pubkey(), fee, decimals.2, curve);
+        let result = send_ixs(svm, &[ix], &[&initializer]);
+        assert!(result.is_ok(), "Initialize Instruction Transaction failed: {:?}", result);
+
+        pool
+    }
+
+    pub fn initialize_ix(
+        &self,
+        initializer: &Pubkey,
+        fee: u16,
+        lp_decimals: u8,
+        curve: CurveParams
+    ) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*initializer, true),
+                AccountMeta::new_readonly(self.mint_x, false),
+                AccountMeta::new_readonly(self.mint_y, false),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.pool_state, false),
+                AccountMeta::new_readonly(amm_config_pda(), false),
+                AccountMeta::new(self.fee_recipient, false),
+                AccountMeta::new(pool_index_pda(&self.mint_x), false),
+                AccountMeta::new(pool_index_pda(&self.mint_y), false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new_readonly(token::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: (amm_ix::Initialize {
+                seed: self.seed,
+                fee,
+                authority: Some(self.authority.pubkey()),
+                lp_decimals,
+                curve,
+            }).data(),
+        }
+    }
+
+    /// Mints X and Y to the user's associated token accounts
+    pub fn fund(
+        &self,
+        svm: &mut LiteSVM,
+        user: &Keypair,
+        amount_x: u64,
+        amount_y: u64
+    ) -> (Pubkey, Pubkey) {
+        let (tx, user_x) = create_mint_to_transaction(
+            &self.mint_x_authority,
+            &self.mint_x,
+            user,
+            amount_x,
+            svm.latest_blockhash()
+        );
+        let result = svm.send_transaction(tx);
+        assert!(result.is_ok(), "Mint X Transaction failed: {:?}", result);
+
+        let (tx, user_y) = create_mint_to_transaction(
+            &self.mint_y_authority,
+            &self.mint_y,
+            user,
+            amount_y,
+            svm.latest_blockhash()
+        );
+        let result = svm.send_transaction(tx);
+        assert!(result.is_ok(), "Mint Y Transaction failed: {:?}", result);
+
+        (user_x, user_y)
+    }
+
+    pub fn user_x(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.mint_x)
+    }
+
+    pub fn user_y(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.mint_y)
+    }
+
+    pub fn user_lp(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.mint_lp)
+    }
+
+    pub fn deposit_ix(&self, user: &Pubkey, amount: u64, max_x: u64, max_y: u64) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new_readonly(self.mint_x, false),
+                AccountMeta::new_readonly(self.mint_y, false),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.pool_state, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(self.user_x(user), false),
+                AccountMeta::new(self.user_y(user), false),
+                AccountMeta::new(self.user_lp(user), false),
+                AccountMeta::new_readonly(token::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: (amm_ix::Deposit { amount, max_x, max_y }).data(),
+        }
+    }
+
+    pub fn withdraw_ix(&self, user: &Pubkey, amount: u64, min_x: u64, min_y: u64) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new_readonly(self.mint_x, false),
+                AccountMeta::new_readonly(self.mint_y, false),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.pool_state, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(self.user_x(user), false),
+                AccountMeta::new(self.user_y(user), false),
+                AccountMeta::new(self.user_lp(user), false),
+                AccountMeta::new_readonly(token::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: (amm_ix::Withdraw { amount, min_x, min_y }).data(),
+        }
+    }
+
+    pub fn swap_ix(&self, user: &Pubkey, x_to_y: bool, amount_in: u64, slippage: u16) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new_readonly(self.mint_x, false),
+                AccountMeta::new_readonly(self.mint_y, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.pool_state, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(self.user_x(user), false),
+                AccountMeta::new(self.user_y(user), false),
+                AccountMeta::new_readonly(token::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: (amm_ix::Swap { x_to_y, amount_in, slippage, referral_fee_bps: 0 }).data(),
+        }
+    }
+
+    pub fn swap_lean_ix(&self, user: &Pubkey, x_to_y: bool, amount_in: u64, slippage: u16) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*user, true),
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new(self.pool_state, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(self.user_x(user), false),
+                AccountMeta::new(self.user_y(user), false),
+                AccountMeta::new_readonly(token::ID, false)
+            ],
+            data: (amm_ix::SwapLean { x_to_y, amount_in, slippage, referral_fee_bps: 0 }).data(),
+        }
+    }
+}
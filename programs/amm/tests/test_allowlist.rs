@@ -0,0 +1,154 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+};
+
+use amm::instruction::{ AddAllowEntry, RemoveAllowEntry, SetAccessMode };
+use amm::{ AccessMode, CurveParams, CurveType };
+
+mod helpers;
+use helpers::*;
+
+fn allow_entry(pool: &TestPool, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"allow".as_slice(), pool.config.as_ref(), wallet.as_ref()],
+        &pool.program_id
+    ).0
+}
+
+fn set_access_mode_ix(pool: &TestPool, authority: &Pubkey, access_mode: AccessMode) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (SetAccessMode { access_mode }).data(),
+    }
+}
+
+fn add_allow_entry_ix(pool: &TestPool, authority: &Pubkey, wallet: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(allow_entry(pool, wallet), false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (AddAllowEntry { wallet: *wallet }).data(),
+    }
+}
+
+fn remove_allow_entry_ix(pool: &TestPool, authority: &Pubkey, wallet: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(allow_entry(pool, wallet), false)
+        ],
+        data: RemoveAllowEntry {}.data(),
+    }
+}
+
+/// Deposit with `entry` as the allowlist account
+fn deposit_with_entry(pool: &TestPool, user: &Keypair, entry: &Pubkey) -> Instruction {
+    let mut ix = pool.deposit_ix(&user.pubkey(), 1_000_000, 100_000_000, 100_000_000);
+    ix.accounts.push(AccountMeta::new_readonly(*entry, false));
+    ix
+}
+
+/// Swap with `entry` as the allowlist account, skipping the oracle and wallet volume accounts
+fn swap_with_entry(pool: &TestPool, user: &Keypair, amount_in: u64, entry: &Pubkey) -> Instruction {
+    let mut ix = pool.swap_ix(&user.pubkey(), true, amount_in, 5_000);
+    ix.accounts.push(AccountMeta::new_readonly(pool.program_id, false));
+    ix.accounts.push(AccountMeta::new_readonly(pool.program_id, false));
+    ix.accounts.push(AccountMeta::new_readonly(*entry, false));
+    ix
+}
+
+#[test]
+fn test_permissioned_pool() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        90,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let authority = pool.authority.pubkey();
+
+    let alice = funded_keypair(&mut svm);
+    let bob = funded_keypair(&mut svm);
+    pool.fund(&mut svm, &alice, 1_000_000_000, 1_000_000_000);
+    pool.fund(&mut svm, &bob, 1_000_000_000, 1_000_000_000);
+
+    // Only the authority can restrict the pool or manage entries
+    let ix = set_access_mode_ix(&pool, &bob.pubkey(), AccessMode::Permissioned);
+    let result = send_ixs(&mut svm, &[ix], &[&bob]);
+    assert!(result.is_err(), "SetAccessMode by non-authority should fail");
+    let ix = add_allow_entry_ix(&pool, &bob.pubkey(), &bob.pubkey());
+    let result = send_ixs(&mut svm, &[ix], &[&bob]);
+    assert!(result.is_err(), "AddAllowEntry by non-authority should fail");
+
+    let ixs = [
+        set_access_mode_ix(&pool, &authority, AccessMode::Permissioned),
+        add_allow_entry_ix(&pool, &authority, &alice.pubkey()),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&pool.authority]);
+    assert!(result.is_ok(), "Allowlist setup Transaction failed: {:?}", result);
+
+    let alice_entry = allow_entry(&pool, &alice.pubkey());
+    let bob_entry = allow_entry(&pool, &bob.pubkey());
+
+    // Deposits need the depositor's own entry
+    let ix = pool.deposit_ix(&alice.pubkey(), 1_000_000, 100_000_000, 100_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&alice]);
+    assert!(result.is_err(), "Deposit without an allow entry should fail");
+    let result = send_ixs(&mut svm, &[deposit_with_entry(&pool, &bob, &alice_entry)], &[&bob]);
+    assert!(result.is_err(), "Deposit with another wallet's entry should fail");
+    let result = send_ixs(&mut svm, &[deposit_with_entry(&pool, &alice, &alice_entry)], &[&alice]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // So do swaps
+    let result = send_ixs(&mut svm, &[swap_with_entry(&pool, &bob, 1_000_000, &bob_entry)], &[&bob]);
+    assert!(result.is_err(), "Swap without an allow entry should fail");
+    let result = send_ixs(&mut svm, &[swap_with_entry(&pool, &alice, 1_000_000, &alice_entry)], &[&alice]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    // Removing the entry refunds its rent and locks Alice out of trading but not withdrawing
+    let ix = remove_allow_entry_ix(&pool, &authority, &alice.pubkey());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "RemoveAllowEntry Instruction Transaction failed: {:?}", result);
+    assert!(svm.get_account(&alice_entry).map_or(true, |account| account.lamports == 0));
+
+    let result = send_ixs(&mut svm, &[swap_with_entry(&pool, &alice, 2_000_000, &alice_entry)], &[&alice]);
+    assert!(result.is_err(), "Swap after removal should fail");
+
+    let withdraw_ix = pool.withdraw_ix(&alice.pubkey(), 500_000, 0, 0);
+    let result = send_ixs(&mut svm, &[withdraw_ix], &[&alice]);
+    assert!(result.is_ok(), "Withdraw Instruction Transaction failed: {:?}", result);
+
+    // Opening the pool again lets anyone trade
+    let ix = set_access_mode_ix(&pool, &authority, AccessMode::Open);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetAccessMode Instruction Transaction failed: {:?}", result);
+
+    let swap_ix = pool.swap_ix(&bob.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&bob]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+}
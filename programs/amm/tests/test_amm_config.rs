@@ -0,0 +1,216 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use anchor_spl::{ associated_token::{ self, get_associated_token_address }, token };
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+use amm::instruction::{ CollectProtocolFees, UpdateAmmConfig };
+use amm::{ CurveParams, CurveType };
+
+mod helpers;
+use helpers::*;
+
+fn stable_curve() -> CurveParams {
+    CurveParams {
+        curve_type: CurveType::StableSwap,
+        amp: 100,
+        weight_x: 0,
+        weight_y: 0,
+    }
+}
+
+#[test]
+fn test_fee_tiers() {
+    let mut svm = setup_svm();
+
+    // The first caller cannot claim the config; only the upgrade authority can
+    let intruder_keypair = funded_keypair(&mut svm);
+    let ix = initialize_amm_config_ix(&intruder_keypair.pubkey(), &Pubkey::new_unique(), 0, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&intruder_keypair]);
+    assert!(result.is_err(), "InitializeAmmConfig by a non-upgrade-authority should fail");
+
+    let admin_keypair = upgrade_authority();
+    let admin = admin_keypair.pubkey();
+    svm.airdrop(&admin, 100 * LAMPORTS_PER_SOL).unwrap();
+    let ixs = [
+        initialize_amm_config_ix(&admin, &Pubkey::new_unique(), 0, 0),
+        add_fee_tier_ix(&admin, 30),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&admin_keypair]);
+    assert!(result.is_ok(), "InitializeAmmConfig Instruction Transaction failed: {:?}", result);
+
+    // Only the admin may manage tiers
+    let ix = add_fee_tier_ix(&intruder_keypair.pubkey(), 25);
+    let result = send_ixs(&mut svm, &[ix], &[&intruder_keypair]);
+    assert!(result.is_err(), "AddFeeTier by a non-admin should fail");
+
+    // A fee outside the registered tiers is rejected
+    let initializer_keypair = funded_keypair(&mut svm);
+    let initializer = initializer_keypair.pubkey();
+    let pool = TestPool::new(&mut svm, 60, 6, 6);
+    let ix = pool.initialize_ix(&initializer, 25, 6, stable_curve());
+    let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+    assert!(result.is_err(), "Initialize with an unregistered fee should fail");
+
+    let ix = pool.initialize_ix(&initializer, 30, 6, stable_curve());
+    let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+    assert!(result.is_ok(), "Initialize Instruction Transaction failed: {:?}", result);
+
+    // Disabling the tier blocks new pools but leaves the existing one alone
+    let ix = disable_fee_tier_ix(&admin, 30);
+    let result = send_ixs(&mut svm, &[ix], &[&admin_keypair]);
+    assert!(result.is_ok(), "DisableFeeTier Instruction Transaction failed: {:?}", result);
+
+    let other_pool = TestPool::new(&mut svm, 61, 6, 6);
+    let ix = other_pool.initialize_ix(&initializer, 30, 6, stable_curve());
+    let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+    assert!(result.is_err(), "Initialize with a disabled fee tier should fail");
+    assert_eq!(fetch_config(&svm, &pool.config).fee, 30);
+
+    let ix = disable_fee_tier_ix(&admin, 5);
+    let result = send_ixs(&mut svm, &[ix], &[&admin_keypair]);
+    assert!(result.is_err(), "Disabling an unknown fee tier should fail");
+
+    // Adding the tier again re-enables it instead of duplicating it
+    let ix = add_fee_tier_ix(&admin, 30);
+    let result = send_ixs(&mut svm, &[ix], &[&admin_keypair]);
+    assert!(result.is_ok(), "AddFeeTier Instruction Transaction failed: {:?}", result);
+    assert_eq!(fetch_amm_config(&svm).fee_tiers.len(), 1);
+
+    svm.expire_blockhash();
+    let ix = other_pool.initialize_ix(&initializer, 30, 6, stable_curve());
+    let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+    assert!(result.is_ok(), "Initialize Instruction Transaction failed: {:?}", result);
+}
+
+#[test]
+fn test_creation_and_protocol_fees() {
+    let mut svm = setup_svm();
+
+    // 20% of every swap fee goes to the protocol; pools cost 1 SOL to create
+    let admin_keypair = upgrade_authority();
+    let admin = admin_keypair.pubkey();
+    svm.airdrop(&admin, 100 * LAMPORTS_PER_SOL).unwrap();
+    let fee_recipient = Pubkey::new_unique();
+    let ixs = [
+        initialize_amm_config_ix(&admin, &fee_recipient, 2_000, LAMPORTS_PER_SOL),
+        add_fee_tier_ix(&admin, 100),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&admin_keypair]);
+    assert!(result.is_ok(), "InitializeAmmConfig Instruction Transaction failed: {:?}", result);
+
+    let pool = TestPool::create(&mut svm, 62, 100, (6, 6, 6), stable_curve());
+    assert_eq!(svm.get_account(&fee_recipient).unwrap().lamports, LAMPORTS_PER_SOL);
+    assert_eq!(fetch_config(&svm, &pool.config).protocol_fee_share, 2_000);
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (_, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // 10 X in pays a 0.1 X fee, 0.02 X of which is owed to the protocol
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 100);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &user_y), 1_009_899_512);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.protocol_fees_x, 20_000);
+    assert_eq!(state.protocol_fees_y, 0);
+
+    let recipient_x = get_associated_token_address(&fee_recipient, &pool.mint_x);
+    let recipient_y = get_associated_token_address(&fee_recipient, &pool.mint_y);
+    let collect_ix = |admin: &Pubkey| Instruction {
+        program_id: amm::id(),
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new_readonly(fee_recipient, false),
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new(recipient_x, false),
+            AccountMeta::new(recipient_y, false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: CollectProtocolFees {}.data(),
+    };
+
+    let result = send_ixs(&mut svm, &[collect_ix(&trader)], &[&trader_keypair]);
+    assert!(result.is_err(), "CollectProtocolFees by a non-admin should fail");
+
+    let result = send_ixs(&mut svm, &[collect_ix(&admin)], &[&admin_keypair]);
+    assert!(result.is_ok(), "CollectProtocolFees Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &recipient_x), 20_000);
+    assert_eq!(token_balance(&svm, &recipient_y), 0);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_009_980_000);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.protocol_fees_x, 0);
+}
+
+#[test]
+fn test_update_amm_config() {
+    let mut svm = setup_svm();
+    ensure_amm_config(&mut svm);
+
+    let update_ix = |admin: &Pubkey, new_admin: &Pubkey, fee_recipient: &Pubkey, protocol_fee_share: u16| {
+        Instruction {
+            program_id: amm::id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*admin, true),
+                AccountMeta::new(amm_config_pda(), false)
+            ],
+            data: (UpdateAmmConfig {
+                admin: *new_admin,
+                fee_recipient: *fee_recipient,
+                protocol_fee_share,
+                pool_creation_fee: LAMPORTS_PER_SOL,
+            }).data(),
+        }
+    };
+    let admin_keypair = upgrade_authority();
+    let admin = admin_keypair.pubkey();
+    let new_admin_keypair = funded_keypair(&mut svm);
+    let new_admin = new_admin_keypair.pubkey();
+    let fee_recipient = Pubkey::new_unique();
+
+    let ix = update_ix(&new_admin, &new_admin, &fee_recipient, 1_000);
+    let result = send_ixs(&mut svm, &[ix], &[&new_admin_keypair]);
+    assert!(result.is_err(), "UpdateAmmConfig by a non-admin should fail");
+
+    let ix = update_ix(&admin, &new_admin, &fee_recipient, 10_001);
+    let result = send_ixs(&mut svm, &[ix], &[&admin_keypair]);
+    assert!(result.is_err(), "A protocol fee share above 100% should fail");
+
+    let ix = update_ix(&admin, &new_admin, &fee_recipient, 1_000);
+    let result = send_ixs(&mut svm, &[ix], &[&admin_keypair]);
+    assert!(result.is_ok(), "UpdateAmmConfig Instruction Transaction failed: {:?}", result);
+    let amm_config = fetch_amm_config(&svm);
+    assert_eq!(amm_config.admin, new_admin);
+    assert_eq!(amm_config.fee_recipient, fee_recipient);
+    assert_eq!(amm_config.protocol_fee_share, 1_000);
+    assert_eq!(amm_config.pool_creation_fee, LAMPORTS_PER_SOL);
+
+    // The old admin is out; the new one manages tiers
+    let ix = add_fee_tier_ix(&admin, 25);
+    let result = send_ixs(&mut svm, &[ix], &[&admin_keypair]);
+    assert!(result.is_err(), "AddFeeTier by the previous admin should fail");
+    let ix = add_fee_tier_ix(&new_admin, 25);
+    let result = send_ixs(&mut svm, &[ix], &[&new_admin_keypair]);
+    assert!(result.is_ok(), "AddFeeTier Instruction Transaction failed: {:?}", result);
+}
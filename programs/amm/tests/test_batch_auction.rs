@@ -0,0 +1,538 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use anchor_spl::{ associated_token::{ self, get_associated_token_address }, token };
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signer::{ keypair::Keypair, Signer },
+    system_program,
+};
+
+use amm::instruction::{
+    ClaimIntent,
+    InitWalletVolume,
+    SetBatchMode,
+    SetLocked,
+    SetVolumeLimits,
+    SettleBatch,
+    SubmitIntent,
+};
+use amm::{ CurveParams, CurveType, VolumeLimits, BATCH_SETTLEMENT_SLOTS, MAX_BATCH_INTENTS };
+
+mod helpers;
+use helpers::*;
+
+const WINDOW: u64 = 10;
+const MIN_INTENT: u64 = 1_000_000;
+
+fn batch_pda(pool: &TestPool, batch_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"batch".as_slice(), pool.config.as_ref(), batch_id.to_le_bytes().as_ref()],
+        &pool.program_id
+    ).0
+}
+
+fn intent_pda(pool: &TestPool, batch: &Pubkey, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"intent".as_slice(), batch.as_ref(), owner.as_ref()], &pool.program_id).0
+}
+
+fn set_batch_mode_ix(
+    pool: &TestPool,
+    authority: &Pubkey,
+    batch_window_slots: u64,
+    min_intent_amount: u64
+) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![AccountMeta::new_readonly(*authority, true), AccountMeta::new(pool.config, false)],
+        data: (SetBatchMode { batch_window_slots, min_intent_amount }).data(),
+    }
+}
+
+fn submit_intent_ix(
+    pool: &TestPool,
+    owner: &Pubkey,
+    batch_id: u64,
+    x_to_y: bool,
+    amount_in: u64,
+    min_amount_out: u64
+) -> Instruction {
+    let batch = batch_pda(pool, batch_id);
+    let owner_src = if x_to_y { pool.user_x(owner) } else { pool.user_y(owner) };
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new_readonly(pool.pool_state, false),
+            AccountMeta::new(batch, false),
+            AccountMeta::new(get_associated_token_address(&batch, &pool.mint_x), false),
+            AccountMeta::new(get_associated_token_address(&batch, &pool.mint_y), false),
+            AccountMeta::new(intent_pda(pool, &batch, owner), false),
+            AccountMeta::new(owner_src, false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (SubmitIntent { batch_id, x_to_y, amount_in, min_amount_out }).data(),
+    }
+}
+
+fn settle_batch_ix(pool: &TestPool, batch_id: u64, owners: &[Pubkey]) -> Instruction {
+    let batch = batch_pda(pool, batch_id);
+    let mut intents: Vec<Pubkey> = owners
+        .iter()
+        .map(|owner| intent_pda(pool, &batch, owner))
+        .collect();
+    intents.sort();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(pool.config, false),
+        AccountMeta::new(pool.pool_state, false),
+        AccountMeta::new(batch, false),
+        AccountMeta::new(get_associated_token_address(&batch, &pool.mint_x), false),
+        AccountMeta::new(get_associated_token_address(&batch, &pool.mint_y), false),
+        AccountMeta::new(pool.vault_x, false),
+        AccountMeta::new(pool.vault_y, false),
+        AccountMeta::new_readonly(token::ID, false),
+        // No oracle
+        AccountMeta::new_readonly(pool.program_id, false)
+    ];
+    accounts.extend(intents.iter().map(|intent| AccountMeta::new(*intent, false)));
+    Instruction {
+        program_id: pool.program_id,
+        accounts,
+        data: SettleBatch {}.data(),
+    }
+}
+
+fn claim_intent_ix(pool: &TestPool, owner: &Pubkey, batch_id: u64) -> Instruction {
+    let batch = batch_pda(pool, batch_id);
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(batch, false),
+            AccountMeta::new(intent_pda(pool, &batch, owner), false),
+            AccountMeta::new(get_associated_token_address(&batch, &pool.mint_x), false),
+            AccountMeta::new(get_associated_token_address(&batch, &pool.mint_y), false),
+            AccountMeta::new(pool.user_x(owner), false),
+            AccountMeta::new(pool.user_y(owner), false),
+            AccountMeta::new_readonly(token::ID, false)
+        ],
+        data: ClaimIntent {}.data(),
+    }
+}
+
+fn authority_ix(pool: &TestPool, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.authority.pubkey(), true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data,
+    }
+}
+
+fn trader(svm: &mut LiteSVM, pool: &TestPool, amount_x: u64, amount_y: u64) -> Keypair {
+    let keypair = funded_keypair(svm);
+    pool.fund(svm, &keypair, amount_x, amount_y);
+    keypair
+}
+
+/// A constant-product pool holding 1000 X and 1000 Y, trading in batches of `WINDOW` slots.
+fn batch_pool(svm: &mut LiteSVM, seed: u64) -> TestPool {
+    let pool = TestPool::create(
+        svm,
+        seed,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let provider_keypair = funded_keypair(svm);
+    let provider = provider_keypair.pubkey();
+    pool.fund(svm, &provider_keypair, 1_000_000_000, 1_000_000_000);
+    let ix = pool.deposit_ix(&provider, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(svm, &[ix], &[&provider_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let ix = set_batch_mode_ix(&pool, &pool.authority.pubkey(), WINDOW, MIN_INTENT);
+    let result = send_ixs(svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetBatchMode Instruction Transaction failed: {:?}", result);
+    pool
+}
+
+#[test]
+fn test_batch_auction() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        101,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let provider_keypair = funded_keypair(&mut svm);
+    let provider = provider_keypair.pubkey();
+    pool.fund(&mut svm, &provider_keypair, 1_000_000_000, 1_000_000_000);
+    let ix = pool.deposit_ix(&provider, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&provider_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let alice_keypair = trader(&mut svm, &pool, 50_000_000, 0);
+    let alice = alice_keypair.pubkey();
+    let bob_keypair = trader(&mut svm, &pool, 0, 20_000_000);
+    let bob = bob_keypair.pubkey();
+    let carol_keypair = trader(&mut svm, &pool, 10_000_000, 0);
+    let carol = carol_keypair.pubkey();
+    let authority = pool.authority.pubkey();
+
+    let ix = set_batch_mode_ix(&pool, &alice, WINDOW, MIN_INTENT);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "SetBatchMode by non-authority should fail");
+
+    let ix = set_batch_mode_ix(&pool, &authority, 1_000, MIN_INTENT);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "A batch window over the maximum should fail");
+
+    let ix = set_batch_mode_ix(&pool, &authority, WINDOW, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "Batch mode without a minimum intent should fail");
+
+    let ix = set_batch_mode_ix(&pool, &authority, WINDOW, MIN_INTENT);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetBatchMode Instruction Transaction failed: {:?}", result);
+    assert_eq!(fetch_config(&svm, &pool.config).batch_window_slots, WINDOW);
+
+    let ix = pool.swap_ix(&alice, true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "Instant swaps should fail in batch mode");
+
+    // Slots 100 to 109 make up batch 10
+    svm.warp_to_slot(105);
+    let batch_id = 105 / WINDOW;
+    let batch = batch_pda(&pool, batch_id);
+
+    // Dust cannot take one of the batch's slots
+    let ix = submit_intent_ix(&pool, &alice, batch_id, true, MIN_INTENT - 1, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "An intent below the minimum should fail");
+
+    let ix = submit_intent_ix(&pool, &alice, batch_id - 1, true, 50_000_000, 40_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "Submitting to a past batch should fail");
+
+    let ix = submit_intent_ix(&pool, &alice, batch_id, true, 50_000_000, 40_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+    let ix = submit_intent_ix(&pool, &bob, batch_id, false, 20_000_000, 15_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&bob_keypair]);
+    assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+    // Asks twice the pool price; left out and refunded
+    let ix = submit_intent_ix(&pool, &carol, batch_id, true, 10_000_000, 20_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&carol_keypair]);
+    assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &get_associated_token_address(&batch, &pool.mint_x)), 60_000_000);
+    assert_eq!(token_balance(&svm, &get_associated_token_address(&batch, &pool.mint_y)), 20_000_000);
+
+    let owners = [alice, bob, carol];
+    let ix = settle_batch_ix(&pool, batch_id, &owners);
+    let result = send_ixs(&mut svm, &[ix], &[&carol_keypair]);
+    assert!(result.is_err(), "Settling an open batch should fail");
+
+    let ix = claim_intent_ix(&pool, &alice, batch_id);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "Claiming before settlement should fail");
+
+    svm.warp_to_slot(110);
+    let dave_keypair = trader(&mut svm, &pool, 1_000_000, 0);
+    let ix = submit_intent_ix(&pool, &dave_keypair.pubkey(), batch_id, true, 1_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&dave_keypair]);
+    assert!(result.is_err(), "Submitting to a closed batch should fail");
+
+    let ix = settle_batch_ix(&pool, batch_id, &[alice, bob]);
+    let result = send_ixs(&mut svm, &[ix], &[&carol_keypair]);
+    assert!(result.is_err(), "Settling without every intent should fail");
+
+    let ix = settle_batch_ix(&pool, batch_id, &owners);
+    let result = send_ixs(&mut svm, &[ix], &[&carol_keypair]);
+    assert!(result.is_ok(), "SettleBatch Instruction Transaction failed: {:?}", result);
+
+    svm.expire_blockhash();
+    let ix = settle_batch_ix(&pool, batch_id, &owners);
+    let result = send_ixs(&mut svm, &[ix], &[&carol_keypair]);
+    assert!(result.is_err(), "Settling a batch twice should fail");
+
+    for (keypair, owner) in [(&alice_keypair, &alice), (&bob_keypair, &bob), (&carol_keypair, &carol)] {
+        let ix = claim_intent_ix(&pool, owner, batch_id);
+        let result = send_ixs(&mut svm, &[ix], &[keypair]);
+        assert!(result.is_ok(), "ClaimIntent Instruction Transaction failed: {:?}", result);
+        let intent = intent_pda(&pool, &batch, owner);
+        assert!(svm.get_account(&intent).map_or(true, |account| account.lamports == 0));
+    }
+
+    // Only the imbalance traded with the pool, and both sides got the same price:
+    // alice_y / 50 Y per X, and 20 / bob_x Y per X
+    let alice_y = token_balance(&svm, &pool.user_y(&alice));
+    let bob_x = token_balance(&svm, &pool.user_x(&bob));
+    assert!(alice_y >= 40_000_000 && bob_x >= 15_000_000);
+    let cross = alice_y as u128 * bob_x as u128;
+    let expected = 50_000_000u128 * 20_000_000;
+    assert!(cross.abs_diff(expected) * 10_000 < expected, "Sides cleared at different prices");
+    assert_eq!(token_balance(&svm, &pool.user_x(&carol)), 10_000_000);
+    assert_eq!(token_balance(&svm, &pool.user_y(&carol)), 0);
+
+    // Switching batch mode off restores instant swaps
+    let ix = set_batch_mode_ix(&pool, &authority, 0, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetBatchMode Instruction Transaction failed: {:?}", result);
+    let ix = pool.swap_ix(&carol, true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[ix], &[&carol_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+}
+
+#[test]
+fn test_batch_mode_requires_constant_product() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        102,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let ix = set_batch_mode_ix(&pool, &pool.authority.pubkey(), WINDOW, MIN_INTENT);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "Batch mode on a StableSwap pool should fail");
+}
+
+#[test]
+fn test_batch_intent_cap() {
+    let mut svm = setup_svm();
+    let pool = batch_pool(&mut svm, 104);
+
+    svm.warp_to_slot(205);
+    let batch_id = 205 / WINDOW;
+    let traders: Vec<Keypair> = (0..=MAX_BATCH_INTENTS)
+        .map(|_| trader(&mut svm, &pool, 1_000_000, 0))
+        .collect();
+    let (last, traders) = traders.split_last().unwrap();
+
+    for keypair in traders {
+        let ix = submit_intent_ix(&pool, &keypair.pubkey(), batch_id, true, 1_000_000, 0);
+        let result = send_ixs(&mut svm, &[ix], &[keypair]);
+        assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+    }
+    let ix = submit_intent_ix(&pool, &last.pubkey(), batch_id, true, 1_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[last]);
+    assert!(result.is_err(), "Submitting to a full batch should fail");
+
+    // A full batch still settles in one transaction
+    svm.warp_to_slot(210);
+    let owners: Vec<Pubkey> = traders.iter().map(|keypair| keypair.pubkey()).collect();
+    let ix = settle_batch_ix(&pool, batch_id, &owners);
+    let result = send_ixs(&mut svm, &[ix], &[last]);
+    assert!(result.is_ok(), "SettleBatch Instruction Transaction failed: {:?}", result);
+}
+
+#[test]
+fn test_batch_refund_after_deadline() {
+    let mut svm = setup_svm();
+    let pool = batch_pool(&mut svm, 105);
+
+    let alice_keypair = trader(&mut svm, &pool, 10_000_000, 0);
+    let alice = alice_keypair.pubkey();
+    let bob_keypair = trader(&mut svm, &pool, 0, 5_000_000);
+    let bob = bob_keypair.pubkey();
+
+    svm.warp_to_slot(205);
+    let batch_id = 205 / WINDOW;
+    let ix = submit_intent_ix(&pool, &alice, batch_id, true, 10_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+    let ix = submit_intent_ix(&pool, &bob, batch_id, false, 5_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&bob_keypair]);
+    assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+
+    // A locked pool keeps the batch from settling
+    svm.warp_to_slot(210);
+    let ix = authority_ix(&pool, (SetLocked { locked: true }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+    let ix = settle_batch_ix(&pool, batch_id, &[alice, bob]);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "Settling on a locked pool should fail");
+
+    let deadline = 210 + BATCH_SETTLEMENT_SLOTS;
+    svm.warp_to_slot(deadline - 1);
+    let ix = claim_intent_ix(&pool, &alice, batch_id);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "Refunding before the deadline should fail");
+
+    // Past the deadline the batch can only be refunded, even once the pool reopens
+    svm.warp_to_slot(deadline);
+    let ix = authority_ix(&pool, (SetLocked { locked: false }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+    svm.expire_blockhash();
+    let ix = settle_batch_ix(&pool, batch_id, &[alice, bob]);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "Settling an expired batch should fail");
+
+    for (keypair, owner) in [(&alice_keypair, &alice), (&bob_keypair, &bob)] {
+        let ix = claim_intent_ix(&pool, owner, batch_id);
+        let result = send_ixs(&mut svm, &[ix], &[keypair]);
+        assert!(result.is_ok(), "ClaimIntent Instruction Transaction failed: {:?}", result);
+    }
+    assert_eq!(token_balance(&svm, &pool.user_x(&alice)), 10_000_000);
+    assert_eq!(token_balance(&svm, &pool.user_y(&alice)), 0);
+    assert_eq!(token_balance(&svm, &pool.user_x(&bob)), 0);
+    assert_eq!(token_balance(&svm, &pool.user_y(&bob)), 5_000_000);
+}
+
+#[test]
+fn test_batch_wallet_volume_limit() {
+    let mut svm = setup_svm();
+    let pool = batch_pool(&mut svm, 106);
+
+    // 15 X per wallet, over a window longer than the batch
+    let ix = authority_ix(
+        &pool,
+        (SetVolumeLimits {
+            limits: VolumeLimits {
+                max_swap_volume: 0,
+                max_window_volume: 0,
+                max_wallet_volume: 15_000_000,
+                window_slots: 1_000,
+            },
+        }).data()
+    );
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Set Volume Limits Transaction failed: {:?}", result);
+
+    let wallet_volume = |owner: &Pubkey| {
+        Pubkey::find_program_address(
+            &[b"wallet_volume".as_slice(), pool.config.as_ref(), owner.as_ref()],
+            &pool.program_id
+        ).0
+    };
+    let volume_trader = |svm: &mut LiteSVM, amount_x: u64, amount_y: u64| {
+        let keypair = trader(svm, &pool, amount_x, amount_y);
+        let ix = Instruction {
+            program_id: pool.program_id,
+            accounts: vec![
+                AccountMeta::new(keypair.pubkey(), true),
+                AccountMeta::new_readonly(pool.config, false),
+                AccountMeta::new(wallet_volume(&keypair.pubkey()), false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: InitWalletVolume {}.data(),
+        };
+        let result = send_ixs(svm, &[ix], &[&keypair]);
+        assert!(result.is_ok(), "Init Wallet Volume Transaction failed: {:?}", result);
+        keypair
+    };
+    let submit = |svm: &mut LiteSVM, owner: &Keypair, batch_id: u64, x_to_y: bool, amount_in: u64| {
+        svm.expire_blockhash();
+        let mut ix = submit_intent_ix(&pool, &owner.pubkey(), batch_id, x_to_y, amount_in, 0);
+        ix.accounts.push(AccountMeta::new(wallet_volume(&owner.pubkey()), false));
+        send_ixs(svm, &[ix], &[owner])
+    };
+
+    let alice_keypair = volume_trader(&mut svm, 20_000_000, 0);
+    let alice = alice_keypair.pubkey();
+    let bob_keypair = volume_trader(&mut svm, 0, 20_000_000);
+    let bob = bob_keypair.pubkey();
+
+    svm.warp_to_slot(205);
+    let batch_id = 205 / WINDOW;
+    let ix = submit_intent_ix(&pool, &alice, batch_id, true, 10_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "SubmitIntent without the wallet volume account should fail");
+
+    assert!(submit(&mut svm, &alice_keypair, batch_id, true, 20_000_000).is_err(), "Intent over the wallet limit should fail");
+    assert!(submit(&mut svm, &alice_keypair, batch_id, true, 10_000_000).is_ok());
+    // Y is valued in X at the pool price
+    assert!(submit(&mut svm, &bob_keypair, batch_id, false, 20_000_000).is_err(), "Intent over the wallet limit should fail");
+    assert!(submit(&mut svm, &bob_keypair, batch_id, false, 5_000_000).is_ok());
+
+    // The net trade belongs to no wallet; its intents were charged as they came in
+    svm.warp_to_slot(210);
+    let ix = settle_batch_ix(&pool, batch_id, &[alice, bob]);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_ok(), "SettleBatch Instruction Transaction failed: {:?}", result);
+
+    for (keypair, owner) in [(&alice_keypair, &alice), (&bob_keypair, &bob)] {
+        let ix = claim_intent_ix(&pool, owner, batch_id);
+        let result = send_ixs(&mut svm, &[ix], &[keypair]);
+        assert!(result.is_ok(), "ClaimIntent Instruction Transaction failed: {:?}", result);
+    }
+    assert!(token_balance(&svm, &pool.user_y(&alice)) > 9_000_000);
+    assert!(token_balance(&svm, &pool.user_x(&bob)) > 4_900_000);
+}
+
+#[test]
+fn test_matched_batch_respects_lock() {
+    let mut svm = setup_svm();
+    let pool = batch_pool(&mut svm, 107);
+
+    let alice_keypair = trader(&mut svm, &pool, 10_000_000, 0);
+    let alice = alice_keypair.pubkey();
+    let bob_keypair = trader(&mut svm, &pool, 0, 10_000_000);
+    let bob = bob_keypair.pubkey();
+
+    // Equal sides at the pool price meet within the fee and never trade with the pool
+    svm.warp_to_slot(205);
+    let batch_id = 205 / WINDOW;
+    let ix = submit_intent_ix(&pool, &alice, batch_id, true, 10_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+    let ix = submit_intent_ix(&pool, &bob, batch_id, false, 10_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&bob_keypair]);
+    assert!(result.is_ok(), "SubmitIntent Instruction Transaction failed: {:?}", result);
+
+    svm.warp_to_slot(210);
+    let ix = authority_ix(&pool, (SetLocked { locked: true }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+    let ix = settle_batch_ix(&pool, batch_id, &[alice, bob]);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_err(), "Settling a matched batch on a locked pool should fail");
+
+    let ix = authority_ix(&pool, (SetLocked { locked: false }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+    svm.expire_blockhash();
+    let ix = settle_batch_ix(&pool, batch_id, &[alice, bob]);
+    let result = send_ixs(&mut svm, &[ix], &[&alice_keypair]);
+    assert!(result.is_ok(), "SettleBatch Instruction Transaction failed: {:?}", result);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!((state.reserve_x, state.reserve_y), (1_000_000_000, 1_000_000_000));
+}
@@ -0,0 +1,113 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use anchor_spl::{ associated_token::{ self, get_associated_token_address }, token };
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+use amm::instruction::ClosePool;
+use amm::{ CurveParams, CurveType };
+
+mod helpers;
+use helpers::*;
+
+fn close_pool_ix(pool: &TestPool, authority: &Pubkey, recipient: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new_readonly(pool.mint_lp, false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new(get_associated_token_address(authority, &pool.mint_x), false),
+            AccountMeta::new(get_associated_token_address(authority, &pool.mint_y), false),
+            AccountMeta::new(pool_index_pda(&pool.mint_x), false),
+            AccountMeta::new(pool_index_pda(&pool.mint_y), false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: ClosePool {}.data(),
+    }
+}
+
+#[test]
+fn test_close_pool() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        70,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let authority = pool.authority.pubkey();
+    let recipient = Pubkey::new_unique();
+
+    let depositer_keypair = funded_keypair(&mut svm);
+    let depositer = depositer_keypair.pubkey();
+    pool.fund(&mut svm, &depositer_keypair, 100_000_000, 100_000_000);
+
+    let deposit_ix = pool.deposit_ix(&depositer, 1_000_000, 100_000_000, 100_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&depositer_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // Outstanding LP tokens keep the pool open
+    let ix = close_pool_ix(&pool, &authority, &recipient);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "ClosePool with LP supply should fail");
+
+    let withdraw_ix = pool.withdraw_ix(&depositer, 1_000_000, 0, 0);
+    let result = send_ixs(&mut svm, &[withdraw_ix], &[&depositer_keypair]);
+    assert!(result.is_ok(), "Withdraw Instruction Transaction failed: {:?}", result);
+
+    // Dust sent straight to a vault is swept to the authority
+    let mint_ix = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &pool.mint_x,
+        &pool.vault_x,
+        &pool.mint_x_authority.pubkey(),
+        &[],
+        1_234
+    ).unwrap();
+    let result = send_ixs(&mut svm, &[mint_ix], &[&depositer_keypair, &pool.mint_x_authority]);
+    assert!(result.is_ok(), "MintTo Transaction failed: {:?}", result);
+
+    // Only the pool authority may close it
+    let ix = close_pool_ix(&pool, &depositer, &recipient);
+    let result = send_ixs(&mut svm, &[ix], &[&depositer_keypair]);
+    assert!(result.is_err(), "ClosePool by a non-authority should fail");
+
+    let rent = svm.get_account(&pool.config).unwrap().lamports +
+        svm.get_account(&pool.pool_state).unwrap().lamports +
+        svm.get_account(&pool.vault_x).unwrap().lamports +
+        svm.get_account(&pool.vault_y).unwrap().lamports;
+
+    svm.expire_blockhash();
+    let ix = close_pool_ix(&pool, &authority, &recipient);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "ClosePool Instruction Transaction failed: {:?}", result);
+
+    for closed in [pool.config, pool.pool_state, pool.vault_x, pool.vault_y] {
+        assert!(svm.get_account(&closed).map_or(true, |account| account.lamports == 0));
+    }
+    assert!(svm.get_account(&recipient).unwrap().lamports > rent);
+    assert!(token_balance(&svm, &get_associated_token_address(&authority, &pool.mint_x)) >= 1_234);
+    assert!(fetch_pool_index(&svm, &pool.mint_x).pools.is_empty());
+    assert!(fetch_pool_index(&svm, &pool.mint_y).pools.is_empty());
+}
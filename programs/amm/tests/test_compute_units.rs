@@ -0,0 +1,95 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use solana_sdk::{ instruction::{ AccountMeta, Instruction }, signer::{ keypair::Keypair, Signer } };
+
+use amm::instruction::{ SetLocked, SyncReserves };
+use amm::{ CurveParams, CurveType };
+
+mod helpers;
+use helpers::*;
+
+// Regression ceilings for the hot paths. Swap, deposit and withdraw only
+// touch the zero-copy PoolState for writes and read Config in place.
+const SWAP_CU_BUDGET: u64 = 100_000;
+// The lean swap skips the mint loads and the four vault and user ATA derivations
+const SWAP_LEAN_CU_BUDGET: u64 = 70_000;
+const DEPOSIT_CU_BUDGET: u64 = 150_000;
+const WITHDRAW_CU_BUDGET: u64 = 150_000;
+
+fn consumed(svm: &mut litesvm::LiteSVM, ix: Instruction, signer: &Keypair) -> u64 {
+    svm.expire_blockhash();
+    let result = send_ixs(svm, &[ix], &[signer]);
+    assert!(result.is_ok(), "Transaction failed: {:?}", result);
+    result.unwrap().compute_units_consumed
+}
+
+#[test]
+fn test_compute_units() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        94,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let deposit = consumed(&mut svm, deposit_ix, &trader_keypair);
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 100);
+    let swap = consumed(&mut svm, swap_ix, &trader_keypair);
+    let swap_lean_ix = pool.swap_lean_ix(&trader, true, 10_000_000, 100);
+    let swap_lean = consumed(&mut svm, swap_lean_ix, &trader_keypair);
+    let withdraw_ix = pool.withdraw_ix(&trader, 100_000_000, 0, 0);
+    let withdraw = consumed(&mut svm, withdraw_ix, &trader_keypair);
+
+    // Sync writes only the zero-copy state; SetLocked round-trips the Borsh Config
+    let sync_ix = Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new_readonly(pool.vault_x, false),
+            AccountMeta::new_readonly(pool.vault_y, false)
+        ],
+        data: SyncReserves {}.data(),
+    };
+    let sync = consumed(&mut svm, sync_ix, &trader_keypair);
+    let lock_ix = Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.authority.pubkey(), true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data: (SetLocked { locked: false }).data(),
+    };
+    let set_locked = consumed(&mut svm, lock_ix, &pool.authority);
+
+    assert!(swap <= SWAP_CU_BUDGET, "Swap used {} CU, budget is {}", swap, SWAP_CU_BUDGET);
+    assert!(swap_lean <= SWAP_LEAN_CU_BUDGET, "SwapLean used {} CU, budget is {}", swap_lean, SWAP_LEAN_CU_BUDGET);
+    assert!(swap_lean < swap, "SwapLean used {} CU, more than Swap's {}", swap_lean, swap);
+    assert!(deposit <= DEPOSIT_CU_BUDGET, "Deposit used {} CU, budget is {}", deposit, DEPOSIT_CU_BUDGET);
+    assert!(withdraw <= WITHDRAW_CU_BUDGET, "Withdraw used {} CU, budget is {}", withdraw, WITHDRAW_CU_BUDGET);
+    // Writing the zero-copy state, mints and vaults loaded, must stay cheaper than re-serialising
+    // the Borsh Config alone; otherwise moving the swap state out of Config bought nothing
+    assert!(
+        sync < set_locked,
+        "SyncReserves used {} CU, not less than SetLocked's {}",
+        sync,
+        set_locked
+    );
+}
@@ -0,0 +1,503 @@
+// #![cfg(feature = "test-sbf")]
+#![allow(deprecated)]
+
+use amm::{
+    instruction as amm_ix,
+    math::{ MAX_SQRT_PRICE_X64, MIN_SQRT_PRICE_X64 },
+    AccessMode,
+    ConcentratedPool,
+    CurveParams,
+    CurveType,
+    DynamicFeeParams,
+    OracleParams,
+    Position,
+    VolumeLimits,
+};
+use anchor_lang::{ AccountDeserialize, InstructionData };
+use anchor_spl::{ associated_token, token };
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+mod helpers;
+use helpers::*;
+
+const TICK_SPACING: u16 = 10;
+
+fn pool_address(pool: &TestPool) -> Pubkey {
+    Pubkey::find_program_address(&[b"clmm".as_slice(), pool.config.as_ref()], &pool.program_id).0
+}
+
+fn tick_array_address(pool: &TestPool, start_tick_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array".as_slice(), pool_address(pool).as_ref(), &start_tick_index.to_le_bytes()],
+        &pool.program_id
+    ).0
+}
+
+fn position_address(pool: &TestPool, owner: &Pubkey, tick_lower: i32, tick_upper: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"position".as_slice(),
+            pool_address(pool).as_ref(),
+            owner.as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+        ],
+        &pool.program_id
+    ).0
+}
+
+/// Start index of the 64-tick array holding `tick`
+fn tick_array_start(tick: i32) -> i32 {
+    let ticks_per_array = 64 * (TICK_SPACING as i32);
+    tick.div_euclid(ticks_per_array) * ticks_per_array
+}
+
+fn fetch_pool(svm: &LiteSVM, pool: &TestPool) -> ConcentratedPool {
+    let account = svm.get_account(&pool_address(pool)).unwrap();
+    ConcentratedPool::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+fn fetch_position(svm: &LiteSVM, position: &Pubkey) -> Position {
+    let account = svm.get_account(position).unwrap();
+    Position::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+fn initialize_concentrated_ix(pool: &TestPool, payer: &Pubkey, initial_sqrt_price: u128) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(pool_address(pool), false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (amm_ix::InitializeConcentrated {
+            tick_spacing: TICK_SPACING,
+            initial_sqrt_price,
+        }).data(),
+    }
+}
+
+fn initialize_tick_array_ix(pool: &TestPool, payer: &Pubkey, start_tick_index: i32) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(pool_address(pool), false),
+            AccountMeta::new(tick_array_address(pool, start_tick_index), false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (amm_ix::InitializeTickArray { start_tick_index }).data(),
+    }
+}
+
+fn open_position_ix(pool: &TestPool, owner: &Pubkey, tick_lower: i32, tick_upper: i32) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(pool_address(pool), false),
+            AccountMeta::new(position_address(pool, owner, tick_lower, tick_upper), false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (amm_ix::OpenPosition { tick_lower, tick_upper }).data(),
+    }
+}
+
+fn modify_liquidity_ix(
+    pool: &TestPool,
+    owner: &Pubkey,
+    tick_lower: i32,
+    tick_upper: i32,
+    data: Vec<u8>
+) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(pool_address(pool), false),
+            AccountMeta::new(position_address(pool, owner, tick_lower, tick_upper), false),
+            AccountMeta::new(tick_array_address(pool, tick_array_start(tick_lower)), false),
+            AccountMeta::new(tick_array_address(pool, tick_array_start(tick_upper)), false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new(pool.user_x(owner), false),
+            AccountMeta::new(pool.user_y(owner), false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false)
+        ],
+        data,
+    }
+}
+
+fn swap_concentrated_ix(
+    pool: &TestPool,
+    user: &Pubkey,
+    x_to_y: bool,
+    amount_in: u64,
+    min_amount_out: u64,
+    tick_arrays: &[i32]
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(pool.mint_x, false),
+        AccountMeta::new_readonly(pool.mint_y, false),
+        AccountMeta::new_readonly(pool.config, false),
+        AccountMeta::new(pool_address(pool), false),
+        AccountMeta::new(pool.vault_x, false),
+        AccountMeta::new(pool.vault_y, false),
+        AccountMeta::new(pool.user_x(user), false),
+        AccountMeta::new(pool.user_y(user), false),
+        AccountMeta::new_readonly(token::ID, false),
+        AccountMeta::new_readonly(associated_token::ID, false),
+        // No allowlist entry
+        AccountMeta::new_readonly(pool.program_id, false)
+    ];
+    accounts.extend(
+        tick_arrays.iter().map(|start| AccountMeta::new(tick_array_address(pool, *start), false))
+    );
+    let sqrt_price_limit = if x_to_y { MIN_SQRT_PRICE_X64 } else { MAX_SQRT_PRICE_X64 };
+    Instruction {
+        program_id: pool.program_id,
+        accounts,
+        data: (amm_ix::SwapConcentrated {
+            x_to_y,
+            amount_in,
+            min_amount_out,
+            sqrt_price_limit,
+        }).data(),
+    }
+}
+
+/// Concentrated pool at price 1.0 with tick arrays around the current price
+fn create_concentrated_pool(svm: &mut LiteSVM, seed: u64) -> TestPool {
+    let pool = TestPool::create(
+        svm,
+        seed,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::Concentrated,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let payer = funded_keypair(svm);
+    let ixs = [
+        initialize_concentrated_ix(&pool, &payer.pubkey(), 1u128 << 64),
+        initialize_tick_array_ix(&pool, &payer.pubkey(), -640),
+        initialize_tick_array_ix(&pool, &payer.pubkey(), 0),
+    ];
+    let result = send_ixs(svm, &ixs, &[&payer]);
+    assert!(result.is_ok(), "Initialize Concentrated Transaction failed: {:?}", result);
+
+    pool
+}
+
+#[test]
+fn test_concentrated_liquidity() {
+    let mut svm = setup_svm();
+    let pool = create_concentrated_pool(&mut svm, 30);
+
+    let lp_keypair = funded_keypair(&mut svm);
+    let lp = lp_keypair.pubkey();
+    let (lp_x, lp_y) = pool.fund(&mut svm, &lp_keypair, 100_000_000, 100_000_000);
+
+    // Position A straddles the price; position B sits entirely below it
+    let ixs = [
+        open_position_ix(&pool, &lp, -100, 100),
+        modify_liquidity_ix(&pool, &lp, -100, 100, (amm_ix::IncreaseLiquidity {
+            liquidity: 1_000_000_000,
+            max_x: 5_000_000,
+            max_y: 5_000_000,
+        }).data()),
+        open_position_ix(&pool, &lp, -300, -100),
+        modify_liquidity_ix(&pool, &lp, -300, -100, (amm_ix::IncreaseLiquidity {
+            liquidity: 2_000_000_000,
+            max_x: 0,
+            max_y: 20_000_000,
+        }).data()),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&lp_keypair]);
+    assert!(result.is_ok(), "Increase Liquidity Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &lp_x), 100_000_000 - 4_987_273);
+    assert_eq!(token_balance(&svm, &lp_y), 100_000_000 - 4_987_273 - 19_800_100);
+    let state = fetch_pool(&svm, &pool);
+    assert_eq!(state.liquidity, 1_000_000_000);
+    assert_eq!(state.tick_current, 0);
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (trader_x, trader_y) = pool.fund(&mut svm, &trader_keypair, 20_000_000, 20_000_000);
+
+    // The pro-rata swap does not apply to concentrated pools
+    let ix = pool.swap_ix(&trader, true, 10_000, 100);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Constant-product swap on a concentrated pool should fail");
+
+    // Small swap stays inside position A
+    let ix = swap_concentrated_ix(&pool, &trader, true, 10_000, 9_969, &[0, -640]);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Concentrated Transaction failed: {:?}", result);
+
+    let state = fetch_pool(&svm, &pool);
+    assert_eq!(state.tick_current, -1);
+    assert_eq!(state.liquidity, 1_000_000_000);
+
+    // Crossing tick -100 leaves A's range and picks up B's liquidity
+    let ix = swap_concentrated_ix(&pool, &trader, true, 10_000_000, 9_883_449, &[-640]);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Swap below minimum output should fail");
+
+    let ix = swap_concentrated_ix(&pool, &trader, true, 10_000_000, 9_883_448, &[-640]);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Concentrated Transaction failed: {:?}", result);
+
+    let state = fetch_pool(&svm, &pool);
+    assert_eq!(state.tick_current, -150);
+    assert_eq!(state.liquidity, 2_000_000_000);
+
+    let ix = swap_concentrated_ix(&pool, &trader, false, 1_000_000, 1_011_495, &[-640]);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Concentrated Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &trader_x), 20_000_000 - 10_000 - 10_000_000 + 1_011_495);
+    assert_eq!(token_balance(&svm, &trader_y), 20_000_000 + 9_969 + 9_883_448 - 1_000_000);
+    assert_eq!(fetch_pool(&svm, &pool).tick_current, -140);
+
+    // Each position earns fees only while the price is inside its range
+    let ixs = [
+        modify_liquidity_ix(&pool, &lp, -100, 100, (amm_ix::CollectFees {}).data()),
+        modify_liquidity_ix(&pool, &lp, -300, -100, (amm_ix::CollectFees {}).data()),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&lp_keypair]);
+    assert!(result.is_ok(), "Collect Fees Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &lp_x), 100_000_000 - 4_987_273 + 15_082 + 14_947);
+    assert_eq!(token_balance(&svm, &lp_y), 100_000_000 - 4_987_273 - 19_800_100 + 2_999);
+
+    // With the price below A's range, A is now held entirely in X
+    let ix = modify_liquidity_ix(&pool, &lp, -100, 100, (amm_ix::DecreaseLiquidity {
+        liquidity: 1_000_000_000,
+        min_x: 9_999_541,
+        min_y: 0,
+    }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Decrease Liquidity Transaction failed: {:?}", result);
+
+    assert_eq!(
+        token_balance(&svm, &lp_x),
+        100_000_000 - 4_987_273 + 15_082 + 14_947 + 9_999_541
+    );
+    let position = fetch_position(&svm, &position_address(&pool, &lp, -100, 100));
+    assert_eq!(position.liquidity, 0);
+    assert_eq!(position.fees_owed_x, 0);
+    assert_eq!(fetch_pool(&svm, &pool).liquidity, 2_000_000_000);
+}
+
+#[test]
+fn test_concentrated_swap_requires_tick_arrays() {
+    let mut svm = setup_svm();
+    let pool = create_concentrated_pool(&mut svm, 31);
+
+    let lp_keypair = funded_keypair(&mut svm);
+    let lp = lp_keypair.pubkey();
+    pool.fund(&mut svm, &lp_keypair, 100_000_000, 100_000_000);
+
+    // Ticks must sit on the pool's spacing
+    let ix = open_position_ix(&pool, &lp, -105, 100);
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_err(), "Position off the tick spacing should fail");
+
+    let ixs = [
+        open_position_ix(&pool, &lp, -100, 100),
+        modify_liquidity_ix(&pool, &lp, -100, 100, (amm_ix::IncreaseLiquidity {
+            liquidity: 1_000_000_000,
+            max_x: 5_000_000,
+            max_y: 5_000_000,
+        }).data()),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&lp_keypair]);
+    assert!(result.is_ok(), "Increase Liquidity Transaction failed: {:?}", result);
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    pool.fund(&mut svm, &trader_keypair, 1_000_000, 1_000_000);
+
+    // Moving down from tick 0 needs the array below it as well
+    let ix = swap_concentrated_ix(&pool, &trader, true, 10_000, 0, &[0]);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Swap without the next tick array should fail");
+
+    let ix = swap_concentrated_ix(&pool, &trader, true, 10_000, 0, &[0, -640]);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Concentrated Transaction failed: {:?}", result);
+}
+
+#[test]
+fn test_permissioned_concentrated_pool() {
+    let mut svm = setup_svm();
+    let pool = create_concentrated_pool(&mut svm, 32);
+    let authority = pool.authority.pubkey();
+
+    let allow_entry = |wallet: &Pubkey| {
+        Pubkey::find_program_address(
+            &[b"allow".as_slice(), pool.config.as_ref(), wallet.as_ref()],
+            &pool.program_id
+        ).0
+    };
+    let add_allow_entry_ix = |wallet: &Pubkey| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(allow_entry(wallet), false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (amm_ix::AddAllowEntry { wallet: *wallet }).data(),
+    };
+
+    let ix = Instruction {
+        program_id: pool.program_id,
+        accounts: vec![AccountMeta::new_readonly(authority, true), AccountMeta::new(pool.config, false)],
+        data: (amm_ix::SetAccessMode { access_mode: AccessMode::Permissioned }).data(),
+    };
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Set Access Mode Transaction failed: {:?}", result);
+
+    let lp_keypair = funded_keypair(&mut svm);
+    let lp = lp_keypair.pubkey();
+    pool.fund(&mut svm, &lp_keypair, 100_000_000, 100_000_000);
+    let increase = (amm_ix::IncreaseLiquidity {
+        liquidity: 1_000_000_000,
+        max_x: 5_000_000,
+        max_y: 5_000_000,
+    }).data();
+
+    let ixs = [
+        open_position_ix(&pool, &lp, -100, 100),
+        modify_liquidity_ix(&pool, &lp, -100, 100, increase.clone()),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&lp_keypair]);
+    assert!(result.is_err(), "Adding liquidity without an allowlist entry should fail");
+
+    let result = send_ixs(&mut svm, &[add_allow_entry_ix(&lp)], &[&pool.authority]);
+    assert!(result.is_ok(), "Add Allow Entry Transaction failed: {:?}", result);
+    let mut ix = modify_liquidity_ix(&pool, &lp, -100, 100, increase);
+    ix.accounts.push(AccountMeta::new_readonly(allow_entry(&lp), false));
+    let result = send_ixs(&mut svm, &[open_position_ix(&pool, &lp, -100, 100), ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Increase Liquidity Transaction failed: {:?}", result);
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    pool.fund(&mut svm, &trader_keypair, 1_000_000, 1_000_000);
+
+    let ix = swap_concentrated_ix(&pool, &trader, true, 10_000, 0, &[0, -640]);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Swap without an allowlist entry should fail");
+
+    let result = send_ixs(&mut svm, &[add_allow_entry_ix(&trader)], &[&pool.authority]);
+    assert!(result.is_ok(), "Add Allow Entry Transaction failed: {:?}", result);
+    let mut ix = swap_concentrated_ix(&pool, &trader, true, 10_000, 0, &[0, -640]);
+    ix.accounts[11] = AccountMeta::new_readonly(allow_entry(&trader), false);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Concentrated Transaction failed: {:?}", result);
+
+    // Removing liquidity stays open without an entry
+    let ix = modify_liquidity_ix(&pool, &lp, -100, 100, (amm_ix::DecreaseLiquidity {
+        liquidity: 1_000_000_000,
+        min_x: 0,
+        min_y: 0,
+    }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Decrease Liquidity Transaction failed: {:?}", result);
+}
+
+#[test]
+fn test_concentrated_rejects_swap_guards() {
+    let mut svm = setup_svm();
+    let pool = create_concentrated_pool(&mut svm, 33);
+    let authority = pool.authority.pubkey();
+
+    // Concentrated swaps never consult these, so switching them on must not look like it worked
+    let config_ix = |data: Vec<u8>| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![AccountMeta::new_readonly(authority, true), AccountMeta::new(pool.config, false)],
+        data,
+    };
+    let pool_state_ix = |data: Vec<u8>| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data,
+    };
+    let guards = [
+        (
+            "SetOracle",
+            config_ix(
+                (amm_ix::SetOracle {
+                    oracle: Some(Pubkey::new_unique()),
+                    params: OracleParams { max_deviation_bps: 50, max_staleness: 60, max_confidence_bps: 10 },
+                }).data()
+            ),
+        ),
+        (
+            "SetVolumeLimits",
+            pool_state_ix(
+                (amm_ix::SetVolumeLimits {
+                    limits: VolumeLimits {
+                        max_swap_volume: 1_000_000,
+                        max_window_volume: 0,
+                        max_wallet_volume: 0,
+                        window_slots: 10,
+                    },
+                }).data()
+            ),
+        ),
+        (
+            "SetCircuitBreaker",
+            pool_state_ix((amm_ix::SetCircuitBreaker { max_price_impact_bps: 500, max_slot_price_move_bps: 0 }).data()),
+        ),
+        (
+            "SetDynamicFee",
+            pool_state_ix(
+                (amm_ix::SetDynamicFee {
+                    params: DynamicFeeParams {
+                        enabled: true,
+                        max_fee: 1_000,
+                        variable_fee_control: 10_000,
+                        max_volatility_accumulator: 5_000,
+                        filter_period: 10,
+                        decay_period: 100,
+                        reduction_factor: 5_000,
+                    },
+                }).data()
+            ),
+        ),
+        ("SetReferralFee", config_ix((amm_ix::SetReferralFee { max_referral_fee_bps: 5_000 }).data())),
+    ];
+    for (name, ix) in guards {
+        let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+        assert!(result.is_err(), "{} on a concentrated pool should fail", name);
+    }
+
+    // The lock is checked by concentrated swaps and stays available
+    let ix = pool_state_ix((amm_ix::SetLocked { locked: true }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+}
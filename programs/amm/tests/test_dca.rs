@@ -0,0 +1,286 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use anchor_spl::{ associated_token::{ self, get_associated_token_address }, token };
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+use amm::instruction::{ CloseDca, CreateDca, ExecuteDca, WithdrawDca };
+use amm::{ CurveParams, CurveType };
+
+mod helpers;
+use helpers::*;
+
+const START: i64 = 1_700_000_000;
+const INTERVAL: i64 = 3_600;
+
+fn dca_pda(pool: &TestPool, owner: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"dca".as_slice(), pool.config.as_ref(), owner.as_ref(), id.to_le_bytes().as_ref()],
+        &pool.program_id
+    ).0
+}
+
+fn create_dca_ix(
+    pool: &TestPool,
+    owner: &Pubkey,
+    id: u64,
+    x_to_y: bool,
+    amount: u64,
+    amount_per_cycle: u64,
+    max_slippage_bps: u16
+) -> Instruction {
+    let dca = dca_pda(pool, owner, id);
+    let (mint_in, mint_out) = match x_to_y {
+        true => (pool.mint_x, pool.mint_y),
+        false => (pool.mint_y, pool.mint_x),
+    };
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(mint_in, false),
+            AccountMeta::new_readonly(mint_out, false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new_readonly(pool.pool_state, false),
+            AccountMeta::new(dca, false),
+            AccountMeta::new(get_associated_token_address(&dca, &mint_in), false),
+            AccountMeta::new(get_associated_token_address(&dca, &mint_out), false),
+            AccountMeta::new(get_associated_token_address(owner, &mint_in), false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (CreateDca {
+            id,
+            amount,
+            amount_per_cycle,
+            interval: INTERVAL,
+            max_slippage_bps,
+        }).data(),
+    }
+}
+
+fn execute_dca_ix(pool: &TestPool, owner: &Pubkey, id: u64) -> Instruction {
+    let dca = dca_pda(pool, owner, id);
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new(dca, false),
+            AccountMeta::new(get_associated_token_address(&dca, &pool.mint_x), false),
+            AccountMeta::new(get_associated_token_address(&dca, &pool.mint_y), false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new_readonly(token::ID, false)
+        ],
+        data: ExecuteDca {}.data(),
+    }
+}
+
+fn withdraw_dca_ix(pool: &TestPool, owner: &Pubkey, id: u64) -> Instruction {
+    let dca = dca_pda(pool, owner, id);
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(dca, false),
+            AccountMeta::new(get_associated_token_address(&dca, &pool.mint_y), false),
+            AccountMeta::new(get_associated_token_address(owner, &pool.mint_y), false),
+            AccountMeta::new_readonly(token::ID, false)
+        ],
+        data: WithdrawDca {}.data(),
+    }
+}
+
+fn close_dca_ix(pool: &TestPool, owner: &Pubkey, dca_owner: &Pubkey, id: u64) -> Instruction {
+    let dca = dca_pda(pool, dca_owner, id);
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(dca, false),
+            AccountMeta::new(get_associated_token_address(&dca, &pool.mint_x), false),
+            AccountMeta::new(get_associated_token_address(&dca, &pool.mint_y), false),
+            AccountMeta::new(get_associated_token_address(owner, &pool.mint_x), false),
+            AccountMeta::new(get_associated_token_address(owner, &pool.mint_y), false),
+            AccountMeta::new_readonly(token::ID, false)
+        ],
+        data: CloseDca {}.data(),
+    }
+}
+
+fn constant_product_pool(svm: &mut litesvm::LiteSVM, seed: u64, fee: u16) -> TestPool {
+    set_unix_timestamp(svm, START);
+    let pool = TestPool::create(
+        svm,
+        seed,
+        fee,
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let provider_keypair = funded_keypair(svm);
+    let provider = provider_keypair.pubkey();
+    pool.fund(svm, &provider_keypair, 1_000_000_000, 1_000_000_000);
+    let ix = pool.deposit_ix(&provider, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(svm, &[ix], &[&provider_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+    pool
+}
+
+#[test]
+fn test_dca() {
+    let mut svm = setup_svm();
+    let pool = constant_product_pool(&mut svm, 99, 30);
+
+    let owner_keypair = funded_keypair(&mut svm);
+    let owner = owner_keypair.pubkey();
+    let (owner_x, owner_y) = pool.fund(&mut svm, &owner_keypair, 25_000_000, 0);
+    let outsider_keypair = funded_keypair(&mut svm);
+    let outsider = outsider_keypair.pubkey();
+    pool.fund(&mut svm, &outsider_keypair, 0, 0);
+    let cranker = funded_keypair(&mut svm);
+
+    let ix = create_dca_ix(&pool, &owner, 1, true, 25_000_000, 10_000_000, 10_001);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_err(), "A slippage above 100% should fail");
+
+    // Sell 25 X, 10 X an hour, accepting 3% below the hourly TWAP
+    let ix = create_dca_ix(&pool, &owner, 1, true, 25_000_000, 10_000_000, 300);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "CreateDca Instruction Transaction failed: {:?}", result);
+
+    let dca = dca_pda(&pool, &owner, 1);
+    let input_escrow = get_associated_token_address(&dca, &pool.mint_x);
+    let output_escrow = get_associated_token_address(&dca, &pool.mint_y);
+    assert_eq!(token_balance(&svm, &owner_x), 0);
+    assert_eq!(token_balance(&svm, &input_escrow), 25_000_000);
+
+    let ix = execute_dca_ix(&pool, &owner, 1);
+    let result = send_ixs(&mut svm, &[ix], &[&cranker]);
+    assert!(result.is_err(), "ExecuteDca before the first interval should fail");
+
+    set_unix_timestamp(&mut svm, START + INTERVAL);
+    let ix = execute_dca_ix(&pool, &owner, 1);
+    let result = send_ixs(&mut svm, &[ix], &[&cranker]);
+    assert!(result.is_ok(), "ExecuteDca Instruction Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &input_escrow), 15_000_000);
+    let first_cycle = token_balance(&svm, &output_escrow);
+    assert!(first_cycle > 9_800_000 && first_cycle < 10_000_000);
+
+    svm.expire_blockhash();
+    let ix = execute_dca_ix(&pool, &owner, 1);
+    let result = send_ixs(&mut svm, &[ix], &[&cranker]);
+    assert!(result.is_err(), "Running a cycle twice in one interval should fail");
+
+    set_unix_timestamp(&mut svm, START + 2 * INTERVAL);
+    let ix = execute_dca_ix(&pool, &owner, 1);
+    let result = send_ixs(&mut svm, &[ix], &[&cranker]);
+    assert!(result.is_ok(), "ExecuteDca Instruction Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &input_escrow), 5_000_000);
+    assert!(token_balance(&svm, &output_escrow) > first_cycle);
+
+    let ix = withdraw_dca_ix(&pool, &owner, 1);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "WithdrawDca Instruction Transaction failed: {:?}", result);
+    let bought = token_balance(&svm, &owner_y);
+    assert!(bought > 2 * 9_700_000);
+    assert_eq!(token_balance(&svm, &output_escrow), 0);
+
+    let ix = close_dca_ix(&pool, &outsider, &owner, 1);
+    let result = send_ixs(&mut svm, &[ix], &[&outsider_keypair]);
+    assert!(result.is_err(), "CloseDca by non-owner should fail");
+
+    let ix = close_dca_ix(&pool, &owner, &owner, 1);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "CloseDca Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &owner_x), 5_000_000);
+    assert_eq!(token_balance(&svm, &owner_y), bought);
+    for closed in [dca, input_escrow, output_escrow] {
+        assert!(svm.get_account(&closed).map_or(true, |account| account.lamports == 0));
+    }
+}
+
+#[test]
+fn test_dca_rejects_manipulated_price() {
+    let mut svm = setup_svm();
+    let pool = constant_product_pool(&mut svm, 100, 30);
+
+    let owner_keypair = funded_keypair(&mut svm);
+    let owner = owner_keypair.pubkey();
+    pool.fund(&mut svm, &owner_keypair, 20_000_000, 0);
+    let attacker_keypair = funded_keypair(&mut svm);
+    let attacker = attacker_keypair.pubkey();
+    let (_, attacker_y) = pool.fund(&mut svm, &attacker_keypair, 300_000_000, 0);
+
+    let ix = create_dca_ix(&pool, &owner, 3, true, 20_000_000, 10_000_000, 300);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "CreateDca Instruction Transaction failed: {:?}", result);
+    let dca = dca_pda(&pool, &owner, 3);
+    let output_escrow = get_associated_token_address(&dca, &pool.mint_y);
+
+    // Dumping X right before the crank leaves the hour's average untouched
+    set_unix_timestamp(&mut svm, START + INTERVAL);
+    let ix = pool.swap_ix(&attacker, true, 300_000_000, 10_000);
+    let result = send_ixs(&mut svm, &[ix], &[&attacker_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    let ix = execute_dca_ix(&pool, &owner, 3);
+    let result = send_ixs(&mut svm, &[ix], &[&attacker_keypair]);
+    assert!(result.is_err(), "ExecuteDca into a manipulated price should fail");
+    assert_eq!(token_balance(&svm, &output_escrow), 0);
+
+    // Once the price is restored the cycle goes through
+    let ix = pool.swap_ix(&attacker, false, token_balance(&svm, &attacker_y), 10_000);
+    let result = send_ixs(&mut svm, &[ix], &[&attacker_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    svm.expire_blockhash();
+    let ix = execute_dca_ix(&pool, &owner, 3);
+    let result = send_ixs(&mut svm, &[ix], &[&attacker_keypair]);
+    assert!(result.is_ok(), "ExecuteDca Instruction Transaction failed: {:?}", result);
+    assert!(token_balance(&svm, &output_escrow) >= 9_700_000);
+}
+
+#[test]
+fn test_dca_fee_above_slippage() {
+    let mut svm = setup_svm();
+    let pool = constant_product_pool(&mut svm, 103, 100); // 1%
+
+    let owner_keypair = funded_keypair(&mut svm);
+    let owner = owner_keypair.pubkey();
+    pool.fund(&mut svm, &owner_keypair, 2_000_000, 0);
+    let cranker = funded_keypair(&mut svm);
+
+    // A 0.5% band on top of the 1% fee the pool charges
+    let ix = create_dca_ix(&pool, &owner, 4, true, 2_000_000, 1_000_000, 50);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "CreateDca Instruction Transaction failed: {:?}", result);
+    let dca = dca_pda(&pool, &owner, 4);
+    let output_escrow = get_associated_token_address(&dca, &pool.mint_y);
+
+    set_unix_timestamp(&mut svm, START + INTERVAL);
+    let ix = execute_dca_ix(&pool, &owner, 4);
+    let result = send_ixs(&mut svm, &[ix], &[&cranker]);
+    assert!(result.is_ok(), "ExecuteDca Instruction Transaction failed: {:?}", result);
+    let bought = token_balance(&svm, &output_escrow);
+    assert!(bought > 985_000 && bought < 990_000);
+
+    set_unix_timestamp(&mut svm, START + 2 * INTERVAL);
+    let ix = execute_dca_ix(&pool, &owner, 4);
+    let result = send_ixs(&mut svm, &[ix], &[&cranker]);
+    assert!(result.is_ok(), "ExecuteDca Instruction Transaction failed: {:?}", result);
+    assert!(token_balance(&svm, &output_escrow) > 2 * 980_000);
+}
@@ -19,6 +19,7 @@ use solana_sdk::{
 use spl_token::state::{Account as SPLTokenAccount, AccountState, Mint as SPLMint};
 
 use amm::instruction::{Deposit, Initialize};
+use amm::{CurveParams, CurveType};
 use litesvm::LiteSVM;
 
 mod helpers;
@@ -51,6 +52,13 @@ fn test_deposit_liquidity() {
         seed,
         fee: 1000, // 1%
         authority: Some(authority),
+        lp_decimals: 6,
+        curve: CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        },
     };
 
     let (config, _) = solana_sdk::pubkey::Pubkey::find_program_address(
@@ -65,6 +73,7 @@ fn test_deposit_liquidity() {
 
     let vault_x = get_associated_token_address(&config, &mint_x_pubkey);
     let vault_y = get_associated_token_address(&config, &mint_y_pubkey);
+    let fee_recipient = ensure_amm_config(&mut svm).fee_recipient;
 
     let ix = Instruction {
         program_id,
@@ -74,6 +83,11 @@ fn test_deposit_liquidity() {
             AccountMeta::new_readonly(mint_y_pubkey, false),
             AccountMeta::new(mint_lp, false),
             AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(fee_recipient, false),
+            AccountMeta::new(pool_index_pda(&mint_x_pubkey), false),
+            AccountMeta::new(pool_index_pda(&mint_y_pubkey), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new_readonly(token::ID, false),
@@ -137,7 +151,8 @@ fn test_deposit_liquidity() {
             AccountMeta::new_readonly(mint_x_pubkey, false),
             AccountMeta::new_readonly(mint_y_pubkey, false),
             AccountMeta::new(mint_lp, false),
-            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new(mint_user_x, false),
@@ -227,6 +242,13 @@ fn test_deposit_liquidity_with_minted_lp() {
         seed,
         fee: 1000, // 1%
         authority: Some(authority),
+        lp_decimals: 6,
+        curve: CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        },
     };
 
     let (config, _) = solana_sdk::pubkey::Pubkey::find_program_address(
@@ -241,6 +263,7 @@ fn test_deposit_liquidity_with_minted_lp() {
 
     let vault_x = get_associated_token_address(&config, &mint_x_pubkey);
     let vault_y = get_associated_token_address(&config, &mint_y_pubkey);
+    let fee_recipient = ensure_amm_config(&mut svm).fee_recipient;
 
     let ix = Instruction {
         program_id,
@@ -250,6 +273,11 @@ fn test_deposit_liquidity_with_minted_lp() {
             AccountMeta::new_readonly(mint_y_pubkey, false),
             AccountMeta::new(mint_lp, false),
             AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(fee_recipient, false),
+            AccountMeta::new(pool_index_pda(&mint_x_pubkey), false),
+            AccountMeta::new(pool_index_pda(&mint_y_pubkey), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new_readonly(token::ID, false),
@@ -313,7 +341,8 @@ fn test_deposit_liquidity_with_minted_lp() {
             AccountMeta::new_readonly(mint_x_pubkey, false),
             AccountMeta::new_readonly(mint_y_pubkey, false),
             AccountMeta::new(mint_lp, false),
-            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new(mint_user_x, false),
@@ -447,3 +476,256 @@ fn test_deposit_liquidity_with_minted_lp() {
     assert_eq!(mint_lp_data.is_initialized, true);
     assert_eq!(mint_lp_data.supply, 1050);
 }
+
+#[test]
+fn test_deposit_liquidity_mixed_decimals() {
+    let program_id = amm::id();
+    let mut svm = LiteSVM::new();
+    let bytes = include_bytes!("../../../target/deploy/amm.so");
+    svm.add_program(program_id, bytes);
+
+    let authority_keypair = Keypair::new();
+    let authority = authority_keypair.pubkey();
+    svm.airdrop(&authority, 100 * LAMPORTS_PER_SOL).unwrap();
+
+    let initializer_keypair = Keypair::new();
+    let initializer = initializer_keypair.pubkey();
+    svm.airdrop(&initializer, 100 * LAMPORTS_PER_SOL).unwrap();
+
+    // 9-decimal X, 0-decimal Y and a 6-decimal LP mint
+    let (mint_x_keypair, mint_x_pubkey, _, mint_x_account) =
+        build_token_mint_account(LAMPORTS_PER_SOL, 9);
+    svm.set_account(mint_x_pubkey, mint_x_account).unwrap();
+    let (mint_y_keypair, mint_y_pubkey, _, mint_y_account) = build_token_mint_account(1_000, 0);
+    svm.set_account(mint_y_pubkey, mint_y_account).unwrap();
+
+    let seed = 123456789u64;
+    let initialize_ix = Initialize {
+        seed,
+        fee: 1000, // 1%
+        authority: Some(authority),
+        lp_decimals: 6,
+        curve: CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        },
+    };
+
+    let (config, _) = solana_sdk::pubkey::Pubkey::find_program_address(
+        &[b"config".as_slice(), seed.to_le_bytes().as_ref()],
+        &program_id,
+    );
+
+    let (mint_lp, _) = solana_sdk::pubkey::Pubkey::find_program_address(
+        &[b"lp".as_slice(), &config.to_bytes()],
+        &program_id,
+    );
+
+    let vault_x = get_associated_token_address(&config, &mint_x_pubkey);
+    let vault_y = get_associated_token_address(&config, &mint_y_pubkey);
+    let fee_recipient = ensure_amm_config(&mut svm).fee_recipient;
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(initializer, true),
+            AccountMeta::new_readonly(mint_x_pubkey, false),
+            AccountMeta::new_readonly(mint_y_pubkey, false),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(fee_recipient, false),
+            AccountMeta::new(pool_index_pda(&mint_x_pubkey), false),
+            AccountMeta::new(pool_index_pda(&mint_y_pubkey), false),
+            AccountMeta::new(vault_x, false),
+            AccountMeta::new(vault_y, false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: initialize_ix.data(),
+    };
+
+    let tx = Transaction::new(
+        &[&initializer_keypair],
+        Message::new(&[ix], Some(&initializer)),
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+    assert!(
+        result.is_ok(),
+        "Initialize Instruction Transaction failed: {:?}",
+        result
+    );
+
+    let depositer_keypair = Keypair::new();
+    let depositer = depositer_keypair.pubkey();
+    svm.airdrop(&depositer, 100 * LAMPORTS_PER_SOL).unwrap();
+
+    let (mint_x_to_tx, mint_user_x) = create_mint_to_transaction(
+        &mint_x_keypair,
+        &mint_x_pubkey,
+        &depositer_keypair,
+        100 * LAMPORTS_PER_SOL, // 100 tokens
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(mint_x_to_tx);
+    assert!(result.is_ok(), "Mint X Transaction failed: {:?}", result);
+
+    let (mint_y_to_tx, mint_user_y) = create_mint_to_transaction(
+        &mint_y_keypair,
+        &mint_y_pubkey,
+        &depositer_keypair,
+        1_000, // 1000 tokens
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(mint_y_to_tx);
+    assert!(result.is_ok(), "Mint Y Transaction failed: {:?}", result);
+
+    let mint_user_lp = get_associated_token_address(&depositer, &mint_lp);
+
+    let accounts = vec![
+        AccountMeta::new(depositer, true),
+        AccountMeta::new_readonly(mint_x_pubkey, false),
+        AccountMeta::new_readonly(mint_y_pubkey, false),
+        AccountMeta::new(mint_lp, false),
+        AccountMeta::new(config, false),
+        AccountMeta::new(pool_state_pda(&config), false),
+        AccountMeta::new(vault_x, false),
+        AccountMeta::new(vault_y, false),
+        AccountMeta::new(mint_user_x, false),
+        AccountMeta::new(mint_user_y, false),
+        AccountMeta::new(mint_user_lp, false),
+        AccountMeta::new_readonly(token::ID, false),
+        AccountMeta::new_readonly(associated_token::ID, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    // Seed the pool with 10 X and 500 Y for 1 LP token
+    let ix = Instruction {
+        program_id,
+        accounts: accounts.clone(),
+        data: Deposit {
+            amount: 1_000_000,
+            max_x: 10 * LAMPORTS_PER_SOL,
+            max_y: 500,
+        }
+        .data(),
+    };
+    let tx = Transaction::new(
+        &[&depositer_keypair],
+        Message::new(&[ix], Some(&depositer)),
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+    assert!(
+        result.is_ok(),
+        "Deposit Instruction Transaction failed: {:?}",
+        result
+    );
+
+    // A 10% top-up must pull exactly 1 X and 50 Y, even though Y has no decimals
+    let ix = Instruction {
+        program_id,
+        accounts,
+        data: Deposit {
+            amount: 100_000,
+            max_x: LAMPORTS_PER_SOL,
+            max_y: 50,
+        }
+        .data(),
+    };
+    let tx = Transaction::new(
+        &[&depositer_keypair],
+        Message::new(&[ix], Some(&depositer)),
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+    assert!(
+        result.is_ok(),
+        "Deposit Instruction Transaction failed: {:?}",
+        result
+    );
+
+    let vault_x_info = svm.get_account(&vault_x).unwrap();
+    let vault_x_data = SPLTokenAccount::unpack(&vault_x_info.data).unwrap();
+    assert_eq!(vault_x_data.amount, 11 * LAMPORTS_PER_SOL);
+
+    let vault_y_info = svm.get_account(&vault_y).unwrap();
+    let vault_y_data = SPLTokenAccount::unpack(&vault_y_info.data).unwrap();
+    assert_eq!(vault_y_data.amount, 550);
+
+    let mint_user_y_info = svm.get_account(&mint_user_y).unwrap();
+    let mint_user_y_data = SPLTokenAccount::unpack(&mint_user_y_info.data).unwrap();
+    assert_eq!(mint_user_y_data.amount, 450);
+
+    let mint_user_lp_info = svm.get_account(&mint_user_lp).unwrap();
+    let mint_user_lp_data = SPLTokenAccount::unpack(&mint_user_lp_info.data).unwrap();
+    assert_eq!(mint_user_lp_data.amount, 1_100_000);
+
+    let mint_lp_info = svm.get_account(&mint_lp).unwrap();
+    let mint_lp_data = SPLMint::unpack(&mint_lp_info.data).unwrap();
+    assert_eq!(mint_lp_data.decimals, 6);
+    assert_eq!(mint_lp_data.supply, 1_100_000);
+}
+
+#[test]
+fn test_deposit_rejects_unrecorded_vault() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        96,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let depositer_keypair = funded_keypair(&mut svm);
+    let depositer = depositer_keypair.pubkey();
+    pool.fund(&mut svm, &depositer_keypair, 1_000_000_000, 1_000_000_000);
+
+    // A token account of the right mint, owned by the config but not the pool's vault
+    let decoy = Keypair::new().pubkey();
+    let mut data = vec![0u8; SPLTokenAccount::LEN];
+    SPLTokenAccount::pack(
+        SPLTokenAccount {
+            mint: pool.mint_x,
+            owner: pool.config,
+            amount: 0,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+    svm.set_account(decoy, Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: token::ID,
+        executable: false,
+        rent_epoch: 0,
+    })
+    .unwrap();
+
+    let mut ix = pool.deposit_ix(&depositer, 1_000_000, 1_000_000, 1_000_000);
+    ix.accounts[6] = AccountMeta::new(decoy, false);
+    let result = send_ixs(&mut svm, &[ix], &[&depositer_keypair]);
+    assert!(result.is_err(), "Deposit into an unrecorded vault should fail");
+    assert_eq!(token_balance(&svm, &decoy), 0);
+
+    let ix = pool.deposit_ix(&depositer, 1_000_000, 1_000_000, 1_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&depositer_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+}
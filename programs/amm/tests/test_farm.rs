@@ -0,0 +1,341 @@
+// #![cfg(feature = "test-sbf")]
+#![allow(deprecated)]
+
+use amm::{ instruction as amm_ix, CurveParams, CurveType, RewardPool, StakeAccount };
+use anchor_lang::{ AccountDeserialize, InstructionData };
+use anchor_spl::{ associated_token, token };
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+};
+use spl_associated_token_account_client::address::get_associated_token_address;
+
+mod helpers;
+use helpers::*;
+
+const START: i64 = 1_000_000;
+// A power of two so the reward-per-share accumulator divides exactly
+const STAKE: u64 = 1_048_576;
+
+struct Farm {
+    reward_mint_authority: Keypair,
+    reward_mint: Pubkey,
+    reward_pool: Pubkey,
+    stake_vault: Pubkey,
+    reward_vault: Pubkey,
+}
+
+impl Farm {
+    fn new(svm: &mut LiteSVM, pool: &TestPool) -> Self {
+        let (reward_mint_authority, reward_mint, _, account) = build_token_mint_account(0, 6);
+        svm.set_account(reward_mint, account).unwrap();
+        let (reward_pool, _) = Pubkey::find_program_address(
+            &[b"reward_pool".as_slice(), pool.config.as_ref(), reward_mint.as_ref()],
+            &pool.program_id
+        );
+        Farm {
+            reward_mint_authority,
+            reward_mint,
+            reward_pool,
+            stake_vault: get_associated_token_address(&reward_pool, &pool.mint_lp),
+            reward_vault: get_associated_token_address(&reward_pool, &reward_mint),
+        }
+    }
+
+    fn stake_account(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"stake".as_slice(), self.reward_pool.as_ref(), user.as_ref()],
+            &amm::id()
+        ).0
+    }
+
+    fn create_ix(&self, pool: &TestPool, emission_per_second: u64, end_ts: i64) -> Instruction {
+        let authority = pool.authority.pubkey();
+        Instruction {
+            program_id: pool.program_id,
+            accounts: vec![
+                AccountMeta::new(authority, true),
+                AccountMeta::new_readonly(pool.config, false),
+                AccountMeta::new_readonly(pool.mint_lp, false),
+                AccountMeta::new_readonly(self.reward_mint, false),
+                AccountMeta::new(self.reward_pool, false),
+                AccountMeta::new(self.stake_vault, false),
+                AccountMeta::new(self.reward_vault, false),
+                AccountMeta::new(get_associated_token_address(&authority, &self.reward_mint), false),
+                AccountMeta::new_readonly(token::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: (amm_ix::CreateRewardPool { emission_per_second, end_ts }).data(),
+        }
+    }
+
+    fn reclaim_ix(&self, pool: &TestPool, authority: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: pool.program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new_readonly(pool.config, false),
+                AccountMeta::new_readonly(self.reward_mint, false),
+                AccountMeta::new(self.reward_pool, false),
+                AccountMeta::new(self.reward_vault, false),
+                AccountMeta::new(get_associated_token_address(authority, &self.reward_mint), false),
+                AccountMeta::new_readonly(token::ID, false)
+            ],
+            data: (amm_ix::ReclaimRewards {}).data(),
+        }
+    }
+
+    fn farm_ix(&self, pool: &TestPool, user: &Pubkey, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: pool.program_id,
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new_readonly(pool.mint_lp, false),
+                AccountMeta::new_readonly(self.reward_mint, false),
+                AccountMeta::new(self.reward_pool, false),
+                AccountMeta::new(self.stake_account(user), false),
+                AccountMeta::new(self.stake_vault, false),
+                AccountMeta::new(self.reward_vault, false),
+                AccountMeta::new(pool.user_lp(user), false),
+                AccountMeta::new(get_associated_token_address(user, &self.reward_mint), false),
+                AccountMeta::new_readonly(token::ID, false),
+                AccountMeta::new_readonly(associated_token::ID, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data,
+        }
+    }
+}
+
+fn fetch_reward_pool(svm: &LiteSVM, reward_pool: &Pubkey) -> RewardPool {
+    let account = svm.get_account(reward_pool).unwrap();
+    RewardPool::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+fn fetch_stake_account(svm: &LiteSVM, stake_account: &Pubkey) -> StakeAccount {
+    let account = svm.get_account(stake_account).unwrap();
+    StakeAccount::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+/// Funds `user` and deposits up to `max` of each token for `STAKE` LP tokens
+fn liquidity_provider(svm: &mut LiteSVM, pool: &TestPool, max: u64) -> Keypair {
+    let user = funded_keypair(svm);
+    pool.fund(svm, &user, 50_000_000, 50_000_000);
+    let ix = pool.deposit_ix(&user.pubkey(), STAKE, max, max);
+    let result = send_ixs(svm, &[ix], &[&user]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+    user
+}
+
+#[test]
+fn test_liquidity_mining_rewards() {
+    let mut svm = setup_svm();
+    set_unix_timestamp(&mut svm, START);
+
+    let pool = TestPool::create(
+        &mut svm,
+        50,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let alice = liquidity_provider(&mut svm, &pool, 10_000_000);
+    let bob = liquidity_provider(&mut svm, &pool, 50_000_000);
+
+    // 100 reward tokens a second for 1 000 seconds, paid for up front
+    let farm = Farm::new(&mut svm, &pool);
+    let (tx, _) = create_mint_to_transaction(
+        &farm.reward_mint_authority,
+        &farm.reward_mint,
+        &pool.authority,
+        100_000,
+        svm.latest_blockhash()
+    );
+    svm.send_transaction(tx).unwrap();
+
+    let ix = farm.create_ix(&pool, 100, START + 1_000);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Create Reward Pool Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &farm.reward_vault), 100_000);
+
+    let ix = farm.farm_ix(&pool, &alice.pubkey(), (amm_ix::StakeLp { amount: STAKE }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&alice]);
+    assert!(result.is_ok(), "Stake Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&alice.pubkey())), 0);
+    assert_eq!(token_balance(&svm, &farm.stake_vault), STAKE);
+
+    // Alice earns alone for 100 seconds, then shares with Bob for 100 seconds
+    set_unix_timestamp(&mut svm, START + 100);
+    let ix = farm.farm_ix(&pool, &bob.pubkey(), (amm_ix::StakeLp { amount: STAKE }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&bob]);
+    assert!(result.is_ok(), "Stake Transaction failed: {:?}", result);
+
+    set_unix_timestamp(&mut svm, START + 200);
+    for user in [&alice, &bob] {
+        let ix = farm.farm_ix(&pool, &user.pubkey(), (amm_ix::ClaimRewards {}).data());
+        let result = send_ixs(&mut svm, &[ix], &[user]);
+        assert!(result.is_ok(), "Claim Transaction failed: {:?}", result);
+    }
+    let alice_reward = get_associated_token_address(&alice.pubkey(), &farm.reward_mint);
+    let bob_reward = get_associated_token_address(&bob.pubkey(), &farm.reward_mint);
+    assert_eq!(token_balance(&svm, &alice_reward), 15_000);
+    assert_eq!(token_balance(&svm, &bob_reward), 5_000);
+
+    // Cannot unstake more than was staked
+    let ix = farm.farm_ix(&pool, &bob.pubkey(), (amm_ix::UnstakeLp { amount: STAKE + 1 }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&bob]);
+    assert!(result.is_err(), "Unstake above stake should fail");
+
+    // Emissions stop at the end time, whenever rewards are claimed
+    set_unix_timestamp(&mut svm, START + 2_000);
+    let ixs = [
+        farm.farm_ix(&pool, &alice.pubkey(), (amm_ix::UnstakeLp { amount: STAKE }).data()),
+        farm.farm_ix(&pool, &alice.pubkey(), (amm_ix::ClaimRewards {}).data()),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&alice]);
+    assert!(result.is_ok(), "Unstake Transaction failed: {:?}", result);
+    let ix = farm.farm_ix(&pool, &bob.pubkey(), (amm_ix::ClaimRewards {}).data());
+    let result = send_ixs(&mut svm, &[ix], &[&bob]);
+    assert!(result.is_ok(), "Claim Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &alice_reward), 15_000 + 40_000);
+    assert_eq!(token_balance(&svm, &bob_reward), 5_000 + 40_000);
+    assert_eq!(token_balance(&svm, &farm.reward_vault), 0);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&alice.pubkey())), STAKE);
+
+    let reward_pool = fetch_reward_pool(&svm, &farm.reward_pool);
+    assert_eq!(reward_pool.total_staked, STAKE);
+    assert_eq!(reward_pool.last_update_ts, START + 1_000);
+    let stake = fetch_stake_account(&svm, &farm.stake_account(&alice.pubkey()));
+    assert_eq!(stake.amount, 0);
+    assert_eq!(stake.rewards_owed, 0);
+}
+
+#[test]
+fn test_idle_emissions_are_reclaimable() {
+    let mut svm = setup_svm();
+    set_unix_timestamp(&mut svm, START);
+
+    let pool = TestPool::create(
+        &mut svm,
+        52,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let alice = liquidity_provider(&mut svm, &pool, 10_000_000);
+    let dust = liquidity_provider(&mut svm, &pool, 10_000_000);
+
+    let farm = Farm::new(&mut svm, &pool);
+    let (tx, _) = create_mint_to_transaction(
+        &farm.reward_mint_authority,
+        &farm.reward_mint,
+        &pool.authority,
+        100_000,
+        svm.latest_blockhash()
+    );
+    svm.send_transaction(tx).unwrap();
+    let ix = farm.create_ix(&pool, 100, START + 1_000);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Create Reward Pool Transaction failed: {:?}", result);
+
+    // Nobody stakes for the first 300 seconds; a dust stake then must not collect that backlog
+    set_unix_timestamp(&mut svm, START + 300);
+    let ix = farm.farm_ix(&pool, &dust.pubkey(), (amm_ix::StakeLp { amount: 1 }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&dust]);
+    assert!(result.is_ok(), "Stake Transaction failed: {:?}", result);
+    let reward_pool = fetch_reward_pool(&svm, &farm.reward_pool);
+    assert_eq!(reward_pool.last_update_ts, START + 300);
+    assert_eq!(reward_pool.undistributed, 30_000);
+
+    let ix = farm.farm_ix(&pool, &dust.pubkey(), (amm_ix::ClaimRewards {}).data());
+    let result = send_ixs(&mut svm, &[ix], &[&dust]);
+    assert!(result.is_ok(), "Claim Transaction failed: {:?}", result);
+    let dust_reward = get_associated_token_address(&dust.pubkey(), &farm.reward_mint);
+    assert_eq!(token_balance(&svm, &dust_reward), 0);
+
+    let ix = farm.farm_ix(&pool, &alice.pubkey(), (amm_ix::StakeLp { amount: STAKE }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&alice]);
+    assert!(result.is_ok(), "Stake Transaction failed: {:?}", result);
+
+    // The remaining 70_000 is shared pro rata, so the dust stake earns nothing
+    set_unix_timestamp(&mut svm, START + 2_000);
+    svm.expire_blockhash();
+    for user in [&alice, &dust] {
+        let ix = farm.farm_ix(&pool, &user.pubkey(), (amm_ix::ClaimRewards {}).data());
+        let result = send_ixs(&mut svm, &[ix], &[user]);
+        assert!(result.is_ok(), "Claim Transaction failed: {:?}", result);
+    }
+    let alice_reward = get_associated_token_address(&alice.pubkey(), &farm.reward_mint);
+    assert_eq!(token_balance(&svm, &alice_reward), 69_999);
+    assert_eq!(token_balance(&svm, &dust_reward), 0);
+
+    // Only the authority may reclaim the idle emissions
+    let intruder = funded_keypair(&mut svm);
+    let mut ix = farm.reclaim_ix(&pool, &pool.authority.pubkey());
+    ix.accounts[0] = AccountMeta::new_readonly(intruder.pubkey(), true);
+    let result = send_ixs(&mut svm, &[ix], &[&intruder]);
+    assert!(result.is_err(), "Reclaim by non-authority should fail");
+
+    let ix = farm.reclaim_ix(&pool, &pool.authority.pubkey());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Reclaim Rewards Transaction failed: {:?}", result);
+    let authority_reward = get_associated_token_address(&pool.authority.pubkey(), &farm.reward_mint);
+    assert_eq!(token_balance(&svm, &authority_reward), 30_000);
+    assert_eq!(fetch_reward_pool(&svm, &farm.reward_pool).undistributed, 0);
+    assert_eq!(token_balance(&svm, &farm.reward_vault), 1);
+}
+
+#[test]
+fn test_create_reward_pool_requires_authority() {
+    let mut svm = setup_svm();
+    set_unix_timestamp(&mut svm, START);
+
+    let pool = TestPool::create(
+        &mut svm,
+        51,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let farm = Farm::new(&mut svm, &pool);
+
+    let intruder = funded_keypair(&mut svm);
+    let (tx, _) = create_mint_to_transaction(
+        &farm.reward_mint_authority,
+        &farm.reward_mint,
+        &intruder,
+        100_000,
+        svm.latest_blockhash()
+    );
+    svm.send_transaction(tx).unwrap();
+
+    let mut ix = farm.create_ix(&pool, 100, START + 1_000);
+    ix.accounts[0] = AccountMeta::new(intruder.pubkey(), true);
+    ix.accounts[7] = AccountMeta::new(
+        get_associated_token_address(&intruder.pubkey(), &farm.reward_mint),
+        false
+    );
+    let result = send_ixs(&mut svm, &[ix], &[&intruder]);
+    assert!(result.is_err(), "Reward pool created by non-authority should fail");
+}
@@ -1,21 +1,28 @@
 // #![cfg(feature = "test-sbf")]
 #![allow(deprecated)]
 
-use anchor_lang::AccountDeserialize;
+use anchor_lang::{AccountDeserialize, AccountSerialize};
 use anchor_lang::InstructionData;
 use anchor_spl::{
     associated_token::{self, get_associated_token_address},
     token,
 };
 use solana_sdk::{
+    account::Account,
     instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    rent::Rent,
     signature::Keypair,
     signer::Signer,
     system_program,
 };
-use solana_sdk::{message::Message, native_token::LAMPORTS_PER_SOL, transaction::Transaction};
+use solana_sdk::{
+    message::Message, native_token::LAMPORTS_PER_SOL, program_option::COption,
+    program_pack::Pack, transaction::Transaction,
+};
+use spl_token::state::Mint as SPLMint;
 
-use amm::{instruction::Initialize, Config};
+use amm::{instruction::Initialize, Config, CurveParams, CurveType, PoolIndex};
 use litesvm::LiteSVM;
 
 mod helpers;
@@ -46,6 +53,13 @@ pub fn test_initialize_amm() {
         seed,
         fee: 1000, // 1%
         authority: Some(authority),
+        lp_decimals: 6,
+        curve: CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        },
     };
 
     let (config, _) = solana_sdk::pubkey::Pubkey::find_program_address(
@@ -60,6 +74,7 @@ pub fn test_initialize_amm() {
 
     let vault_x = get_associated_token_address(&config, &mint_x_pubkey);
     let vault_y = get_associated_token_address(&config, &mint_y_pubkey);
+    let fee_recipient = ensure_amm_config(&mut svm).fee_recipient;
 
     let ix = Instruction {
         program_id,
@@ -69,6 +84,11 @@ pub fn test_initialize_amm() {
             AccountMeta::new_readonly(mint_y_pubkey, false),
             AccountMeta::new(mint_lp, false),
             AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(fee_recipient, false),
+            AccountMeta::new(pool_index_pda(&mint_x_pubkey), false),
+            AccountMeta::new(pool_index_pda(&mint_y_pubkey), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new_readonly(token::ID, false),
@@ -94,4 +114,246 @@ pub fn test_initialize_amm() {
     assert_eq!(config_data.authority, Some(authority));
     assert_eq!(config_data.mint_x, mint_x_pubkey);
     assert_eq!(config_data.mint_y, mint_y_pubkey);
+    assert_eq!(config_data.curve_type, CurveType::ConstantProduct);
+}
+
+#[test]
+pub fn test_initialize_amm_with_mixed_decimals() {
+    let program_id = amm::id();
+    let mut svm = LiteSVM::new();
+    let bytes = include_bytes!("../../../target/deploy/amm.so");
+    svm.add_program(program_id, bytes);
+
+    let authority_keypair = Keypair::new();
+    let authority = authority_keypair.pubkey();
+    svm.airdrop(&authority, 100 * LAMPORTS_PER_SOL).unwrap();
+
+    let initializer_keypair = Keypair::new();
+    let initializer = initializer_keypair.pubkey();
+    svm.airdrop(&initializer, 100 * LAMPORTS_PER_SOL).unwrap();
+
+    let (_, mint_x_pubkey, _, mint_x_account) = build_token_mint_account(LAMPORTS_PER_SOL, 9);
+    svm.set_account(mint_x_pubkey, mint_x_account).unwrap();
+    let (_, mint_y_pubkey, _, mint_y_account) = build_token_mint_account(1_000_000, 0);
+    svm.set_account(mint_y_pubkey, mint_y_account).unwrap();
+
+    let seed = 987654321u64;
+    let (config, _) = solana_sdk::pubkey::Pubkey::find_program_address(
+        &[b"config".as_slice(), seed.to_le_bytes().as_ref()],
+        &program_id,
+    );
+
+    let (mint_lp, _) = solana_sdk::pubkey::Pubkey::find_program_address(
+        &[b"lp".as_slice(), &config.to_bytes()],
+        &program_id,
+    );
+
+    let vault_x = get_associated_token_address(&config, &mint_x_pubkey);
+    let vault_y = get_associated_token_address(&config, &mint_y_pubkey);
+    let fee_recipient = ensure_amm_config(&mut svm).fee_recipient;
+
+    let accounts = vec![
+        AccountMeta::new(initializer, true),
+        AccountMeta::new_readonly(mint_x_pubkey, false),
+        AccountMeta::new_readonly(mint_y_pubkey, false),
+        AccountMeta::new(mint_lp, false),
+        AccountMeta::new(config, false),
+        AccountMeta::new(pool_state_pda(&config), false),
+        AccountMeta::new_readonly(amm_config_pda(), false),
+        AccountMeta::new(fee_recipient, false),
+        AccountMeta::new(pool_index_pda(&mint_x_pubkey), false),
+        AccountMeta::new(pool_index_pda(&mint_y_pubkey), false),
+        AccountMeta::new(vault_x, false),
+        AccountMeta::new(vault_y, false),
+        AccountMeta::new_readonly(token::ID, false),
+        AccountMeta::new_readonly(associated_token::ID, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+
+    // LP decimals above the supported precision are rejected
+    let ix = Instruction {
+        program_id,
+        accounts: accounts.clone(),
+        data: Initialize {
+            seed,
+            fee: 1000,
+            authority: Some(authority),
+            lp_decimals: 10,
+            curve: CurveParams {
+                curve_type: CurveType::ConstantProduct,
+                amp: 0,
+                weight_x: 0,
+                weight_y: 0,
+            },
+        }
+        .data(),
+    };
+    let tx = Transaction::new(
+        &[&initializer_keypair],
+        Message::new(&[ix], Some(&initializer)),
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+    assert!(result.is_err(), "Initialize with 10 LP decimals should fail");
+
+    let ix = Instruction {
+        program_id,
+        accounts,
+        data: Initialize {
+            seed,
+            fee: 1000,
+            authority: Some(authority),
+            lp_decimals: 9,
+            curve: CurveParams {
+                curve_type: CurveType::ConstantProduct,
+                amp: 0,
+                weight_x: 0,
+                weight_y: 0,
+            },
+        }
+        .data(),
+    };
+    let tx = Transaction::new(
+        &[&initializer_keypair],
+        Message::new(&[ix], Some(&initializer)),
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx);
+    assert!(result.is_ok(), "Transaction failed: {:?}", result);
+
+    let mint_lp_account = svm.get_account(&mint_lp).unwrap();
+    let mint_lp_data = SPLMint::unpack(&mint_lp_account.data).unwrap();
+    assert_eq!(mint_lp_data.decimals, 9);
+    assert_eq!(mint_lp_data.mint_authority, COption::Some(config));
+
+    // The pool's accounts are recorded so later instructions can check them by key
+    let config = fetch_config(&svm, &config);
+    assert_eq!(config.vault_x, vault_x);
+    assert_eq!(config.vault_y, vault_y);
+    assert_eq!(config.mint_lp, mint_lp);
+    assert_eq!((config.decimals_x, config.decimals_y), (9, 0));
+}
+
+#[test]
+pub fn test_initialize_weighted_pool_validates_weights() {
+    let mut svm = setup_svm();
+    let initializer_keypair = funded_keypair(&mut svm);
+    let initializer = initializer_keypair.pubkey();
+    let pool = TestPool::new(&mut svm, 55, 6, 6);
+
+    let invalid_weights = [(8_000, 1_000), (9_950, 50), (0, 10_000)];
+    for (weight_x, weight_y) in invalid_weights {
+        let ix = pool.initialize_ix(
+            &initializer,
+            30,
+            6,
+            CurveParams {
+                curve_type: CurveType::Weighted,
+                amp: 0,
+                weight_x,
+                weight_y,
+            },
+        );
+        let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+        assert!(result.is_err(), "Weights {}/{} should be rejected", weight_x, weight_y);
+    }
+
+    let ix = pool.initialize_ix(
+        &initializer,
+        30,
+        6,
+        CurveParams {
+            curve_type: CurveType::Weighted,
+            amp: 0,
+            weight_x: 8_000,
+            weight_y: 2_000,
+        },
+    );
+    let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+    assert!(result.is_ok(), "Transaction failed: {:?}", result);
+
+    let config_data = fetch_config(&svm, &pool.config);
+    assert_eq!(config_data.curve_type, CurveType::Weighted);
+    assert_eq!(config_data.weight_x, 8_000);
+    assert_eq!(config_data.weight_y, 2_000);
+}
+
+#[test]
+fn test_initialize_registers_pool_by_mint() {
+    let mut svm = setup_svm();
+
+    let curve = CurveParams {
+        curve_type: CurveType::ConstantProduct,
+        amp: 0,
+        weight_x: 0,
+        weight_y: 0,
+    };
+    let first = TestPool::create(&mut svm, 56, 30, (6, 6, 6), curve);
+
+    let index_x = fetch_pool_index(&svm, &first.mint_x);
+    assert_eq!(index_x.mint, first.mint_x);
+    assert_eq!(index_x.pools, vec![first.config]);
+    assert_eq!(fetch_pool_index(&svm, &first.mint_y).pools, vec![first.config]);
+
+    // A second pool over the same X mint grows its index by one entry
+    let (mint_z_authority, mint_z, _, mint_z_account) = build_token_mint_account(0, 6);
+    svm.set_account(mint_z, mint_z_account).unwrap();
+    let second = TestPool::from_mints(
+        &mut svm,
+        57,
+        (first.mint_x_authority.insecure_clone(), first.mint_x),
+        (mint_z_authority, mint_z),
+    );
+    let initializer_keypair = funded_keypair(&mut svm);
+    let ix = second.initialize_ix(&initializer_keypair.pubkey(), 30, 6, curve);
+    let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+    assert!(result.is_ok(), "Transaction failed: {:?}", result);
+
+    assert_eq!(fetch_pool_index(&svm, &first.mint_x).pools, vec![first.config, second.config]);
+    assert_eq!(fetch_pool_index(&svm, &mint_z).pools, vec![second.config]);
+    assert_eq!(fetch_pool_index(&svm, &first.mint_y).pools, vec![first.config]);
+
+    let account = svm.get_account(&pool_index_pda(&first.mint_x)).unwrap();
+    assert_eq!(account.owner, amm::id());
+    assert_eq!(account.data.len(), amm::PoolIndex::space(2));
+}
+
+#[test]
+fn test_pool_index_appends_in_place() {
+    let mut svm = setup_svm();
+
+    // An index far past what the program could decode on its heap
+    let pool = TestPool::new(&mut svm, 58, 6, 6);
+    let (index, bump) =
+        Pubkey::find_program_address(&[b"pool_index".as_slice(), pool.mint_x.as_ref()], &amm::id());
+    let pools: Vec<Pubkey> = (0..3_000).map(|_| Pubkey::new_unique()).collect();
+    let mut data = Vec::new();
+    PoolIndex { mint: pool.mint_x, bump, pools: pools.clone() }
+        .try_serialize(&mut data)
+        .unwrap();
+    let account = Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: amm::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(index, account).unwrap();
+
+    let initializer_keypair = funded_keypair(&mut svm);
+    let curve = CurveParams {
+        curve_type: CurveType::ConstantProduct,
+        amp: 0,
+        weight_x: 0,
+        weight_y: 0,
+    };
+    let ix = pool.initialize_ix(&initializer_keypair.pubkey(), 30, 6, curve);
+    let result = send_ixs(&mut svm, &[ix], &[&initializer_keypair]);
+    assert!(result.is_ok(), "Transaction failed: {:?}", result);
+
+    let index_x = fetch_pool_index(&svm, &pool.mint_x);
+    assert_eq!(index_x.pools.len(), 3_001);
+    assert_eq!(index_x.pools[..3_000], pools[..]);
+    assert_eq!(index_x.pools[3_000], pool.config);
+    assert_eq!(svm.get_account(&index).unwrap().data.len(), PoolIndex::space(3_001));
 }
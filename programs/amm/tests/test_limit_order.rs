@@ -0,0 +1,232 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use anchor_spl::{ associated_token::{ self, get_associated_token_address }, token };
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+use amm::instruction::{ CancelOrder, FillOrder, PlaceOrder };
+use amm::{ CurveParams, CurveType };
+
+mod helpers;
+use helpers::*;
+
+fn order_pda(pool: &TestPool, owner: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"order".as_slice(), pool.config.as_ref(), owner.as_ref(), id.to_le_bytes().as_ref()],
+        &pool.program_id
+    ).0
+}
+
+fn place_order_ix(
+    pool: &TestPool,
+    owner: &Pubkey,
+    id: u64,
+    mint_in: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    tip: u64
+) -> Instruction {
+    let order = order_pda(pool, owner, id);
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*mint_in, false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new(get_associated_token_address(&order, mint_in), false),
+            AccountMeta::new(get_associated_token_address(owner, mint_in), false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (PlaceOrder { id, amount_in, min_amount_out, tip }).data(),
+    }
+}
+
+fn fill_order_ix(
+    pool: &TestPool,
+    filler: &Pubkey,
+    owner: &Pubkey,
+    id: u64,
+    mint_in: &Pubkey,
+    owner_dst: &Pubkey
+) -> Instruction {
+    let order = order_pda(pool, owner, id);
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*filler, true),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new(get_associated_token_address(&order, mint_in), false),
+            AccountMeta::new(*owner_dst, false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new_readonly(token::ID, false)
+        ],
+        data: FillOrder {}.data(),
+    }
+}
+
+fn cancel_order_ix(pool: &TestPool, owner: &Pubkey, order_owner: &Pubkey, id: u64, mint_in: &Pubkey) -> Instruction {
+    let order = order_pda(pool, order_owner, id);
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(order, false),
+            AccountMeta::new(get_associated_token_address(&order, mint_in), false),
+            AccountMeta::new(get_associated_token_address(owner, mint_in), false),
+            AccountMeta::new_readonly(token::ID, false)
+        ],
+        data: CancelOrder {}.data(),
+    }
+}
+
+#[test]
+fn test_limit_order() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        97,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let provider_keypair = funded_keypair(&mut svm);
+    let provider = provider_keypair.pubkey();
+    pool.fund(&mut svm, &provider_keypair, 1_000_000_000, 1_000_000_000);
+    let ix = pool.deposit_ix(&provider, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&provider_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let owner_keypair = funded_keypair(&mut svm);
+    let owner = owner_keypair.pubkey();
+    let (owner_x, owner_y) = pool.fund(&mut svm, &owner_keypair, 10_000_000, 0);
+    let filler_keypair = funded_keypair(&mut svm);
+    let filler = filler_keypair.pubkey();
+    let (_, filler_y) = pool.fund(&mut svm, &filler_keypair, 0, 0);
+    let payer = funded_keypair(&mut svm);
+
+    // Sell 10 X for at least 10.1 Y, tipping 0.001 SOL
+    let ix = place_order_ix(&pool, &owner, 1, &pool.mint_x, 0, 10_100_000, 1_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_err(), "An empty order should fail");
+
+    let ix = place_order_ix(&pool, &owner, 1, &pool.mint_x, 10_000_000, 10_100_000, 1_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "PlaceOrder Instruction Transaction failed: {:?}", result);
+
+    let order = order_pda(&pool, &owner, 1);
+    let escrow = get_associated_token_address(&order, &pool.mint_x);
+    assert_eq!(token_balance(&svm, &owner_x), 0);
+    assert_eq!(token_balance(&svm, &escrow), 10_000_000);
+
+    // At 1:1 the pool pays ~9.87 Y, short of the limit
+    let ix = fill_order_ix(&pool, &filler, &owner, 1, &pool.mint_x, &owner_y);
+    let result = send_ixs(&mut svm, &[ix], &[&payer, &filler_keypair]);
+    assert!(result.is_err(), "Filling below the limit price should fail");
+
+    // Buying X pushes its price past the limit
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    pool.fund(&mut svm, &trader_keypair, 0, 100_000_000);
+    let ix = pool.swap_ix(&trader, false, 100_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    // The output has to go to the order owner
+    svm.expire_blockhash();
+    let ix = fill_order_ix(&pool, &filler, &owner, 1, &pool.mint_x, &filler_y);
+    let result = send_ixs(&mut svm, &[ix], &[&payer, &filler_keypair]);
+    assert!(result.is_err(), "Filling into the filler's account should fail");
+
+    let filler_lamports = svm.get_account(&filler).unwrap().lamports;
+    let ix = fill_order_ix(&pool, &filler, &owner, 1, &pool.mint_x, &owner_y);
+    let result = send_ixs(&mut svm, &[ix], &[&payer, &filler_keypair]);
+    assert!(result.is_ok(), "FillOrder Instruction Transaction failed: {:?}", result);
+
+    assert!(token_balance(&svm, &owner_y) >= 10_100_000);
+    assert_eq!(svm.get_account(&filler).unwrap().lamports, filler_lamports + 1_000_000);
+    for closed in [order, escrow] {
+        assert!(svm.get_account(&closed).map_or(true, |account| account.lamports == 0));
+    }
+
+    // A filled order is gone
+    svm.expire_blockhash();
+    let ix = fill_order_ix(&pool, &filler, &owner, 1, &pool.mint_x, &owner_y);
+    let result = send_ixs(&mut svm, &[ix], &[&payer, &filler_keypair]);
+    assert!(result.is_err(), "Filling an order twice should fail");
+}
+
+#[test]
+fn test_cancel_limit_order() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        98,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let provider_keypair = funded_keypair(&mut svm);
+    let provider = provider_keypair.pubkey();
+    pool.fund(&mut svm, &provider_keypair, 1_000_000_000, 1_000_000_000);
+    let ix = pool.deposit_ix(&provider, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&provider_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let owner_keypair = funded_keypair(&mut svm);
+    let owner = owner_keypair.pubkey();
+    let (owner_x, owner_y) = pool.fund(&mut svm, &owner_keypair, 0, 5_000_000);
+    let outsider_keypair = funded_keypair(&mut svm);
+    let outsider = outsider_keypair.pubkey();
+    pool.fund(&mut svm, &outsider_keypair, 0, 0);
+
+    // Sell 5 Y for at least 100 X, far from the pool price
+    let ix = place_order_ix(&pool, &owner, 7, &pool.mint_y, 5_000_000, 100_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "PlaceOrder Instruction Transaction failed: {:?}", result);
+    let order = order_pda(&pool, &owner, 7);
+    let escrow = get_associated_token_address(&order, &pool.mint_y);
+    assert_eq!(token_balance(&svm, &escrow), 5_000_000);
+
+    let ix = fill_order_ix(&pool, &outsider, &owner, 7, &pool.mint_y, &owner_x);
+    let result = send_ixs(&mut svm, &[ix], &[&outsider_keypair]);
+    assert!(result.is_err(), "Filling below the limit price should fail");
+
+    let ix = cancel_order_ix(&pool, &outsider, &owner, 7, &pool.mint_y);
+    let result = send_ixs(&mut svm, &[ix], &[&outsider_keypair]);
+    assert!(result.is_err(), "CancelOrder by non-owner should fail");
+
+    let ix = cancel_order_ix(&pool, &owner, &owner, 7, &pool.mint_y);
+    let result = send_ixs(&mut svm, &[ix], &[&owner_keypair]);
+    assert!(result.is_ok(), "CancelOrder Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &owner_y), 5_000_000);
+    for closed in [order, escrow] {
+        assert!(svm.get_account(&closed).map_or(true, |account| account.lamports == 0));
+    }
+}
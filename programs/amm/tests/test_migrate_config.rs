@@ -0,0 +1,322 @@
+#![allow(deprecated)]
+
+use anchor_lang::{ AnchorSerialize, Discriminator, InstructionData, Space };
+use solana_sdk::{
+    account::Account,
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    rent::Rent,
+    signer::Signer,
+    system_program,
+};
+
+use amm::instruction::MigrateConfig;
+use amm::{ Config, ConfigV0, ConfigV1, CurveParams, CurveType, PoolState, CONFIG_VERSION };
+
+mod helpers;
+use helpers::*;
+
+fn migrate_config_ix(pool: &TestPool, payer: &Pubkey, vault_x: &Pubkey, vault_y: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new_readonly(*vault_x, false),
+            AccountMeta::new_readonly(*vault_y, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: MigrateConfig {}.data(),
+    }
+}
+
+/// Config account data in the original layout, written field by field as Borsh would
+fn v0_config_data(config: &Config) -> Vec<u8> {
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&config.seed.to_le_bytes());
+    match config.authority {
+        Some(authority) => {
+            data.push(1);
+            data.extend_from_slice(authority.as_ref());
+        }
+        None => data.push(0),
+    }
+    data.extend_from_slice(config.mint_x.as_ref());
+    data.extend_from_slice(config.mint_y.as_ref());
+    data.extend_from_slice(&config.fee.to_le_bytes());
+    data.push(config.locked as u8);
+    data.push(config.config_bump);
+    data.push(config.lp_bump);
+    data.resize(ConfigV0::LEN, 0);
+    data
+}
+
+/// Config account data in the version 1 layout, with the swap state held in the config
+fn v1_config_data(config: &Config, state: &PoolState) -> Vec<u8> {
+    let v1 = ConfigV1 {
+        version: 1,
+        seed: config.seed,
+        authority: config.authority,
+        mint_x: config.mint_x,
+        mint_y: config.mint_y,
+        fee: config.fee,
+        locked: config.locked,
+        config_bump: config.config_bump,
+        lp_bump: config.lp_bump,
+        curve_type: config.curve_type,
+        initial_amp: config.initial_amp,
+        target_amp: config.target_amp,
+        ramp_start_ts: config.ramp_start_ts,
+        ramp_stop_ts: config.ramp_stop_ts,
+        weight_x: config.weight_x,
+        weight_y: config.weight_y,
+        dynamic_fee: config.dynamic_fee,
+        volatility_accumulator: state.volatility_accumulator,
+        volatility_reference: state.volatility_reference,
+        reference_price: state.reference_price,
+        last_swap_slot: state.last_swap_slot,
+        protocol_fee_share: config.protocol_fee_share,
+        protocol_fees_x: state.protocol_fees_x,
+        protocol_fees_y: state.protocol_fees_y,
+        reserve_x: state.reserve_x,
+        reserve_y: state.reserve_y,
+        max_price_impact_bps: config.max_price_impact_bps,
+        max_slot_price_move_bps: config.max_slot_price_move_bps,
+        slot_price_move_bps: state.slot_price_move_bps,
+        price_move_slot: state.price_move_slot,
+        oracle: config.oracle,
+        oracle_params: config.oracle_params,
+        volume_limits: config.volume_limits,
+        volume_window: state.volume_window,
+        access_mode: config.access_mode,
+        max_referral_fee_bps: config.max_referral_fee_bps,
+        pauser: config.pauser,
+        fee_manager: config.fee_manager,
+        timelock_seconds: config.timelock_seconds,
+        padding: [0; 128],
+    };
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    v1.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), ConfigV1::LEN);
+    data
+}
+
+#[test]
+fn test_migrate_config() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        93,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let authority = pool.authority.pubkey();
+
+    let trader = funded_keypair(&mut svm);
+    pool.fund(&mut svm, &pool.authority, 1_000_000_000, 1_000_000_000);
+    pool.fund(&mut svm, &trader, 1_000_000_000, 1_000_000_000);
+
+    let ix = pool.deposit_ix(&authority, 100_000_000, 100_000_000, 200_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // Rewind the pool to a version 0 account, as created before the layout was versioned
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.version, CONFIG_VERSION);
+    let data = v0_config_data(&config);
+    svm.set_account(pool.config, Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: pool.program_id,
+        executable: false,
+        rent_epoch: 0,
+    }).unwrap();
+    svm.set_account(pool.pool_state, Account::default()).unwrap();
+
+    let swap_ix = pool.swap_ix(&trader.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader]);
+    assert!(result.is_err(), "Swap against a v0 config should fail");
+
+    // Anyone may migrate, but only with the pool's own vaults
+    let ix = migrate_config_ix(&pool, &trader.pubkey(), &pool.vault_y, &pool.vault_x);
+    let result = send_ixs(&mut svm, &[ix], &[&trader]);
+    assert!(result.is_err(), "Migrate with swapped vaults should fail");
+
+    let ix = migrate_config_ix(&pool, &trader.pubkey(), &pool.vault_x, &pool.vault_y);
+    let result = send_ixs(&mut svm, &[ix], &[&trader]);
+    assert!(result.is_ok(), "MigrateConfig Instruction Transaction failed: {:?}", result);
+
+    let account = svm.get_account(&pool.config).unwrap();
+    assert_eq!(account.data.len(), 8 + Config::INIT_SPACE);
+    assert!(account.lamports >= Rent::default().minimum_balance(account.data.len()));
+
+    // The v0 fields carry over; the new pool state picks up the vault balances
+    let migrated = fetch_config(&svm, &pool.config);
+    assert_eq!(migrated.version, CONFIG_VERSION);
+    assert_eq!(migrated.seed, config.seed);
+    assert_eq!(migrated.authority, Some(authority));
+    assert_eq!(migrated.fee, 30);
+    assert_eq!(migrated.config_bump, config.config_bump);
+    assert_eq!(migrated.vault_x, pool.vault_x);
+    assert_eq!(migrated.vault_y, pool.vault_y);
+    assert_eq!(migrated.mint_lp, pool.mint_lp);
+    assert_eq!(migrated.decimals_x, config.decimals_x);
+    assert_eq!(migrated.decimals_y, config.decimals_y);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.config, pool.config);
+    assert_eq!(state.reserve_x, token_balance(&svm, &pool.vault_x));
+    assert_eq!(state.reserve_y, token_balance(&svm, &pool.vault_y));
+    assert_eq!(state.reserve_x, 100_000_000);
+    assert_eq!(state.reserve_y, 200_000_000);
+
+    svm.expire_blockhash();
+    let swap_ix = pool.swap_ix(&trader.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    // Current-layout accounts are left alone
+    svm.expire_blockhash();
+    let ix = migrate_config_ix(&pool, &authority, &pool.vault_x, &pool.vault_y);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "Migrating a current config should fail");
+}
+
+#[test]
+fn test_migrate_config_v1() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        59,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let authority = pool.authority.pubkey();
+
+    let trader = funded_keypair(&mut svm);
+    pool.fund(&mut svm, &pool.authority, 1_000_000_000, 1_000_000_000);
+    pool.fund(&mut svm, &trader, 1_000_000_000, 1_000_000_000);
+
+    let ix = pool.deposit_ix(&authority, 100_000_000, 100_000_000, 200_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+    let swap_ix = pool.swap_ix(&trader.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    // Rewind the pool to a version 1 account, which held its reserves and fees in the config
+    let config = fetch_config(&svm, &pool.config);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    let data = v1_config_data(&config, &state);
+    svm.set_account(pool.config, Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: pool.program_id,
+        executable: false,
+        rent_epoch: 0,
+    }).unwrap();
+    svm.set_account(pool.pool_state, Account::default()).unwrap();
+
+    let ix = migrate_config_ix(&pool, &authority, &pool.vault_x, &pool.vault_y);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "MigrateConfig Instruction Transaction failed: {:?}", result);
+
+    let account = svm.get_account(&pool.config).unwrap();
+    assert_eq!(account.data.len(), 8 + Config::INIT_SPACE);
+
+    // The config decodes in place and the swap state moves to the pool state unchanged
+    let migrated = fetch_config(&svm, &pool.config);
+    assert_eq!(migrated.version, CONFIG_VERSION);
+    assert_eq!(migrated.authority, Some(authority));
+    assert_eq!(migrated.fee, config.fee);
+    assert_eq!(migrated.protocol_fee_share, config.protocol_fee_share);
+    assert_eq!(migrated.timelock_seconds, config.timelock_seconds);
+    assert_eq!(migrated.vault_x, pool.vault_x);
+    assert_eq!(migrated.mint_lp, pool.mint_lp);
+    let migrated_state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(migrated_state.config, pool.config);
+    assert_eq!(migrated_state.reserve_x, state.reserve_x);
+    assert_eq!(migrated_state.reserve_y, state.reserve_y);
+    assert_eq!(migrated_state.protocol_fees_x, state.protocol_fees_x);
+    assert_eq!(migrated_state.protocol_fees_y, state.protocol_fees_y);
+    assert_eq!(migrated_state.reference_price, state.reference_price);
+    assert_eq!(migrated_state.last_swap_slot, state.last_swap_slot);
+
+    svm.expire_blockhash();
+    let swap_ix = pool.swap_ix(&trader.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+}
+
+#[test]
+fn test_migrate_config_without_authority() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        60,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let authority = pool.authority.pubkey();
+
+    let trader = funded_keypair(&mut svm);
+    pool.fund(&mut svm, &pool.authority, 1_000_000_000, 1_000_000_000);
+    pool.fund(&mut svm, &trader, 1_000_000_000, 1_000_000_000);
+
+    let ix = pool.deposit_ix(&authority, 100_000_000, 100_000_000, 200_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // Rewind the pool to a version 0 account created without an authority
+    let mut config = fetch_config(&svm, &pool.config);
+    config.authority = None;
+    let data = v0_config_data(&config);
+    svm.set_account(pool.config, Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: pool.program_id,
+        executable: false,
+        rent_epoch: 0,
+    }).unwrap();
+    svm.set_account(pool.pool_state, Account::default()).unwrap();
+
+    let ix = migrate_config_ix(&pool, &trader.pubkey(), &pool.vault_x, &pool.vault_y);
+    let result = send_ixs(&mut svm, &[ix], &[&trader]);
+    assert!(result.is_ok(), "MigrateConfig Instruction Transaction failed: {:?}", result);
+
+    let migrated = fetch_config(&svm, &pool.config);
+    assert_eq!(migrated.version, CONFIG_VERSION);
+    assert_eq!(migrated.authority, None);
+    assert_eq!(migrated.vault_x, pool.vault_x);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.reserve_x, 100_000_000);
+    assert_eq!(state.reserve_y, 200_000_000);
+
+    svm.expire_blockhash();
+    let swap_ix = pool.swap_ix(&trader.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+}
@@ -0,0 +1,321 @@
+// #![cfg(feature = "test-sbf")]
+#![allow(deprecated)]
+
+use amm::instruction as amm_ix;
+use anchor_lang::InstructionData;
+use anchor_spl::{ associated_token, token };
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+};
+use spl_associated_token_account_client::address::get_associated_token_address;
+
+mod helpers;
+use helpers::*;
+
+/// A multi-asset pool with the keys needed to drive it
+struct MultiTestPool {
+    program_id: Pubkey,
+    pool: Pubkey,
+    mint_lp: Pubkey,
+    mint_authorities: Vec<Keypair>,
+    mints: Vec<Pubkey>,
+    vaults: Vec<Pubkey>,
+}
+
+impl MultiTestPool {
+    fn new(svm: &mut LiteSVM, seed: u64, decimals: &[u8]) -> Self {
+        let program_id = amm::id();
+        let (pool, _) = Pubkey::find_program_address(
+            &[b"multi_pool".as_slice(), seed.to_le_bytes().as_ref()],
+            &program_id
+        );
+        let (mint_lp, _) = Pubkey::find_program_address(
+            &[b"multi_lp".as_slice(), pool.as_ref()],
+            &program_id
+        );
+
+        let mut mint_authorities = Vec::new();
+        let mut mints = Vec::new();
+        for decimals in decimals {
+            let (authority, mint, _, account) = build_token_mint_account(0, *decimals);
+            svm.set_account(mint, account).unwrap();
+            mint_authorities.push(authority);
+            mints.push(mint);
+        }
+        let vaults = mints
+            .iter()
+            .map(|mint| get_associated_token_address(&pool, mint))
+            .collect();
+
+        MultiTestPool { program_id, pool, mint_lp, mint_authorities, mints, vaults }
+    }
+
+    fn initialize_ix(&self, initializer: &Pubkey, seed: u64, fee: u16, amp: u64) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.mint_lp, false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ];
+        accounts.extend(self.mints.iter().map(|mint| AccountMeta::new_readonly(*mint, false)));
+        accounts.extend(self.vaults.iter().map(|vault| AccountMeta::new(*vault, false)));
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: (amm_ix::InitializeMultiPool {
+                seed,
+                fee,
+                authority: None,
+                amp,
+                lp_decimals: 6,
+            }).data(),
+        }
+    }
+
+    fn fund(&self, svm: &mut LiteSVM, user: &Keypair, amounts: &[u64]) -> Vec<Pubkey> {
+        self.mints
+            .iter()
+            .zip(&self.mint_authorities)
+            .zip(amounts)
+            .map(|((mint, authority), amount)| {
+                let (tx, ata) = create_mint_to_transaction(
+                    authority,
+                    mint,
+                    user,
+                    *amount,
+                    svm.latest_blockhash()
+                );
+                let result = svm.send_transaction(tx);
+                assert!(result.is_ok(), "Mint Transaction failed: {:?}", result);
+                ata
+            })
+            .collect()
+    }
+
+    /// Mints `amount` of token `i` straight into its vault, bypassing the pool
+    fn donate(&self, svm: &mut LiteSVM, i: usize, amount: u64) {
+        let payer = funded_keypair(svm);
+        let ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &self.mints[i],
+            &self.vaults[i],
+            &self.mint_authorities[i].pubkey(),
+            &[],
+            amount
+        ).unwrap();
+        let result = send_ixs(svm, &[ix], &[&payer, &self.mint_authorities[i]]);
+        assert!(result.is_ok(), "MintTo Transaction failed: {:?}", result);
+    }
+
+    fn user_token(&self, user: &Pubkey, i: usize) -> Pubkey {
+        get_associated_token_address(user, &self.mints[i])
+    }
+
+    fn user_lp(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.mint_lp)
+    }
+
+    fn vault_metas(&self) -> Vec<AccountMeta> {
+        self.vaults.iter().map(|vault| AccountMeta::new(*vault, false)).collect()
+    }
+
+    fn deposit_ix(&self, user: &Pubkey, amounts: Vec<u64>, min_lp: u64) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.mint_lp, false),
+            AccountMeta::new(self.user_lp(user), false),
+            AccountMeta::new_readonly(token::ID, false),
+            AccountMeta::new_readonly(associated_token::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ];
+        accounts.extend(self.vault_metas());
+        accounts.extend(
+            (0..self.mints.len()).map(|i| AccountMeta::new(self.user_token(user, i), false))
+        );
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: (amm_ix::DepositMulti { amounts, min_lp }).data(),
+        }
+    }
+
+    fn withdraw_accounts(&self, user: &Pubkey, tokens: &[usize]) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.mint_lp, false),
+            AccountMeta::new(self.user_lp(user), false),
+            AccountMeta::new_readonly(token::ID, false)
+        ];
+        accounts.extend(self.vault_metas());
+        accounts.extend(tokens.iter().map(|i| AccountMeta::new(self.user_token(user, *i), false)));
+        accounts
+    }
+
+    fn withdraw_ix(&self, user: &Pubkey, amount: u64, min_amounts: Vec<u64>) -> Instruction {
+        let tokens: Vec<usize> = (0..self.mints.len()).collect();
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.withdraw_accounts(user, &tokens),
+            data: (amm_ix::WithdrawMulti { amount, min_amounts }).data(),
+        }
+    }
+
+    fn withdraw_one_ix(&self, user: &Pubkey, amount: u64, index: u8, min_amount: u64) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.withdraw_accounts(user, &[index as usize]),
+            data: (amm_ix::WithdrawMultiOne { amount, index, min_amount }).data(),
+        }
+    }
+
+    fn swap_ix(&self, user: &Pubkey, i: u8, j: u8, amount_in: u64, min_amount_out: u64) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new_readonly(token::ID, false)
+        ];
+        accounts.extend(self.vault_metas());
+        accounts.push(AccountMeta::new(self.user_token(user, i as usize), false));
+        accounts.push(AccountMeta::new(self.user_token(user, j as usize), false));
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: (amm_ix::SwapMulti { i, j, amount_in, min_amount_out }).data(),
+        }
+    }
+}
+
+#[test]
+fn test_multi_pool() {
+    let mut svm = setup_svm();
+    let pool = MultiTestPool::new(&mut svm, 40, &[6, 6, 9]);
+
+    let initializer = funded_keypair(&mut svm);
+    let ix = pool.initialize_ix(&initializer.pubkey(), 40, 4, 100); // 0.04%, A = 100
+    let result = send_ixs(&mut svm, &[ix], &[&initializer]);
+    assert!(result.is_ok(), "Initialize Multi Pool Transaction failed: {:?}", result);
+
+    let lp_keypair = funded_keypair(&mut svm);
+    let lp = lp_keypair.pubkey();
+    let lp_tokens = pool.fund(
+        &mut svm,
+        &lp_keypair,
+        &[2_000_000_000, 2_000_000_000, 2_000_000_000_000]
+    );
+
+    // The first deposit must seed every token
+    let ix = pool.deposit_ix(&lp, vec![1_000_000_000, 0, 1_000_000_000_000], 0);
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_err(), "Unbalanced first deposit should fail");
+
+    // 1 000 of each token, normalised to 9 decimals, gives D = 3 000 and 3 000 LP tokens
+    let ix = pool.deposit_ix(&lp, vec![1_000_000_000, 1_000_000_000, 1_000_000_000_000], 0);
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Deposit Multi Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&lp)), 3_000_000_000);
+    assert_eq!(token_balance(&svm, &pool.vaults[2]), 1_000_000_000_000);
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let trader_tokens = pool.fund(&mut svm, &trader_keypair, &[1_000_000, 0, 0]);
+
+    let ix = pool.swap_ix(&trader, 0, 0, 1_000_000, 0);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Swap into the same token should fail");
+
+    let ix = pool.swap_ix(&trader, 0, 2, 1_000_000, 999_598_891);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Swap below minimum output should fail");
+
+    let ix = pool.swap_ix(&trader, 0, 2, 1_000_000, 999_598_890);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Multi Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &trader_tokens[0]), 0);
+    assert_eq!(token_balance(&svm, &trader_tokens[2]), 999_598_890);
+
+    // Single-asset deposit pays the fee on its imbalance
+    let ix = pool.deposit_ix(&lp, vec![0, 10_000_000, 0], 9_998_628);
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Deposit Multi Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&lp)), 3_009_998_628);
+
+    let ix = pool.withdraw_ix(&lp, 1_000_000_000, vec![332_558_291, 335_548_325, 331_893_972_248]);
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Withdraw Multi Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &lp_tokens[0]), 1_000_000_000 + 332_558_291);
+    assert_eq!(token_balance(&svm, &lp_tokens[1]), 990_000_000 + 335_548_325);
+    assert_eq!(token_balance(&svm, &lp_tokens[2]), 1_000_000_000_000 + 331_893_972_248);
+
+    let ix = pool.withdraw_one_ix(&lp, 100_000_000, 1, 99_981_531);
+    let result = send_ixs(&mut svm, &[ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Withdraw Multi One Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &lp_tokens[1]), 990_000_000 + 335_548_325 + 99_981_531);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&lp)), 1_909_998_628);
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), 1_909_998_628);
+    assert_eq!(token_balance(&svm, &pool.vaults[0]), 668_441_709);
+    assert_eq!(token_balance(&svm, &pool.vaults[1]), 574_470_144);
+    assert_eq!(token_balance(&svm, &pool.vaults[2]), 667_106_428_862);
+}
+
+#[test]
+fn test_multi_pool_ignores_donations() {
+    let mut svm = setup_svm();
+    let pool = MultiTestPool::new(&mut svm, 61, &[6, 6, 9]);
+
+    let initializer = funded_keypair(&mut svm);
+    let ix = pool.initialize_ix(&initializer.pubkey(), 61, 4, 100);
+    let result = send_ixs(&mut svm, &[ix], &[&initializer]);
+    assert!(result.is_ok(), "Initialize Multi Pool Transaction failed: {:?}", result);
+
+    // A first depositor seeds dust, then donates heavily to inflate the LP token's value
+    let attacker_keypair = funded_keypair(&mut svm);
+    let attacker = attacker_keypair.pubkey();
+    pool.fund(&mut svm, &attacker_keypair, &[1, 1, 1_000]);
+    let ix = pool.deposit_ix(&attacker, vec![1, 1, 1_000], 0);
+    let result = send_ixs(&mut svm, &[ix], &[&attacker_keypair]);
+    assert!(result.is_ok(), "Deposit Multi Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&attacker)), 3);
+    pool.donate(&mut svm, 0, 1_000_000_000);
+    pool.donate(&mut svm, 1, 1_000_000_000);
+    pool.donate(&mut svm, 2, 1_000_000_000_000);
+
+    // The victim's LP is priced against the tracked reserves, not the vault balances
+    let victim_keypair = funded_keypair(&mut svm);
+    let victim = victim_keypair.pubkey();
+    let victim_tokens = pool.fund(
+        &mut svm,
+        &victim_keypair,
+        &[1_000_000_000, 1_000_000_000, 1_000_000_000_000]
+    );
+    let ix = pool.deposit_ix(&victim, vec![1_000_000_000, 1_000_000_000, 1_000_000_000_000], 0);
+    let result = send_ixs(&mut svm, &[ix], &[&victim_keypair]);
+    assert!(result.is_ok(), "Deposit Multi Transaction failed: {:?}", result);
+    let victim_lp = token_balance(&svm, &pool.user_lp(&victim));
+    assert!(victim_lp >= 2_999_000_000, "Victim minted only {} LP", victim_lp);
+
+    let ix = pool.withdraw_ix(&victim, victim_lp, vec![0, 0, 0]);
+    let result = send_ixs(&mut svm, &[ix], &[&victim_keypair]);
+    assert!(result.is_ok(), "Withdraw Multi Transaction failed: {:?}", result);
+    assert!(token_balance(&svm, &victim_tokens[0]) >= 999_000_000);
+    assert!(token_balance(&svm, &victim_tokens[2]) >= 999_000_000_000);
+}
+
+#[test]
+fn test_multi_pool_requires_three_tokens() {
+    let mut svm = setup_svm();
+    let pool = MultiTestPool::new(&mut svm, 41, &[6, 6]);
+
+    let initializer = funded_keypair(&mut svm);
+    let ix = pool.initialize_ix(&initializer.pubkey(), 41, 4, 100);
+    let result = send_ixs(&mut svm, &[ix], &[&initializer]);
+    assert!(result.is_err(), "Two-token multi pool should fail");
+}
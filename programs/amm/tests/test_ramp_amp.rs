@@ -0,0 +1,119 @@
+// #![cfg(feature = "test-sbf")]
+#![allow(deprecated)]
+
+use amm::{ instruction::{ RampAmp, StopRampAmp }, CurveParams, CurveType };
+use anchor_lang::InstructionData;
+use solana_sdk::{
+    clock::Clock,
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+
+mod helpers;
+use helpers::*;
+
+fn ramp_amp_ix(pool: &TestPool, authority: &Pubkey, target_amp: u64, stop_ts: i64) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (RampAmp { target_amp, stop_ts }).data(),
+    }
+}
+
+fn stop_ramp_amp_ix(pool: &TestPool, authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (StopRampAmp {}).data(),
+    }
+}
+
+#[test]
+fn test_ramp_amp() {
+    let mut svm = setup_svm();
+    set_unix_timestamp(&mut svm, 1_000_000);
+
+    let pool = TestPool::create(
+        &mut svm,
+        1,
+        4, // 0.04%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let authority = pool.authority.pubkey();
+
+    // Only the pool authority may ramp
+    let intruder = funded_keypair(&mut svm);
+    let ix = ramp_amp_ix(&pool, &intruder.pubkey(), 200, 1_000_000 + 2 * 86_400);
+    let result = send_ixs(&mut svm, &[ix], &[&intruder]);
+    assert!(result.is_err(), "Ramp by non-authority should fail");
+
+    // Ramps shorter than a day are rejected
+    let ix = ramp_amp_ix(&pool, &authority, 200, 1_000_000 + 3_600);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "Ramp shorter than a day should fail");
+
+    // More than a 10x move is rejected
+    let ix = ramp_amp_ix(&pool, &authority, 1_001, 1_000_000 + 2 * 86_400);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "Ramp beyond 10x should fail");
+
+    let ix = ramp_amp_ix(&pool, &authority, 300, 1_000_000 + 2 * 86_400);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Ramp Instruction Transaction failed: {:?}", result);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.initial_amp, 100);
+    assert_eq!(config.target_amp, 300);
+    assert_eq!(config.ramp_start_ts, 1_000_000);
+    assert_eq!(config.ramp_stop_ts, 1_000_000 + 2 * 86_400);
+    assert_eq!(config.current_amp(1_000_000 + 86_400), 200);
+
+    // Halfway through, stopping freezes the amplification where it is
+    set_unix_timestamp(&mut svm, 1_000_000 + 86_400);
+    let ix = stop_ramp_amp_ix(&pool, &authority);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Stop Ramp Instruction Transaction failed: {:?}", result);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.initial_amp, 200);
+    assert_eq!(config.target_amp, 200);
+    assert_eq!(config.current_amp(1_000_000 + 10 * 86_400), 200);
+}
+
+#[test]
+fn test_ramp_amp_rejects_constant_product_pool() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        2,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let authority: &Keypair = &pool.authority;
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    let ix = ramp_amp_ix(&pool, &authority.pubkey(), 10, now + 2 * 86_400);
+    let result = send_ixs(&mut svm, &[ix], &[authority]);
+    assert!(result.is_err(), "Ramp on a constant-product pool should fail");
+}
@@ -0,0 +1,157 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signer::Signer,
+};
+
+use amm::instruction::{ SetCircuitBreaker, SetFee, SetLocked, SetRole };
+use amm::{ CurveParams, CurveType, Role };
+
+mod helpers;
+use helpers::*;
+
+fn set_role_ix(pool: &TestPool, authority: &Pubkey, role: Role, holder: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (SetRole { role, holder }).data(),
+    }
+}
+
+fn set_fee_ix(pool: &TestPool, authority: &Pubkey, fee: u16) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (SetFee { fee }).data(),
+    }
+}
+
+fn config_ix(pool: &TestPool, authority: &Pubkey, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data,
+    }
+}
+
+#[test]
+fn test_roles() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        91,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let admin = pool.authority.pubkey();
+
+    let pauser = funded_keypair(&mut svm);
+    let fee_manager = funded_keypair(&mut svm);
+    let trader = funded_keypair(&mut svm);
+    pool.fund(&mut svm, &pool.authority, 1_000_000_000, 1_000_000_000);
+    pool.fund(&mut svm, &trader, 1_000_000_000, 1_000_000_000);
+
+    let ix = pool.deposit_ix(&admin, 100_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // Only the admin hands out roles
+    let ix = set_role_ix(&pool, &pauser.pubkey(), Role::Pauser, Some(pauser.pubkey()));
+    let result = send_ixs(&mut svm, &[ix], &[&pauser]);
+    assert!(result.is_err(), "SetRole by non-admin should fail");
+
+    let ixs = [
+        set_role_ix(&pool, &admin, Role::Pauser, Some(pauser.pubkey())),
+        set_role_ix(&pool, &admin, Role::FeeManager, Some(fee_manager.pubkey())),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&pool.authority]);
+    assert!(result.is_ok(), "SetRole Instruction Transaction failed: {:?}", result);
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.pauser, Some(pauser.pubkey()));
+    assert_eq!(config.fee_manager, Some(fee_manager.pubkey()));
+
+    // The fee manager moves between enabled tiers but holds no other power
+    let ix = set_fee_ix(&pool, &pauser.pubkey(), 100);
+    let result = send_ixs(&mut svm, &[ix], &[&pauser]);
+    assert!(result.is_err(), "SetFee by pauser should fail");
+    let ix = set_fee_ix(&pool, &fee_manager.pubkey(), 50);
+    let result = send_ixs(&mut svm, &[ix], &[&fee_manager]);
+    assert!(result.is_err(), "SetFee outside the fee tiers should fail");
+    let ix = set_fee_ix(&pool, &fee_manager.pubkey(), 100);
+    let result = send_ixs(&mut svm, &[ix], &[&fee_manager]);
+    assert!(result.is_ok(), "SetFee Instruction Transaction failed: {:?}", result);
+    assert_eq!(fetch_config(&svm, &pool.config).fee, 100);
+
+    let ix = config_ix(&pool, &fee_manager.pubkey(), (SetLocked { locked: true }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&fee_manager]);
+    assert!(result.is_err(), "SetLocked by fee manager should fail");
+    let ix = config_ix(
+        &pool,
+        &fee_manager.pubkey(),
+        (SetCircuitBreaker { max_price_impact_bps: 100, max_slot_price_move_bps: 0 }).data()
+    );
+    let result = send_ixs(&mut svm, &[ix], &[&fee_manager]);
+    assert!(result.is_err(), "SetCircuitBreaker by fee manager should fail");
+
+    // The pauser can lock the pool, but only the admin can unlock it
+    let ix = config_ix(&pool, &pauser.pubkey(), (SetLocked { locked: true }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pauser]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+
+    let swap_ix = pool.swap_ix(&trader.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader]);
+    assert!(result.is_err(), "Swap on a locked pool should fail");
+
+    let ix = config_ix(&pool, &pauser.pubkey(), (SetLocked { locked: false }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pauser]);
+    assert!(result.is_err(), "Unlock by pauser should fail");
+    let ix = config_ix(&pool, &admin, (SetLocked { locked: false }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+
+    svm.expire_blockhash();
+    let swap_ix = pool.swap_ix(&trader.pubkey(), true, 1_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    // Revoking a role takes effect immediately
+    let ix = set_role_ix(&pool, &admin, Role::Pauser, None);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetRole Instruction Transaction failed: {:?}", result);
+
+    svm.expire_blockhash();
+    let ix = config_ix(&pool, &pauser.pubkey(), (SetLocked { locked: true }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pauser]);
+    assert!(result.is_err(), "SetLocked by revoked pauser should fail");
+
+    // Renouncing the admin freezes every role
+    let ix = set_role_ix(&pool, &admin, Role::Admin, None);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetRole Instruction Transaction failed: {:?}", result);
+
+    let ix = set_fee_ix(&pool, &fee_manager.pubkey(), 30);
+    let result = send_ixs(&mut svm, &[ix], &[&fee_manager]);
+    assert!(result.is_err(), "SetFee after the admin renounced should fail");
+    assert_eq!(fetch_config(&svm, &pool.config).fee, 100);
+}
@@ -19,7 +19,19 @@ use solana_sdk::{
 use spl_token::{ state::{ Account as SPLTokenAccount, Mint as SPLMint } };
 
 use litesvm::LiteSVM;
-use amm::instruction::{ Deposit, Initialize, Swap };
+use amm::instruction::{
+    Deposit,
+    InitWalletVolume,
+    Initialize,
+    SetCircuitBreaker,
+    SetDynamicFee,
+    SetLocked,
+    SetOracle,
+    SetReferralFee,
+    SetVolumeLimits,
+    Swap,
+};
+use amm::{ CurveParams, CurveType, DynamicFeeParams, OracleParams, VolumeLimits };
 
 mod helpers;
 use helpers::*;
@@ -55,6 +67,13 @@ fn test_swap() {
         seed,
         fee: 1000, // 1%
         authority: Some(authority),
+        lp_decimals: 6,
+        curve: CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        },
     };
 
     let (config, _) = solana_sdk::pubkey::Pubkey::find_program_address(
@@ -69,6 +88,7 @@ fn test_swap() {
 
     let vault_x = get_associated_token_address(&config, &mint_x_pubkey);
     let vault_y = get_associated_token_address(&config, &mint_y_pubkey);
+    let fee_recipient = ensure_amm_config(&mut svm).fee_recipient;
 
     let ix = Instruction {
         program_id,
@@ -78,6 +98,11 @@ fn test_swap() {
             AccountMeta::new_readonly(mint_y_pubkey, false),
             AccountMeta::new(mint_lp, false),
             AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(fee_recipient, false),
+            AccountMeta::new(pool_index_pda(&mint_x_pubkey), false),
+            AccountMeta::new(pool_index_pda(&mint_y_pubkey), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new_readonly(token::ID, false),
@@ -136,7 +161,8 @@ fn test_swap() {
             AccountMeta::new_readonly(mint_x_pubkey, false),
             AccountMeta::new_readonly(mint_y_pubkey, false),
             AccountMeta::new(mint_lp, false),
-            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new(mint_user_x, false),
@@ -161,6 +187,7 @@ fn test_swap() {
         x_to_y: true,
         amount_in: 30 * LAMPORTS_PER_SOL,
         slippage: 5_000, // 50% slippage
+        referral_fee_bps: 0,
     };
 
     let ix = Instruction {
@@ -169,7 +196,8 @@ fn test_swap() {
             AccountMeta::new(depositer, true),
             AccountMeta::new_readonly(mint_x_pubkey, false),
             AccountMeta::new_readonly(mint_y_pubkey, false),
-            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new(pool_state_pda(&config), false),
             AccountMeta::new(vault_x, false),
             AccountMeta::new(vault_y, false),
             AccountMeta::new(mint_user_x, false),
@@ -226,3 +254,566 @@ fn test_swap() {
     assert_eq!(mint_lp_data.is_initialized, true);
     assert_eq!(mint_lp_data.supply, 1000);
 }
+
+#[test]
+fn test_swap_stable_swap_curve() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        42,
+        100, // 1%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (user_x, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // 10 X in, 9.9 X after the 1% fee; a constant-product pool would pay ~9.80 Y
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 100);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &user_x), 990_000_000);
+    assert_eq!(token_balance(&svm, &user_y), 1_009_899_512);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_010_000_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 990_100_488);
+}
+
+#[test]
+fn test_swap_weighted_curve() {
+    let mut svm = setup_svm();
+
+    // 80/20 pool without fees so the output is the pure curve quote
+    let pool = TestPool::create(
+        &mut svm,
+        43,
+        0,
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::Weighted,
+            amp: 0,
+            weight_x: 8_000,
+            weight_y: 2_000,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (user_x, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // 1e9 · (1 - (1e9 / 1.01e9)^4) ≈ 39.02 Y for 10 X, four times the 50/50 quote
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 0);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &user_x), 990_000_000);
+    assert_eq!(token_balance(&svm, &user_y), 1_039_019_655);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_010_000_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 960_980_345);
+}
+
+#[test]
+fn test_swap_dynamic_fee() {
+    let mut svm = setup_svm();
+
+    // 50/50 weighted pool with a 0.3% base fee
+    let pool = TestPool::create(
+        &mut svm,
+        44,
+        30,
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::Weighted,
+            amp: 0,
+            weight_x: 5_000,
+            weight_y: 5_000,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (_, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 1_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let set_dynamic_fee_ix = |authority: &solana_sdk::pubkey::Pubkey| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data: (SetDynamicFee {
+            params: DynamicFeeParams {
+                enabled: true,
+                max_fee: 1_000,
+                variable_fee_control: 10_000,
+                max_volatility_accumulator: 5_000,
+                filter_period: 10,
+                decay_period: 100,
+                reduction_factor: 5_000,
+            },
+        }).data(),
+    };
+
+    let intruder = funded_keypair(&mut svm);
+    let result = send_ixs(&mut svm, &[set_dynamic_fee_ix(&intruder.pubkey())], &[&intruder]);
+    assert!(result.is_err(), "Dynamic fee set by non-authority should fail");
+
+    let result = send_ixs(
+        &mut svm,
+        &[set_dynamic_fee_ix(&pool.authority.pubkey())],
+        &[&pool.authority]
+    );
+    assert!(result.is_ok(), "Set Dynamic Fee Transaction failed: {:?}", result);
+
+    let mut received = token_balance(&svm, &user_y);
+    let mut swap = |svm: &mut LiteSVM, slot: u64, amount_in: u64| {
+        svm.warp_to_slot(slot);
+        svm.expire_blockhash();
+        let swap_ix = pool.swap_ix(&trader, true, amount_in, 5_000);
+        let result = send_ixs(svm, &[swap_ix], &[&trader_keypair]);
+        assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+        let balance = token_balance(svm, &user_y);
+        let amount_out = balance - received;
+        received = balance;
+        amount_out
+    };
+
+    // A quiet pool charges the base fee; the 17% move it causes is remembered
+    assert_eq!(swap(&mut svm, 1_000, 100_000_000), 90_661_089);
+    assert_eq!(fetch_pool_state(&svm, &pool.pool_state).volatility_accumulator, 1_733);
+
+    // Trading on in the same slot pays 0.3% + 3%
+    assert_eq!(swap(&mut svm, 1_000, 100_000_000), 73_479_629);
+    assert_eq!(fetch_pool_state(&svm, &pool.pool_state).volatility_accumulator, 3_034);
+
+    // Between the filter and decay periods half of the volatility carries over
+    assert_eq!(swap(&mut svm, 1_050, 10_000_000), 6_729_767);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.volatility_reference, 1_517);
+    assert_eq!(state.volatility_accumulator, 1_679);
+
+    // After the decay period the fee is back to the base fee
+    assert_eq!(swap(&mut svm, 2_000, 10_000_000), 6_775_921);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.volatility_reference, 0);
+    assert_eq!(state.volatility_accumulator, 163);
+}
+
+#[test]
+fn test_swap_circuit_breaker() {
+    let mut svm = setup_svm();
+
+    // Fee-free 50/50 weighted pool, priced like constant product
+    let pool = TestPool::create(
+        &mut svm,
+        45,
+        0,
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::Weighted,
+            amp: 0,
+            weight_x: 5_000,
+            weight_y: 5_000,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let authority_ix = |authority: &solana_sdk::pubkey::Pubkey, data: Vec<u8>| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data,
+    };
+
+    // At most 5% per swap and 3% per slot
+    let ix = authority_ix(
+        &pool.authority.pubkey(),
+        (SetCircuitBreaker { max_price_impact_bps: 500, max_slot_price_move_bps: 300 }).data()
+    );
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Set Circuit Breaker Transaction failed: {:?}", result);
+
+    // 10% of the pool moves the price ~17%
+    let swap_ix = pool.swap_ix(&trader, true, 100_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Swap over the price impact limit should fail");
+
+    // Two ~2% moves in one slot trip the breaker; the second swap still settles
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+    assert_eq!(fetch_pool_state(&svm, &pool.pool_state).breaker_tripped, 0);
+
+    let swap_ix = pool.swap_ix(&trader, false, 10_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+    assert_eq!(fetch_pool_state(&svm, &pool.pool_state).breaker_tripped, 1);
+
+    svm.expire_blockhash();
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Swap on a locked pool should fail");
+
+    // Only the authority can reset the breaker
+    let ix = authority_ix(&trader, (SetLocked { locked: false }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "Unlock by non-authority should fail");
+
+    let ix = authority_ix(&pool.authority.pubkey(), (SetLocked { locked: false }).data());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Set Locked Transaction failed: {:?}", result);
+
+    svm.expire_blockhash();
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 5_000);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+    assert_eq!(fetch_pool_state(&svm, &pool.pool_state).breaker_tripped, 0);
+}
+
+#[test]
+fn test_swap_oracle_guard() {
+    let mut svm = setup_svm();
+    set_unix_timestamp(&mut svm, 1_000_000);
+
+    let pool = TestPool::create(
+        &mut svm,
+        46,
+        0,
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (_, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // X trades at 1.00 Y with a 0.01% confidence interval
+    let oracle = solana_sdk::pubkey::Pubkey::new_unique();
+    svm.set_account(oracle, build_pyth_price_account(100_000_000, 10_000, -8, 1_000_000, 1)).unwrap();
+
+    let set_oracle_ix = |max_confidence_bps: u16| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.authority.pubkey(), true),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (SetOracle {
+            oracle: Some(oracle),
+            params: OracleParams {
+                max_deviation_bps: 50,
+                max_staleness: 60,
+                max_confidence_bps,
+            },
+        }).data(),
+    };
+    // A zero confidence bound would refuse every price
+    let result = send_ixs(&mut svm, &[set_oracle_ix(0)], &[&pool.authority]);
+    assert!(result.is_err(), "Set Oracle without a confidence bound should fail");
+
+    let result = send_ixs(&mut svm, &[set_oracle_ix(10)], &[&pool.authority]);
+    assert!(result.is_ok(), "Set Oracle Transaction failed: {:?}", result);
+
+    let swap = |svm: &mut LiteSVM, x_to_y: bool, amount_in: u64, oracle: Option<solana_sdk::pubkey::Pubkey>| {
+        svm.expire_blockhash();
+        let mut swap_ix = pool.swap_ix(&trader, x_to_y, amount_in, 100);
+        if let Some(oracle) = oracle {
+            swap_ix.accounts.push(AccountMeta::new_readonly(oracle, false));
+        }
+        send_ixs(svm, &[swap_ix], &[&trader_keypair])
+    };
+
+    assert!(swap(&mut svm, true, 10_000_000, None).is_err(), "Swap without the oracle should fail");
+    let other_oracle = solana_sdk::pubkey::Pubkey::new_unique();
+    svm.set_account(other_oracle, build_pyth_price_account(100_000_000, 10_000, -8, 1_000_000, 1)).unwrap();
+    assert!(swap(&mut svm, true, 10_000_000, Some(other_oracle)).is_err(), "Swap with another oracle should fail");
+
+    let result = swap(&mut svm, true, 10_000_000, Some(oracle));
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &user_y), 1_009_999_502);
+
+    // Pushing the pool ~0.9% away from the oracle is rejected
+    assert!(swap(&mut svm, true, 500_000_000, Some(oracle)).is_err(), "Swap past the deviation should fail");
+
+    // Stale, wide or halted prices are refused
+    set_unix_timestamp(&mut svm, 1_000_061);
+    assert!(swap(&mut svm, true, 10_000_000, Some(oracle)).is_err(), "Swap on a stale price should fail");
+    svm.set_account(oracle, build_pyth_price_account(100_000_000, 200_000, -8, 1_000_061, 1)).unwrap();
+    assert!(swap(&mut svm, true, 10_000_000, Some(oracle)).is_err(), "Swap on a wide confidence should fail");
+    svm.set_account(oracle, build_pyth_price_account(100_000_000, 10_000, -8, 1_000_061, 0)).unwrap();
+    assert!(swap(&mut svm, true, 10_000_000, Some(oracle)).is_err(), "Swap on a halted price should fail");
+
+    // With the oracle at 0.97 the pool is ~3% off: swaps towards it pass, swaps away fail
+    svm.set_account(oracle, build_pyth_price_account(97_000_000, 10_000, -8, 1_000_061, 1)).unwrap();
+    assert!(swap(&mut svm, false, 100_000_000, Some(oracle)).is_err(), "Swap away from the oracle should fail");
+    let result = swap(&mut svm, true, 300_000_000, Some(oracle));
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &user_y), 1_309_472_639);
+}
+
+#[test]
+fn test_swap_volume_limits() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        47,
+        0,
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let lp_keypair = funded_keypair(&mut svm);
+    pool.fund(&mut svm, &lp_keypair, 1_000_000_000, 1_000_000_000);
+    let deposit_ix = pool.deposit_ix(&lp_keypair.pubkey(), 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&lp_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // 20 X per swap, 30 X per pool and 15 X per wallet every 10 slots
+    let set_limits_ix = |authority: &solana_sdk::pubkey::Pubkey| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data: (SetVolumeLimits {
+            limits: VolumeLimits {
+                max_swap_volume: 20_000_000,
+                max_window_volume: 30_000_000,
+                max_wallet_volume: 15_000_000,
+                window_slots: 10,
+            },
+        }).data(),
+    };
+    let intruder = funded_keypair(&mut svm);
+    let result = send_ixs(&mut svm, &[set_limits_ix(&intruder.pubkey())], &[&intruder]);
+    assert!(result.is_err(), "Volume limits set by non-authority should fail");
+    let result = send_ixs(&mut svm, &[set_limits_ix(&pool.authority.pubkey())], &[&pool.authority]);
+    assert!(result.is_ok(), "Set Volume Limits Transaction failed: {:?}", result);
+
+    let wallet_volume = |user: &solana_sdk::pubkey::Pubkey| {
+        solana_sdk::pubkey::Pubkey::find_program_address(
+            &[b"wallet_volume".as_slice(), pool.config.as_ref(), user.as_ref()],
+            &pool.program_id
+        ).0
+    };
+    let trader = |svm: &mut LiteSVM| {
+        let keypair = funded_keypair(svm);
+        pool.fund(svm, &keypair, 100_000_000, 100_000_000);
+        let ix = Instruction {
+            program_id: pool.program_id,
+            accounts: vec![
+                AccountMeta::new(keypair.pubkey(), true),
+                AccountMeta::new_readonly(pool.config, false),
+                AccountMeta::new(wallet_volume(&keypair.pubkey()), false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ],
+            data: InitWalletVolume {}.data(),
+        };
+        let result = send_ixs(svm, &[ix], &[&keypair]);
+        assert!(result.is_ok(), "Init Wallet Volume Transaction failed: {:?}", result);
+        keypair
+    };
+    let swap = |svm: &mut LiteSVM, trader: &Keypair, x_to_y: bool, amount_in: u64| {
+        svm.expire_blockhash();
+        let mut swap_ix = pool.swap_ix(&trader.pubkey(), x_to_y, amount_in, 100);
+        // No oracle, then the trader's volume account
+        swap_ix.accounts.push(AccountMeta::new_readonly(pool.program_id, false));
+        swap_ix.accounts.push(AccountMeta::new(wallet_volume(&trader.pubkey()), false));
+        send_ixs(svm, &[swap_ix], &[trader])
+    };
+
+    let alice = trader(&mut svm);
+    let bob = trader(&mut svm);
+    let carol = trader(&mut svm);
+
+    let swap_ix = pool.swap_ix(&alice.pubkey(), true, 10_000_000, 100);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&alice]);
+    assert!(result.is_err(), "Swap without the wallet volume account should fail");
+
+    assert!(swap(&mut svm, &alice, true, 25_000_000).is_err(), "Swap over the per-swap limit should fail");
+    assert!(swap(&mut svm, &alice, true, 10_000_000).is_ok());
+    // Selling Y counts the X paid out, taking Alice past 15 X
+    assert!(swap(&mut svm, &alice, false, 10_000_000).is_err(), "Swap over the wallet limit should fail");
+
+    assert!(swap(&mut svm, &bob, true, 15_000_000).is_ok());
+    assert!(swap(&mut svm, &carol, true, 10_000_000).is_err(), "Swap over the pool limit should fail");
+
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.volume_window.volume, 25_000_000);
+
+    // A new window frees the pool limit again
+    svm.warp_to_slot(100);
+    assert!(swap(&mut svm, &carol, true, 10_000_000).is_ok());
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.volume_window.start_slot, 100);
+    assert_eq!(state.volume_window.volume, 10_000_000);
+}
+
+#[test]
+fn test_swap_referral_fee() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        48,
+        100, // 1%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (user_x, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // Integrators may take up to half of the fee
+    let ix = Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.authority.pubkey(), true),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (SetReferralFee { max_referral_fee_bps: 5_000 }).data(),
+    };
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "Set Referral Fee Transaction failed: {:?}", result);
+
+    let integrator = funded_keypair(&mut svm);
+    let (referrer_x, referrer_y) = pool.fund(&mut svm, &integrator, 0, 0);
+
+    let referred_swap = |referrer: &solana_sdk::pubkey::Pubkey, referral_fee_bps: u16| {
+        let mut ix = pool.swap_ix(&trader, true, 10_000_000, 100);
+        ix.data = (Swap { x_to_y: true, amount_in: 10_000_000, slippage: 100, referral_fee_bps }).data();
+        // No oracle, wallet volume or allowlist accounts
+        for _ in 0..3 {
+            ix.accounts.push(AccountMeta::new_readonly(pool.program_id, false));
+        }
+        ix.accounts.push(AccountMeta::new(*referrer, false));
+        ix
+    };
+
+    let result = send_ixs(&mut svm, &[referred_swap(&referrer_x, 6_000)], &[&trader_keypair]);
+    assert!(result.is_err(), "Referral fee over the cap should fail");
+    let result = send_ixs(&mut svm, &[referred_swap(&referrer_y, 2_000)], &[&trader_keypair]);
+    assert!(result.is_err(), "Referrer of the output mint should fail");
+    let result = send_ixs(&mut svm, &[referred_swap(&user_x, 2_000)], &[&trader_keypair]);
+    assert!(result.is_err(), "Trader referring their own swap should fail");
+
+    // 20% of the 0.1 X fee goes to the integrator; the trader's quote is unchanged
+    let result = send_ixs(&mut svm, &[referred_swap(&referrer_x, 2_000)], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+    assert!(result.unwrap().logs.iter().any(|log| log.starts_with("Program data: ")));
+
+    assert_eq!(token_balance(&svm, &user_y), 1_009_899_512);
+    assert_eq!(token_balance(&svm, &referrer_x), 20_000);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_009_980_000);
+    assert_eq!(fetch_pool_state(&svm, &pool.pool_state).reserve_x, 1_009_980_000);
+}
+
+#[test]
+fn test_swap_lean() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        95,
+        100, // 1%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (user_x, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+    let other_keypair = funded_keypair(&mut svm);
+    let (_, other_y) = pool.fund(&mut svm, &other_keypair, 0, 0);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let config = fetch_config(&svm, &pool.config);
+    assert_eq!(config.vault_x, pool.vault_x);
+    assert_eq!(config.vault_y, pool.vault_y);
+
+    // Vaults must be the ones recorded on the config, in order
+    let mut ix = pool.swap_lean_ix(&trader, true, 10_000_000, 100);
+    ix.accounts.swap(3, 4);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "SwapLean with swapped vaults should fail");
+
+    // The user accounts must belong to the signer
+    let mut ix = pool.swap_lean_ix(&trader, true, 10_000_000, 100);
+    ix.accounts[6] = AccountMeta::new(other_y, false);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_err(), "SwapLean paying out to another wallet should fail");
+
+    // Same quote as `swap` on the StableSwap pool, using the decimals stored on the config
+    let swap_ix = pool.swap_lean_ix(&trader, true, 10_000_000, 100);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "SwapLean Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &user_x), 990_000_000);
+    assert_eq!(token_balance(&svm, &user_y), 1_009_899_512);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.reserve_x, 1_010_000_000);
+    assert_eq!(state.reserve_y, 990_100_488);
+}
@@ -0,0 +1,129 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use anchor_spl::token;
+use solana_sdk::{ instruction::{ AccountMeta, Instruction }, pubkey::Pubkey, signer::Signer };
+
+use amm::instruction::{ Skim, SyncReserves };
+use amm::{ CurveParams, CurveType };
+
+mod helpers;
+use helpers::*;
+
+fn sync_ix(pool: &TestPool) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new_readonly(pool.vault_x, false),
+            AccountMeta::new_readonly(pool.vault_y, false)
+        ],
+        data: SyncReserves {}.data(),
+    }
+}
+
+fn skim_ix(pool: &TestPool, recipient_x: &Pubkey, recipient_y: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool.mint_x, false),
+            AccountMeta::new_readonly(pool.mint_y, false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new_readonly(pool.pool_state, false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new(*recipient_x, false),
+            AccountMeta::new(*recipient_y, false),
+            AccountMeta::new_readonly(token::ID, false)
+        ],
+        data: Skim {}.data(),
+    }
+}
+
+fn donate(svm: &mut litesvm::LiteSVM, pool: &TestPool, is_x: bool, amount: u64) {
+    let (mint, vault, mint_authority) = match is_x {
+        true => (pool.mint_x, pool.vault_x, &pool.mint_x_authority),
+        false => (pool.mint_y, pool.vault_y, &pool.mint_y_authority),
+    };
+    let payer = funded_keypair(svm);
+    let ix = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &mint,
+        &vault,
+        &mint_authority.pubkey(),
+        &[],
+        amount
+    ).unwrap();
+    let result = send_ixs(svm, &[ix], &[&payer, mint_authority]);
+    assert!(result.is_ok(), "MintTo Transaction failed: {:?}", result);
+}
+
+#[test]
+fn test_donations_do_not_move_price() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        80,
+        100, // 1%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let trader_keypair = funded_keypair(&mut svm);
+    let trader = trader_keypair.pubkey();
+    let (_, user_y) = pool.fund(&mut svm, &trader_keypair, 2_000_000_000, 2_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&trader, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // Half the pool's X donated straight to the vault is ignored by the swap quote
+    donate(&mut svm, &pool, true, 500_000_000);
+
+    let swap_ix = pool.swap_ix(&trader, true, 10_000_000, 100);
+    let result = send_ixs(&mut svm, &[swap_ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Swap Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &user_y), 1_009_899_512);
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.reserve_x, 1_010_000_000);
+    assert_eq!(state.reserve_y, 990_100_488);
+
+    // Skim pays the donation out without touching the reserves
+    let recipient_keypair = funded_keypair(&mut svm);
+    let (recipient_x, recipient_y) = pool.fund(&mut svm, &recipient_keypair, 0, 0);
+
+    let ix = skim_ix(&pool, &recipient_x, &recipient_y);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Skim Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &recipient_x), 500_000_000);
+    assert_eq!(token_balance(&svm, &recipient_y), 0);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 1_010_000_000);
+
+    // Sync absorbs a donation into the reserves instead
+    donate(&mut svm, &pool, false, 1_000);
+
+    let ix = sync_ix(&pool);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "SyncReserves Instruction Transaction failed: {:?}", result);
+
+    let state = fetch_pool_state(&svm, &pool.pool_state);
+    assert_eq!(state.reserve_x, 1_010_000_000);
+    assert_eq!(state.reserve_y, 990_101_488);
+
+    svm.expire_blockhash();
+    let ix = skim_ix(&pool, &recipient_x, &recipient_y);
+    let result = send_ixs(&mut svm, &[ix], &[&trader_keypair]);
+    assert!(result.is_ok(), "Skim Instruction Transaction failed: {:?}", result);
+    assert_eq!(token_balance(&svm, &recipient_y), 0);
+}
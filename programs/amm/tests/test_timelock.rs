@@ -0,0 +1,220 @@
+#![allow(deprecated)]
+
+use anchor_lang::InstructionData;
+use solana_sdk::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    signer::Signer,
+    system_program,
+};
+
+use amm::instruction::{ CancelChange, ExecuteChange, ProposeChange, SetFee, SetLocked, SetRole };
+use amm::{ ConfigChange, CurveParams, CurveType, Role };
+
+mod helpers;
+use helpers::*;
+
+fn pending_change(pool: &TestPool) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"pending_change".as_slice(), pool.config.as_ref()],
+        &pool.program_id
+    ).0
+}
+
+fn propose_change_ix(pool: &TestPool, authority: &Pubkey, change: ConfigChange) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(pending_change(pool), false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ],
+        data: (ProposeChange { change }).data(),
+    }
+}
+
+fn execute_change_ix(pool: &TestPool, proposer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new(*proposer, false),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false),
+            AccountMeta::new(pending_change(pool), false)
+        ],
+        data: ExecuteChange {}.data(),
+    }
+}
+
+fn cancel_change_ix(pool: &TestPool, authority: &Pubkey, proposer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*proposer, false),
+            AccountMeta::new_readonly(pool.config, false),
+            AccountMeta::new(pending_change(pool), false)
+        ],
+        data: CancelChange {}.data(),
+    }
+}
+
+#[test]
+fn test_timelocked_changes() {
+    let mut svm = setup_svm();
+    set_unix_timestamp(&mut svm, 1_700_000_000);
+
+    let pool = TestPool::create(
+        &mut svm,
+        92,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let admin = pool.authority.pubkey();
+    let outsider = funded_keypair(&mut svm);
+
+    // Without a timelock a queued change is executable straight away, so the admin can turn
+    // the timelock on in one transaction
+    let ixs = [
+        propose_change_ix(&pool, &admin, ConfigChange::TimelockSeconds(3_600)),
+        execute_change_ix(&pool, &admin),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&pool.authority]);
+    assert!(result.is_ok(), "Timelock setup Transaction failed: {:?}", result);
+    assert_eq!(fetch_config(&svm, &pool.config).timelock_seconds, 3_600);
+
+    // Fee changes can no longer be made directly
+    let ix = Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new_readonly(amm_config_pda(), false),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (SetFee { fee: 100 }).data(),
+    };
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "SetFee under a timelock should fail");
+
+    // Invalid changes are rejected when proposed, not an hour later
+    let ix = propose_change_ix(&pool, &admin, ConfigChange::Fee(50));
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "Proposing a fee outside the tiers should fail");
+    let ix = propose_change_ix(&pool, &outsider.pubkey(), ConfigChange::Fee(100));
+    let result = send_ixs(&mut svm, &[ix], &[&outsider]);
+    assert!(result.is_err(), "Propose by non-authority should fail");
+
+    let ix = propose_change_ix(&pool, &admin, ConfigChange::Fee(100));
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "ProposeChange Instruction Transaction failed: {:?}", result);
+
+    let ix = execute_change_ix(&pool, &admin);
+    let result = send_ixs(&mut svm, &[ix], &[&outsider]);
+    assert!(result.is_err(), "Executing before the timelock should fail");
+    assert_eq!(fetch_config(&svm, &pool.config).fee, 30);
+
+    // The emergency lock is never delayed
+    let ix = Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.pool_state, false)
+        ],
+        data: (SetLocked { locked: true }).data(),
+    };
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetLocked Instruction Transaction failed: {:?}", result);
+    assert!(fetch_config(&svm, &pool.config).locked);
+
+    // Once the delay has passed anyone may execute, and the proposer gets the rent back
+    set_unix_timestamp(&mut svm, 1_700_003_600);
+    let ix = execute_change_ix(&pool, &admin);
+    let result = send_ixs(&mut svm, &[ix], &[&outsider]);
+    assert!(result.is_ok(), "ExecuteChange Instruction Transaction failed: {:?}", result);
+    assert_eq!(fetch_config(&svm, &pool.config).fee, 100);
+    assert!(svm.get_account(&pending_change(&pool)).map_or(true, |account| account.lamports == 0));
+
+    // A queued change can be cancelled, but not by just anyone
+    let ix = propose_change_ix(&pool, &admin, ConfigChange::Fee(1_000));
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "ProposeChange Instruction Transaction failed: {:?}", result);
+
+    let ix = cancel_change_ix(&pool, &outsider.pubkey(), &admin);
+    let result = send_ixs(&mut svm, &[ix], &[&outsider]);
+    assert!(result.is_err(), "Cancel by non-authority should fail");
+    let ix = cancel_change_ix(&pool, &admin, &admin);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "CancelChange Instruction Transaction failed: {:?}", result);
+
+    set_unix_timestamp(&mut svm, 1_700_007_200);
+    let ix = execute_change_ix(&pool, &admin);
+    let result = send_ixs(&mut svm, &[ix], &[&outsider]);
+    assert!(result.is_err(), "Executing a cancelled change should fail");
+    assert_eq!(fetch_config(&svm, &pool.config).fee, 100);
+}
+
+#[test]
+fn test_timelocked_change_needs_proposer_role() {
+    let mut svm = setup_svm();
+    set_unix_timestamp(&mut svm, 1_700_000_000);
+
+    let pool = TestPool::create(
+        &mut svm,
+        62,
+        30, // 0.3%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+    let admin = pool.authority.pubkey();
+    let fee_manager = funded_keypair(&mut svm);
+    let set_fee_manager_ix = |holder: Option<Pubkey>| Instruction {
+        program_id: pool.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(pool.config, false)
+        ],
+        data: (SetRole { role: Role::FeeManager, holder }).data(),
+    };
+
+    let ixs = [
+        set_fee_manager_ix(Some(fee_manager.pubkey())),
+        propose_change_ix(&pool, &admin, ConfigChange::TimelockSeconds(3_600)),
+        execute_change_ix(&pool, &admin),
+    ];
+    let result = send_ixs(&mut svm, &ixs, &[&pool.authority]);
+    assert!(result.is_ok(), "Timelock setup Transaction failed: {:?}", result);
+
+    let ix = propose_change_ix(&pool, &fee_manager.pubkey(), ConfigChange::Fee(100));
+    let result = send_ixs(&mut svm, &[ix], &[&fee_manager]);
+    assert!(result.is_ok(), "ProposeChange Instruction Transaction failed: {:?}", result);
+
+    // The fee manager is removed before the delay passes; their queued change dies with the role
+    let ix = set_fee_manager_ix(None);
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "SetRole Instruction Transaction failed: {:?}", result);
+
+    set_unix_timestamp(&mut svm, 1_700_003_600);
+    let ix = execute_change_ix(&pool, &fee_manager.pubkey());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_err(), "Executing a change from a removed fee manager should fail");
+    assert_eq!(fetch_config(&svm, &pool.config).fee, 30);
+
+    let ix = cancel_change_ix(&pool, &admin, &fee_manager.pubkey());
+    let result = send_ixs(&mut svm, &[ix], &[&pool.authority]);
+    assert!(result.is_ok(), "CancelChange Instruction Transaction failed: {:?}", result);
+}
@@ -0,0 +1,87 @@
+// #![cfg(feature = "test-sbf")]
+#![allow(deprecated)]
+
+use amm::{ CurveParams, CurveType };
+use solana_sdk::signer::Signer;
+
+mod helpers;
+use helpers::*;
+
+#[test]
+fn test_withdraw_liquidity() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        7,
+        100, // 1%
+        (6, 9, 6),
+        CurveParams {
+            curve_type: CurveType::ConstantProduct,
+            amp: 0,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let depositer_keypair = funded_keypair(&mut svm);
+    let depositer = depositer_keypair.pubkey();
+    let (user_x, user_y) = pool.fund(&mut svm, &depositer_keypair, 100_000_000, 100_000_000_000);
+
+    let deposit_ix = pool.deposit_ix(&depositer, 1_000_000, 100_000_000, 100_000_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&depositer_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    // Asking for more than the pro-rata share fails
+    let withdraw_ix = pool.withdraw_ix(&depositer, 250_000, 25_000_001, 25_000_000_000);
+    let result = send_ixs(&mut svm, &[withdraw_ix], &[&depositer_keypair]);
+    assert!(result.is_err(), "Withdraw with excessive minimum should fail");
+
+    let withdraw_ix = pool.withdraw_ix(&depositer, 250_000, 25_000_000, 25_000_000_000);
+    let result = send_ixs(&mut svm, &[withdraw_ix], &[&depositer_keypair]);
+    assert!(result.is_ok(), "Withdraw Instruction Transaction failed: {:?}", result);
+
+    assert_eq!(token_balance(&svm, &user_x), 25_000_000);
+    assert_eq!(token_balance(&svm, &user_y), 25_000_000_000);
+    assert_eq!(token_balance(&svm, &pool.user_lp(&depositer)), 750_000);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 75_000_000);
+    assert_eq!(token_balance(&svm, &pool.vault_y), 75_000_000_000);
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), 750_000);
+}
+
+#[test]
+fn test_withdraw_more_than_balance_fails() {
+    let mut svm = setup_svm();
+
+    let pool = TestPool::create(
+        &mut svm,
+        8,
+        100, // 1%
+        (6, 6, 6),
+        CurveParams {
+            curve_type: CurveType::StableSwap,
+            amp: 100,
+            weight_x: 0,
+            weight_y: 0,
+        }
+    );
+
+    let depositer_keypair = funded_keypair(&mut svm);
+    let depositer = depositer_keypair.pubkey();
+    pool.fund(&mut svm, &depositer_keypair, 10_000_000, 10_000_000);
+
+    let deposit_ix = pool.deposit_ix(&depositer, 1_000_000, 10_000_000, 10_000_000);
+    let result = send_ixs(&mut svm, &[deposit_ix], &[&depositer_keypair]);
+    assert!(result.is_ok(), "Deposit Instruction Transaction failed: {:?}", result);
+
+    let withdraw_ix = pool.withdraw_ix(&depositer, 1_000_001, 0, 0);
+    let result = send_ixs(&mut svm, &[withdraw_ix], &[&depositer_keypair]);
+    assert!(result.is_err(), "Withdraw above LP balance should fail");
+
+    // The whole position can be pulled back out
+    let withdraw_ix = pool.withdraw_ix(&depositer, 1_000_000, 10_000_000, 10_000_000);
+    let result = send_ixs(&mut svm, &[withdraw_ix], &[&depositer_keypair]);
+    assert!(result.is_ok(), "Withdraw Instruction Transaction failed: {:?}", result);
+    assert_eq!(mint_supply(&svm, &pool.mint_lp), 0);
+    assert_eq!(token_balance(&svm, &pool.vault_x), 0);
+}
@@ -118,7 +118,12 @@ async function initializeProgram() {
     // Initialize the AMM
     console.log("Sending initialize transaction...");
     const tx = await program.methods
-      .initialize(seed, fee, authority)
+      .initialize(seed, fee, authority, 6, {
+        curveType: { constantProduct: {} },
+        amp: new anchor.BN(0),
+        weightX: 0,
+        weightY: 0,
+      })
       .accountsPartial({
         config: configPda,
         mintX: mintX,
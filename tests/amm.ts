@@ -67,7 +67,12 @@ describe("AMM Initialize Tests", () => {
 
       // Execute initialize instruction
       const tx = await program.methods
-        .initialize(seed, fee, authority)
+        .initialize(seed, fee, authority, 6, {
+          curveType: { constantProduct: {} },
+          amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+        })
         .accountsPartial({
           initializer: wallet.publicKey,
           mintX: mintX,
@@ -144,7 +149,12 @@ describe("AMM Initialize Tests", () => {
       const vaultY = await getAssociatedTokenAddress(mintY, configPda, true);
 
       const tx = await program.methods
-        .initialize(seed, fee, authority)
+        .initialize(seed, fee, authority, 6, {
+          curveType: { constantProduct: {} },
+          amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+        })
         .accountsPartial({
           initializer: wallet.publicKey,
           mintX: mintX,
@@ -204,7 +214,12 @@ describe("AMM Initialize Tests", () => {
       const vaultY = await getAssociatedTokenAddress(mintY, configPda, true);
 
       const tx = await program.methods
-        .initialize(seed, fee, authority)
+        .initialize(seed, fee, authority, 6, {
+          curveType: { constantProduct: {} },
+          amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+        })
         .accountsPartial({
           initializer: wallet.publicKey,
           mintX: mintX,
@@ -266,7 +281,12 @@ describe("AMM Initialize Tests", () => {
 
       // First initialization should succeed
       await program.methods
-        .initialize(seed, fee, authority)
+        .initialize(seed, fee, authority, 6, {
+          curveType: { constantProduct: {} },
+          amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+        })
         .accountsPartial({
           initializer: wallet.publicKey,
           mintX: mintX,
@@ -284,7 +304,12 @@ describe("AMM Initialize Tests", () => {
       // Second initialization with same seed should fail
       try {
         await program.methods
-          .initialize(seed, fee, authority)
+          .initialize(seed, fee, authority, 6, {
+            curveType: { constantProduct: {} },
+            amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+          })
           .accountsPartial({
             initializer: wallet.publicKey,
             mintX: mintX,
@@ -337,7 +362,12 @@ describe("AMM Initialize Tests", () => {
 
       try {
         await program.methods
-          .initialize(seed, fee, authority)
+          .initialize(seed, fee, authority, 6, {
+            curveType: { constantProduct: {} },
+            amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+          })
           .accountsPartial({
             initializer: wallet.publicKey,
             mintX: mint, // Same mint
@@ -411,7 +441,12 @@ describe("AMM Initialize Tests", () => {
       // Test if 100% fee is allowed
       try {
         await program.methods
-          .initialize(seed, fee, authority)
+          .initialize(seed, fee, authority, 6, {
+            curveType: { constantProduct: {} },
+            amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+          })
           .accountsPartial({
             initializer: wallet.publicKey,
             mintX: mintX,
@@ -475,7 +510,12 @@ describe("AMM Initialize Tests", () => {
 
       try {
         await program.methods
-          .initialize(seed, fee, authority)
+          .initialize(seed, fee, authority, 6, {
+            curveType: { constantProduct: {} },
+            amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+          })
           .accountsPartial({
             initializer: wallet.publicKey,
             mintX: mintX,
@@ -545,7 +585,12 @@ describe("AMM Initialize Tests", () => {
       const vaultY = await getAssociatedTokenAddress(mintY, configPda, true);
 
       const tx = await program.methods
-        .initialize(seed, fee, authority)
+        .initialize(seed, fee, authority, 6, {
+          curveType: { constantProduct: {} },
+          amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+        })
         .accountsPartial({
           initializer: wallet.publicKey,
           mintX: mintX,
@@ -605,7 +650,12 @@ describe("AMM Initialize Tests", () => {
       const vaultY = await getAssociatedTokenAddress(mintY, configPda, true);
 
       const tx = await program.methods
-        .initialize(seed, fee, authority)
+        .initialize(seed, fee, authority, 6, {
+          curveType: { constantProduct: {} },
+          amp: new anchor.BN(0),
+          weightX: 0,
+          weightY: 0,
+        })
         .accountsPartial({
           initializer: wallet.publicKey,
           mintX: mintX,
@@ -693,7 +743,12 @@ describe("AMM Deposit Tests", () => {
 
     // Initialize the AMM
     await program.methods
-      .initialize(seed, fee, authority)
+      .initialize(seed, fee, authority, 6, {
+        curveType: { constantProduct: {} },
+        amp: new anchor.BN(0),
+        weightX: 0,
+        weightY: 0,
+      })
       .accountsPartial({
         initializer: wallet.publicKey,
         mintX: mintX,
@@ -983,7 +1038,12 @@ describe("AMM Swap Tests", () => {
 
     // Initialize the AMM
     await program.methods
-      .initialize(seed, fee, authority)
+      .initialize(seed, fee, authority, 6, {
+        curveType: { constantProduct: {} },
+        amp: new anchor.BN(0),
+        weightX: 0,
+        weightY: 0,
+      })
       .accountsPartial({
         initializer: wallet.publicKey,
         mintX: mintX,